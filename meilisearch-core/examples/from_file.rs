@@ -306,7 +306,7 @@ fn crop_text(
     let highlights = highlights
         .take_while(|m| (m.char_index as usize) + (m.char_length as usize) <= start + (context * 2))
         .map(|highlight| Highlight {
-            char_index: highlight.char_index - start as u16,
+            char_index: highlight.char_index - start as u32,
             ..highlight
         })
         .collect();
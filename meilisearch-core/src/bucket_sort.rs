@@ -1,5 +1,6 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::ops::Deref;
 use std::ops::Range;
@@ -7,19 +8,23 @@ use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 use std::fmt;
+use std::hash::Hash;
 
 use compact_arena::{SmallArena, Idx32, mk_arena};
+use indexmap::IndexMap;
 use log::{debug, error};
-use sdset::{Set, SetBuf, exponential_search, SetOperation, Counter, duo::OpBuilder};
+use sdset::{Set, SetBuf, exponential_search, SetOperation, duo::OpBuilder};
 use slice_group_by::{GroupBy, GroupByMut};
 
+use meilisearch_schema::FieldId;
 use meilisearch_types::DocIndex;
 
-use crate::criterion::{Criteria, Context, ContextMut};
-use crate::distinct_map::{BufferedDistinctMap, DistinctMap};
+use crate::criterion::{Criteria, Context, ContextMut, CriterionValue, SearchAfter};
+use crate::facets::{sort_facet_values, FacetDistributionOptions, FacetKey, FacetRange, FacetStats};
+use crate::distinct_map::{BufferedDistinctMap, DistinctMap, GroupsRange};
 use crate::raw_document::RawDocument;
 use crate::{database::MainT, reordered_attrs::ReorderedAttrs};
-use crate::{store, Document, DocumentId, MResult, Index, RankedMap, MainReader, Error};
+use crate::{store, Document, DocumentId, MResult, Index, Number, RankedMap, MainReader, Error};
 use crate::query_tree::{create_query_tree, traverse_query_tree};
 use crate::query_tree::{Operation, QueryResult, QueryKind, QueryId, PostingsKey};
 use crate::query_tree::Context as QTContext;
@@ -29,8 +34,30 @@ pub struct SortResult {
     pub documents: Vec<Document>,
     pub nb_hits: usize,
     pub exhaustive_nb_hit: bool,
-    pub facets: Option<HashMap<String, HashMap<String, usize>>>,
+    pub facets: Option<HashMap<String, IndexMap<String, usize>>>,
+    pub facets_stats: Option<HashMap<String, FacetStats>>,
+    /// The values of the hierarchical facets counted by level, the first level first.
+    pub hierarchical_facets: Option<HashMap<String, Vec<IndexMap<String, usize>>>>,
     pub exhaustive_facets_count: Option<bool>,
+    /// The number of hits sharing the distinct value of each returned document.
+    pub group_sizes: Option<HashMap<DocumentId, usize>>,
+    /// The number of groups of hits sharing a distinct value, when the hits are grouped.
+    pub nb_groups: Option<usize>,
+    /// The cursor of the last returned document when the requested range is full,
+    /// the next hits are the ones ranked after it.
+    pub search_after: Option<SearchAfter>,
+}
+
+/// The documents in which each value of the facets to count appears, along with
+/// the ranges of numbers to count instead of the values for some of these facets
+/// and the way the counted values must be returned.
+pub struct FacetCountDocids<'a> {
+    pub values: HashMap<String, HashMap<FacetKey, (&'a str, Cow<'a, Set<DocumentId>>)>>,
+    pub ranges: HashMap<String, Vec<FacetRange>>,
+    pub options: FacetDistributionOptions,
+    /// The disjunctive facets, counted without their own facet filters, associated with
+    /// the documents allowed by the other filters, `None` when every document is allowed.
+    pub disjunctive: HashMap<String, Option<SetBuf<DocumentId>>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -39,8 +66,10 @@ pub fn bucket_sort<'c, FI>(
     query: &str,
     range: Range<usize>,
     facets_docids: Option<SetBuf<DocumentId>>,
-    facet_count_docids: Option<HashMap<String, HashMap<String, (&str, Cow<Set<DocumentId>>)>>>,
+    facet_count_docids: Option<FacetCountDocids>,
     filter: Option<FI>,
+    exhaustive_hits_count: Option<usize>,
+    search_after: Option<SearchAfter>,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     index: &Index,
@@ -62,6 +91,10 @@ where
             filter,
             distinct,
             distinct_size,
+            exhaustive_hits_count,
+            search_after,
+            false,
+            false,
             criteria,
             searchable_attrs,
             index,
@@ -100,6 +133,9 @@ where
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
+    // the disjunctive facets are counted on the documents matching the query without the facet filters
+    let query_docids = facet_count_docids.as_ref().filter(|f| !f.disjunctive.is_empty()).map(|_| docids.clone());
+
     if let Some(facets_docids) = facets_docids {
         let intersection = sdset::duo::OpBuilder::new(docids.as_ref(), facets_docids.as_set())
             .intersection()
@@ -110,7 +146,11 @@ where
     if let Some(f) = facet_count_docids {
         // hardcoded value, until approximation optimization
         result.exhaustive_facets_count = Some(true);
-        result.facets = Some(facet_count(f, &docids));
+        let query_docids = query_docids.as_deref().unwrap_or(&docids);
+        let (facets, hierarchical_facets, facets_stats) = facet_count(f, &docids, query_docids);
+        result.facets = Some(facets);
+        result.hierarchical_facets = Some(hierarchical_facets);
+        result.facets_stats = Some(facets_stats);
     }
 
     let before = Instant::now();
@@ -131,6 +171,11 @@ where
         before_raw_documents_building.elapsed(),
     );
 
+    if let Some(search_after) = search_after {
+        let (_, after) = split_search_after(reader, raw_documents, &search_after, &criteria, &mut arena, &mapping, index)?;
+        raw_documents = after;
+    }
+
     let before_criterion_loop = Instant::now();
     let proximity_count = AtomicUsize::new(0);
 
@@ -180,6 +225,11 @@ where
     debug!("criterion loop took {:.02?}", before_criterion_loop.elapsed());
     debug!("proximity evaluation called {} times", proximity_count.load(Ordering::Relaxed));
 
+    if !range.is_empty() && raw_documents.len() >= range.end {
+        let ctx = Context { postings_lists: &arena, query_mapping: &mapping };
+        result.search_after = Some(search_after_cursor(&criteria, &ctx, &raw_documents[range.end - 1]));
+    }
+
     let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
     let iter = raw_documents.into_iter().skip(range.start).take(range.len());
     let iter = iter.map(|rd| Document::from_raw(rd, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
@@ -188,7 +238,9 @@ where
     debug!("bucket sort took {:.02?}", before_bucket_sort.elapsed());
 
     result.documents = documents;
+    // without filter nor distinct rule all the documents matching the query are hits
     result.nb_hits = docids.len();
+    result.exhaustive_nb_hit = exhaustive_hits_count.is_some();
 
     Ok(result)
 }
@@ -199,10 +251,14 @@ pub fn bucket_sort_with_distinct<'c, FI, FD>(
     query: &str,
     range: Range<usize>,
     facets_docids: Option<SetBuf<DocumentId>>,
-    facet_count_docids: Option<HashMap<String, HashMap<String, (&str, Cow<Set<DocumentId>>)>>>,
+    facet_count_docids: Option<FacetCountDocids>,
     filter: Option<FI>,
     distinct: FD,
     distinct_size: usize,
+    exhaustive_hits_count: Option<usize>,
+    search_after: Option<SearchAfter>,
+    group_sizes: bool,
+    group_hits: bool,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     index: &Index,
@@ -243,6 +299,9 @@ where
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
+    // the disjunctive facets are counted on the documents matching the query without the facet filters
+    let query_docids = facet_count_docids.as_ref().filter(|f| !f.disjunctive.is_empty()).map(|_| docids.clone());
+
     if let Some(facets_docids) = facets_docids {
         let intersection = OpBuilder::new(docids.as_ref(), facets_docids.as_set())
             .intersection()
@@ -253,7 +312,11 @@ where
     if let Some(f) = facet_count_docids {
         // hardcoded value, until approximation optimization
         result.exhaustive_facets_count = Some(true);
-        result.facets = Some(facet_count(f, &docids));
+        let query_docids = query_docids.as_deref().unwrap_or(&docids);
+        let (facets, hierarchical_facets, facets_stats) = facet_count(f, &docids, query_docids);
+        result.facets = Some(facets);
+        result.hierarchical_facets = Some(hierarchical_facets);
+        result.facets_stats = Some(facets_stats);
     }
 
     let before = Instant::now();
@@ -272,7 +335,6 @@ where
         before_raw_documents_building.elapsed(),
     );
 
-    let mut groups = vec![raw_documents.as_mut_slice()];
     let mut key_cache = HashMap::new();
 
     let mut filter_map = HashMap::new();
@@ -282,9 +344,35 @@ where
     let mut distinct_map = DistinctMap::new(distinct_size);
     let mut distinct_raw_offset = 0;
 
+    // the distinct keys of the hits ranked before the search after cursor are already
+    // seen, the requested range is then shifted by the number of these hits
+    let mut range = range;
+    if let Some(search_after) = search_after {
+        let (before, after) = split_search_after(reader, raw_documents, &search_after, &criteria, &mut arena, &mapping, index)?;
+        let mut buf_distinct = BufferedDistinctMap::new(&mut distinct_map);
+        for document in before {
+            if let Some(key) = (distinct)(document.id) {
+                if filter.as_ref().map_or(true, |filter| (filter)(document.id)) {
+                    buf_distinct.register(Rc::new(key));
+                }
+            }
+        }
+        buf_distinct.transfert_to_internal();
+        let seen = distinct_map.len();
+        range = range.start + seen..range.end + seen;
+        raw_documents = after;
+    }
+
+    let mut groups = vec![raw_documents.as_mut_slice()];
+
+    // when the hits are grouped the requested range is a range of groups of hits sharing a distinct
+    // value, the documents are sorted until these groups contain all their hits kept by the distinct rule
+    let new_range_groups = || if group_hits { Some(GroupsRange::new(range.clone(), distinct_size)) } else { None };
+
     'criteria: for criterion in criteria.as_ref() {
         let tmp_groups = mem::replace(&mut groups, Vec::new());
         let mut buf_distinct = BufferedDistinctMap::new(&mut distinct_map);
+        let mut range_groups = new_range_groups();
         let mut documents_seen = 0;
 
         for mut group in tmp_groups {
@@ -331,14 +419,20 @@ where
                         let entry = key_cache.entry(document.id);
                         let key = entry.or_insert_with(|| (distinct)(document.id).map(Rc::new));
 
-                        match key.clone() {
+                        let groups_len = buf_distinct.groups_len();
+                        let distinct_accepted = match key.clone() {
                             Some(key) => buf_distinct.register(key),
                             None => buf_distinct.register_without_key(),
                         };
+
+                        if let (Some(range_groups), true) = (&mut range_groups, distinct_accepted) {
+                            let new_group_rank = Some(groups_len).filter(|_| buf_distinct.groups_len() > groups_len);
+                            range_groups.register(key.clone(), new_group_rank);
+                        }
                     }
 
                     // the requested range end is reached: stop computing distinct
-                    if buf_distinct.len() >= range.end {
+                    if range_end_reached(&buf_distinct, range_groups.as_ref(), &range) {
                         break;
                     }
                 }
@@ -348,14 +442,15 @@ where
 
                 // if this sub-group does not overlap with the requested range
                 // we must update the distinct map and its start index
-                if buf_distinct.len() < range.start {
+                let seen_len = if group_hits { buf_distinct.groups_len() } else { buf_distinct.len() };
+                if seen_len < range.start {
                     buf_distinct.transfert_to_internal();
                     distinct_raw_offset = documents_seen;
                 }
 
                 // we have sort enough documents if the last document sorted is after
                 // the end of the requested range, we can continue to the next criterion
-                if buf_distinct.len() >= range.end {
+                if range_end_reached(&buf_distinct, range_groups.as_ref(), &range) {
                     continue 'criteria;
                 }
             }
@@ -365,10 +460,21 @@ where
     // once we classified the documents related to the current
     // automatons we save that as the next valid result
     let mut seen = BufferedDistinctMap::new(&mut distinct_map);
+    let mut range_groups = new_range_groups();
     let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
 
-    let mut documents = Vec::with_capacity(range.len());
+    let mut documents = Vec::new();
     for raw_document in raw_documents.into_iter().skip(distinct_raw_offset) {
+        // stop before registering the next document once the range is full,
+        // an empty range, like a zero limit, must not return any document
+        let range_full = match &range_groups {
+            Some(range_groups) => range_groups.is_complete(seen.groups_len()),
+            None => documents.len() == range.len(),
+        };
+        if range_full {
+            break;
+        }
+
         let filter_accepted = match &filter {
             Some(_) => filter_map.remove(&raw_document.id).unwrap_or_else(|| {
                 error!("error during filtering: expected value for document id {}", &raw_document.id.0);
@@ -382,25 +488,214 @@ where
                 error!("error during distinct: expected value for document id {}", &raw_document.id.0);
                 Default::default()
             });
-            let distinct_accepted = match key {
+            let groups_len = seen.groups_len();
+            let distinct_accepted = match key.clone() {
                 Some(key) => seen.register(key),
                 None => seen.register_without_key(),
             };
 
-            if distinct_accepted && seen.len() > range.start {
-                documents.push(Document::from_raw(raw_document, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
-                if documents.len() == range.len() {
-                    break;
+            let in_range = match &mut range_groups {
+                Some(range_groups) => {
+                    let new_group_rank = Some(groups_len).filter(|_| seen.groups_len() > groups_len);
+                    distinct_accepted && range_groups.register(key, new_group_rank)
                 }
+                None => distinct_accepted && seen.len() > range.start,
+            };
+
+            if in_range {
+                if !group_hits && documents.len() + 1 == range.len() {
+                    let ctx = Context { postings_lists: &arena, query_mapping: &mapping };
+                    result.search_after = Some(search_after_cursor(&criteria, &ctx, &raw_document));
+                }
+                documents.push(Document::from_raw(raw_document, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
             }
         }
     }
+    let filter = filter.as_ref().map(|f| f as &dyn Fn(DocumentId) -> bool);
+    if group_sizes {
+        result.group_sizes = Some(self::group_sizes(&docids, &documents, filter, &distinct));
+    }
+    if group_hits {
+        result.nb_groups = Some(count_groups(&docids, filter, &distinct));
+    }
+
     result.documents = documents;
     result.nb_hits = docids.len();
 
+    if let Some(cap) = exhaustive_hits_count {
+        let distinct = Some((&distinct as &dyn Fn(DocumentId) -> Option<u64>, distinct_size));
+        let (nb_hits, exhaustive) = count_hits(&docids, filter, distinct, cap);
+        result.nb_hits = nb_hits;
+        result.exhaustive_nb_hit = exhaustive;
+    }
+
     Ok(result)
 }
 
+/// Whether the documents registered in the distinct map reach the end of the
+/// requested range, which is a range of groups when the groups are given.
+fn range_end_reached<K: Hash + Eq>(
+    seen: &BufferedDistinctMap<K>,
+    range_groups: Option<&GroupsRange<K>>,
+    range: &Range<usize>,
+) -> bool
+{
+    match range_groups {
+        Some(range_groups) => range_groups.is_complete(seen.groups_len()),
+        None => seen.len() >= range.end,
+    }
+}
+
+/// Counts the documents accepted by the filter and the distinct rule, stops counting once the
+/// cap is reached, returns the number of hits and whether it is the exact number of hits.
+pub fn count_hits(
+    docids: &[DocumentId],
+    filter: Option<&dyn Fn(DocumentId) -> bool>,
+    distinct: Option<(&dyn Fn(DocumentId) -> Option<u64>, usize)>,
+    cap: usize,
+) -> (usize, bool) {
+    // all the documents are hits, there is nothing to count
+    if filter.is_none() && distinct.is_none() {
+        return (docids.len(), true);
+    }
+
+    let mut distinct_map = DistinctMap::new(distinct.map_or(1, |(_, size)| size));
+    let mut distinct_map = BufferedDistinctMap::new(&mut distinct_map);
+    let mut nb_hits = 0;
+    for &id in docids {
+        if !filter.map_or(true, |filter| filter(id)) {
+            continue;
+        }
+
+        let distinct_accepted = match distinct.map(|(distinct, _)| distinct(id)) {
+            Some(Some(key)) => distinct_map.register(key),
+            _ => distinct_map.register_without_key(),
+        };
+
+        if distinct_accepted {
+            if nb_hits == cap {
+                return (nb_hits, false);
+            }
+            nb_hits += 1;
+        }
+    }
+    (nb_hits, true)
+}
+
+/// Counts, for each of the documents, the hits accepted by the filter which share its distinct value,
+/// the documents without distinct value are not part of a group and are not counted.
+pub fn group_sizes(
+    docids: &[DocumentId],
+    documents: &[Document],
+    filter: Option<&dyn Fn(DocumentId) -> bool>,
+    distinct: &dyn Fn(DocumentId) -> Option<u64>,
+) -> HashMap<DocumentId, usize> {
+    let keys: HashMap<_, _> = documents.iter()
+        .filter_map(|document| distinct(document.id).map(|key| (document.id, key)))
+        .collect();
+
+    let mut sizes: HashMap<_, _> = keys.values().map(|key| (*key, 0)).collect();
+    if !sizes.is_empty() {
+        for &id in docids {
+            if let Some(size) = distinct(id).and_then(|key| sizes.get_mut(&key)) {
+                if filter.map_or(true, |filter| filter(id)) {
+                    *size += 1;
+                }
+            }
+        }
+    }
+
+    keys.into_iter().map(|(id, key)| (id, sizes[&key])).collect()
+}
+
+/// Counts the groups of the hits accepted by the filter which share a distinct value,
+/// each document without distinct value is a group by itself.
+pub fn count_groups(
+    docids: &[DocumentId],
+    filter: Option<&dyn Fn(DocumentId) -> bool>,
+    distinct: &dyn Fn(DocumentId) -> Option<u64>,
+) -> usize {
+    let mut keys = HashSet::new();
+    let mut without_key = 0;
+    for &id in docids {
+        if filter.map_or(true, |filter| filter(id)) {
+            match distinct(id) {
+                Some(key) => { keys.insert(key); },
+                None => without_key += 1,
+            }
+        }
+    }
+    keys.len() + without_key
+}
+
+/// Splits the documents between the ones ranked before the hit of the search after cursor,
+/// the documents ranked like it included, and the ones ranked after it. A criterion is only
+/// prepared on the documents that the previous criteria ranked like the hit of the cursor.
+#[allow(clippy::type_complexity)]
+fn split_search_after<'a, 'tag, 'txn>(
+    reader: &heed::RoTxn<MainT>,
+    raw_documents: Vec<RawDocument<'a, 'tag>>,
+    search_after: &SearchAfter,
+    criteria: &Criteria,
+    arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
+    mapping: &HashMap<QueryId, Range<usize>>,
+    index: &Index,
+) -> MResult<(Vec<RawDocument<'a, 'tag>>, Vec<RawDocument<'a, 'tag>>)>
+{
+    if search_after.values().len() != criteria.as_ref().len() {
+        return Err(Error::InvalidSearchAfter);
+    }
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut tied = raw_documents;
+
+    for (criterion, cursor_value) in criteria.as_ref().iter().zip(search_after.values()) {
+        if tied.is_empty() {
+            break;
+        }
+
+        let ctx = ContextMut {
+            reader,
+            postings_lists: &mut *arena,
+            query_mapping: mapping,
+            documents_fields_counts_store: index.documents_fields_counts,
+        };
+        criterion.prepare(ctx, &mut tied)?;
+
+        let ctx = Context {
+            postings_lists: &*arena,
+            query_mapping: mapping,
+        };
+
+        let mut still_tied = Vec::new();
+        for raw_document in tied {
+            match criterion.value(&ctx, &raw_document).rank_cmp(cursor_value) {
+                Some(cmp::Ordering::Less) => before.push(raw_document),
+                Some(cmp::Ordering::Equal) => still_tied.push(raw_document),
+                Some(cmp::Ordering::Greater) => after.push(raw_document),
+                None => return Err(Error::InvalidSearchAfter),
+            }
+        }
+        tied = still_tied;
+    }
+
+    // the documents ranked like the hit of the cursor, this hit included if it still matches
+    before.extend(tied);
+
+    Ok((before, after))
+}
+
+/// The cursor of a document, made of the values that the criteria give to it.
+fn search_after_cursor<'tag>(
+    criteria: &Criteria,
+    ctx: &Context<'_, 'tag, '_, '_>,
+    document: &RawDocument<'_, 'tag>,
+) -> SearchAfter
+{
+    SearchAfter::new(criteria.as_ref().iter().map(|criterion| criterion.value(ctx, document)).collect())
+}
+
 fn cleanup_bare_matches<'tag, 'txn>(
     arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
     docids: &Set<DocumentId>,
@@ -447,7 +742,7 @@ fn cleanup_bare_matches<'tag, 'txn>(
                 let group = postings_list_view[offset..]
                     .linear_group_by_key(|m| m.document_id)
                     .next()
-                    .filter(|matches| matches[0].document_id == *id);
+                    .filter(|matches| matches[0].document_id.0 == id.0);
 
                 if let Some(matches) = group {
                     let range = postings_list_view.range(offset, matches.len());
@@ -497,8 +792,8 @@ impl fmt::Debug for BareMatch<'_> {
 pub struct SimpleMatch {
     pub query_index: usize,
     pub distance: u8,
-    pub attribute: u16,
-    pub word_index: u16,
+    pub attribute: u32,
+    pub word_index: u32,
     pub is_exact: bool,
 }
 
@@ -601,61 +896,202 @@ pub fn placeholder_document_sort(
     reader: &MainReader,
     ranked_map: &RankedMap
 ) -> MResult<()> {
-    use crate::settings::RankingRule;
     use std::cmp::Ordering;
 
-    enum SortOrder {
-        Asc,
-        Desc,
-    }
-
-    if let Some(ranking_rules) = index.main.ranking_rules(reader)? {
-        let schema = index.main.schema(reader)?
-            .ok_or(Error::SchemaMissing)?;
-
-        // Select custom rules from ranking rules, and map them to custom rules
-        // containing a field_id
-        let ranking_rules = ranking_rules.iter().filter_map(|r|
-            match r {
-                RankingRule::Asc(name) => schema.id(name).map(|f| (f, SortOrder::Asc)),
-                RankingRule::Desc(name) => schema.id(name).map(|f| (f, SortOrder::Desc)),
-                _ => None,
-            }).collect::<Vec<_>>();
-
+    if let Some(ranking_rules) = placeholder_ranking_rules(index, reader)? {
         document_ids.sort_unstable_by(|a, b| {
-            for (field_id, order) in &ranking_rules {
+            for (field_id, reversed) in &ranking_rules {
                 let a_value = ranked_map.get(*a, *field_id);
                 let b_value = ranked_map.get(*b, *field_id);
-                let (a, b) = match order {
-                    SortOrder::Asc => (a_value, b_value),
-                    SortOrder::Desc => (b_value, a_value),
-                };
+                let (a, b) = if *reversed { (b_value, a_value) } else { (a_value, b_value) };
                 match a.cmp(&b) {
                     Ordering::Equal => continue,
                     ordering => return ordering,
                 }
             }
-            Ordering::Equal
+            // the document ids break the ties, to sort them the same way at each search
+            a.cmp(b)
         });
     }
     Ok(())
 }
 
-/// For each entry in facet_docids, calculates the number of documents in the intersection with candidate_docids.
+/// Selects the custom rules of the ranking rules, by which the placeholder search sorts the
+/// documents, with their field and whether the documents with the higher values come first.
+pub fn placeholder_ranking_rules(index: &store::Index, reader: &MainReader) -> MResult<Option<Vec<(FieldId, bool)>>> {
+    use crate::settings::RankingRule;
+
+    match index.main.ranking_rules(reader)? {
+        Some(ranking_rules) => {
+            let schema = index.main.schema(reader)?
+                .ok_or(Error::SchemaMissing)?;
+
+            let ranking_rules = ranking_rules.iter().filter_map(|r|
+                match r {
+                    RankingRule::Asc(name) => schema.id(name).map(|f| (f, false)),
+                    RankingRule::Desc(name) => schema.id(name).map(|f| (f, true)),
+                    _ => None,
+                }).collect();
+
+            Ok(Some(ranking_rules))
+        }
+        None => Ok(None),
+    }
+}
+
+/// The values by which the placeholder search ranks the documents,
+/// the values of the custom rules followed by the document id.
+pub fn placeholder_document_values(
+    document_id: DocumentId,
+    ranking_rules: &[(FieldId, bool)],
+    ranked_map: &RankedMap,
+) -> Vec<CriterionValue> {
+    let mut values: Vec<_> = ranking_rules.iter().map(|(field_id, reversed)| {
+        let value = ranked_map.get(document_id, *field_id);
+        if *reversed { CriterionValue::Descending(value) } else { CriterionValue::Ascending(value) }
+    }).collect();
+    values.push(CriterionValue::Ascending(Some(Number::Unsigned(u64::from(document_id.0)))));
+    values
+}
+
+/// Whether the values of a search after cursor are values that the placeholder search gives with
+/// these ranking rules, a value in the direction of each rule followed by the document id.
+pub fn placeholder_cursor_matches(values: &[CriterionValue], ranking_rules: &[(FieldId, bool)]) -> bool {
+    match values.split_last() {
+        Some((CriterionValue::Ascending(Some(_)), values)) => {
+            values.len() == ranking_rules.len()
+                && values.iter().zip(ranking_rules).all(|(value, (_, reversed))| {
+                    matches!(value, CriterionValue::Descending(_)) == *reversed
+                })
+        },
+        _ => false,
+    }
+}
+
+/// Compares the ranks that the placeholder search gives to documents with these values, the
+/// documents without value come first in ascending order, `None` if the values do not match.
+pub fn placeholder_rank_cmp(lhs: &[CriterionValue], rhs: &[CriterionValue]) -> Option<cmp::Ordering> {
+    if lhs.len() != rhs.len() {
+        return None;
+    }
+
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        let ordering = match (lhs, rhs) {
+            (CriterionValue::Ascending(a), CriterionValue::Ascending(b)) => a.cmp(b),
+            (CriterionValue::Descending(a), CriterionValue::Descending(b)) => b.cmp(a),
+            _ => return None,
+        };
+        if ordering != cmp::Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(cmp::Ordering::Equal)
+}
+
+/// For each entry in facet_docids, calculates the number of documents in the intersection with candidate_docids,
+/// the documents are counted by range for the facets with ranges, and the numeric facets get their min and max.
+/// The values of the hierarchical facets are paths which are counted by level, apart from the other facets.
+/// The disjunctive facets are counted on the `query_docids` allowed by the filters other than their own.
 pub fn facet_count(
-    facet_docids: HashMap<String, HashMap<String, (&str, Cow<Set<DocumentId>>)>>,
+    facet_docids: FacetCountDocids,
     candidate_docids: &Set<DocumentId>,
-) -> HashMap<String, HashMap<String, usize>> {
-    let mut facets_counts = HashMap::with_capacity(facet_docids.len());
-    for (key, doc_map) in facet_docids {
-        let mut count_map = HashMap::with_capacity(doc_map.len());
-        for (_, (value, docids)) in doc_map {
-            let mut counter = Counter::new();
-            let op = OpBuilder::new(docids.as_ref(), candidate_docids).intersection();
-            SetOperation::<DocumentId>::extend_collection(op, &mut counter);
-            count_map.insert(value.to_string(), counter.0);
+    query_docids: &Set<DocumentId>,
+) -> (HashMap<String, IndexMap<String, usize>>, HashMap<String, Vec<IndexMap<String, usize>>>, HashMap<String, FacetStats>) {
+    let FacetCountDocids { values, ranges, options, disjunctive } = facet_docids;
+    let mut facets_counts = HashMap::with_capacity(values.len());
+    let mut hierarchical_counts = HashMap::new();
+    let mut facets_stats = HashMap::new();
+    for (key, doc_map) in values {
+        let counted_docids = match disjunctive.get(&key) {
+            Some(Some(allowed)) => Cow::Owned(OpBuilder::new(query_docids, allowed.as_set()).intersection().into_set_buf()),
+            Some(None) => Cow::Borrowed(query_docids),
+            None => Cow::Borrowed(candidate_docids),
+        };
+
+        let ranges = ranges.get(&key).map(Vec::as_slice).unwrap_or_default();
+        let separator = options.hierarchies.get(&key).filter(|_| ranges.is_empty());
+        let mut ranges_docids = vec![Vec::new(); ranges.len()];
+        let mut levels_docids: Vec<HashMap<String, Vec<DocumentId>>> = Vec::new();
+        let mut stats: Option<FacetStats> = None;
+
+        let mut values_docids: HashMap<String, Vec<DocumentId>> = HashMap::with_capacity(doc_map.len());
+        for (facet_key, (value, docids)) in doc_map {
+            let op = OpBuilder::new(docids.as_ref(), counted_docids.as_ref()).intersection();
+            // only the numbers are counted in ranges and get stats, not the strings looking like numbers
+            let number = facet_key.as_number();
+
+            let count = match (number, separator) {
+                (Some(number), _) if !ranges.is_empty() => {
+                    let matching = op.into_set_buf();
+                    for (range, range_docids) in ranges.iter().zip(&mut ranges_docids) {
+                        if range.contains(number) {
+                            range_docids.extend_from_slice(&matching);
+                        }
+                    }
+                    matching.len()
+                },
+                (_, Some(separator)) => {
+                    let matching = op.into_set_buf();
+                    let mut path = String::new();
+                    for (level, part) in value.split(separator.as_str()).map(str::trim).enumerate() {
+                        if level != 0 {
+                            path.push_str(separator);
+                        }
+                        path.push_str(part);
+                        if levels_docids.len() == level {
+                            levels_docids.push(HashMap::new());
+                        }
+                        levels_docids[level].entry(path.clone()).or_default().extend_from_slice(&matching);
+                    }
+                    matching.len()
+                },
+                _ => {
+                    // a number and a string with the same representation are the same value,
+                    // a document containing both must only be counted once
+                    let matching = op.into_set_buf();
+                    values_docids.entry(value.to_string()).or_default().extend_from_slice(&matching);
+                    matching.len()
+                },
+            };
+
+            if let Some(number) = number.filter(|_| count != 0) {
+                let stats = stats.get_or_insert(FacetStats { min: number, max: number });
+                stats.min = stats.min.min(number);
+                stats.max = stats.max.max(number);
+            }
+        }
+
+        if let Some(stats) = stats {
+            facets_stats.insert(key.clone(), stats);
+        }
+
+        if !ranges.is_empty() {
+            // the facets with ranges only return the number of documents in each range
+            let count_map = ranges
+                .iter()
+                .zip(ranges_docids)
+                .map(|(range, docids)| (range.name.clone(), SetBuf::from_dirty(docids).len()))
+                .collect();
+            facets_counts.insert(key, count_map);
+        } else if separator.is_some() {
+            let levels = levels_docids
+                .into_iter()
+                .map(|docids_map| {
+                    let count_map = docids_map
+                        .into_iter()
+                        .map(|(path, docids)| (path, SetBuf::from_dirty(docids).len()))
+                        .collect();
+                    sort_facet_values(count_map, &options)
+                })
+                .collect();
+            hierarchical_counts.insert(key, levels);
+        } else {
+            let count_map = values_docids
+                .into_iter()
+                .map(|(value, docids)| (value, SetBuf::from_dirty(docids).len()))
+                .collect();
+            facets_counts.insert(key, sort_facet_values(count_map, &options));
         }
-        facets_counts.insert(key, count_map);
     }
-    facets_counts
+    (facets_counts, hierarchical_counts, facets_stats)
 }
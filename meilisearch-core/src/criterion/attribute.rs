@@ -1,8 +1,8 @@
 use std::cmp::Ordering;
 use slice_group_by::GroupBy;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use crate::bucket_sort::SimpleMatch;
-use super::{Criterion, Context, ContextMut, prepare_bare_matches};
+use super::{Criterion, CriterionValue, Context, ContextMut, prepare_bare_matches};
 
 pub struct Attribute;
 
@@ -20,18 +20,23 @@ impl Criterion for Attribute {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn sum_of_attribute(matches: &[SimpleMatch]) -> usize {
-            let mut sum_of_attribute = 0;
-            for group in matches.linear_group_by_key(|bm| bm.query_index) {
-                sum_of_attribute += group[0].attribute as usize;
-            }
-            sum_of_attribute
-        }
-
         let lhs = sum_of_attribute(&lhs.processed_matches);
         let rhs = sum_of_attribute(&rhs.processed_matches);
 
         lhs.cmp(&rhs)
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> CriterionValue {
+        let sum = sum_of_attribute(&document.processed_matches);
+        CriterionValue::Ascending(Some(Number::Unsigned(sum as u64)))
+    }
+}
+
+#[inline]
+fn sum_of_attribute(matches: &[SimpleMatch]) -> usize {
+    let mut sum_of_attribute = 0;
+    for group in matches.linear_group_by_key(|bm| bm.query_index) {
+        sum_of_attribute += group[0].attribute as usize;
+    }
+    sum_of_attribute
 }
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
-use crate::RawDocument;
-use super::{Criterion, Context};
+use crate::{Number, RawDocument};
+use super::{Criterion, CriterionValue, Context};
 
 pub struct DocumentId;
 
@@ -13,4 +13,8 @@ impl Criterion for DocumentId {
 
         lhs.cmp(rhs)
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> CriterionValue {
+        CriterionValue::Ascending(Some(Number::Unsigned(u64::from(document.id.0))))
+    }
 }
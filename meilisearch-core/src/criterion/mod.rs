@@ -4,12 +4,13 @@ use std::ops::Range;
 
 use compact_arena::SmallArena;
 use sdset::SetBuf;
+use serde::{Deserialize, Serialize};
 use slice_group_by::GroupBy;
 
 use crate::bucket_sort::{SimpleMatch, PostingsListView};
 use crate::database::MainT;
 use crate::query_tree::QueryId;
-use crate::{store, RawDocument, MResult};
+use crate::{store, Error, Number, RawDocument, MResult};
 
 mod typo;
 mod words;
@@ -48,6 +49,14 @@ pub trait Criterion {
         rhs: &RawDocument<'r, 'tag>,
     ) -> Ordering;
 
+    /// The value this criterion gives to the document, the documents are ranked
+    /// in the order of these values, like the evaluate function ranks them.
+    fn value<'p, 'tag, 'txn, 'q, 'r>(
+        &self,
+        ctx: &Context<'p, 'tag, 'txn, 'q>,
+        document: &RawDocument<'r, 'tag>,
+    ) -> CriterionValue;
+
     #[inline]
     fn eq<'p, 'tag, 'txn, 'q, 'r>(
         &self,
@@ -60,6 +69,71 @@ pub trait Criterion {
     }
 }
 
+/// The value a criterion gives to a document, the documents
+/// without value are ranked after the other ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CriterionValue {
+    /// The documents with the lower values are ranked first.
+    Ascending(Option<Number>),
+    /// The documents with the higher values are ranked first.
+    Descending(Option<Number>),
+}
+
+impl CriterionValue {
+    /// Compares the ranks of the documents with these values,
+    /// returns `None` if the values do not have the same order.
+    pub fn rank_cmp(&self, other: &CriterionValue) -> Option<Ordering> {
+        use CriterionValue::{Ascending, Descending};
+
+        match (self, other) {
+            (Ascending(Some(a)), Ascending(Some(b))) => Some(a.cmp(b)),
+            (Descending(Some(a)), Descending(Some(b))) => Some(b.cmp(a)),
+            (Ascending(a), Ascending(b)) | (Descending(a), Descending(b)) => {
+                Some(a.is_none().cmp(&b.is_none()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The version of the encoding of the search after cursors, a cursor encoded
+/// with another version is refused instead of being read wrongly.
+const SEARCH_AFTER_VERSION: u8 = 1;
+
+/// A cursor on a hit, made of the values the criteria gave to it, a search after this
+/// cursor returns the hits ranked after this hit. The cursor is encoded but not encrypted,
+/// the values can be read back and include the internal id of the hit that breaks the ties.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchAfter {
+    values: Vec<CriterionValue>,
+}
+
+impl SearchAfter {
+    pub fn new(values: Vec<CriterionValue>) -> SearchAfter {
+        SearchAfter { values }
+    }
+
+    pub fn values(&self) -> &[CriterionValue] {
+        &self.values
+    }
+
+    /// Encodes the cursor in a string that can be given back to a search.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![SEARCH_AFTER_VERSION];
+        bincode::serialize_into(&mut bytes, self).expect("a search after cursor is always serializable");
+        base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Decodes a cursor returned by the encode function of the same version.
+    pub fn decode(cursor: &str) -> MResult<SearchAfter> {
+        let bytes = base64::decode_config(cursor, base64::URL_SAFE_NO_PAD).map_err(|_| Error::InvalidSearchAfter)?;
+        match bytes.split_first() {
+            Some((&SEARCH_AFTER_VERSION, bytes)) => bincode::deserialize(bytes).map_err(|_| Error::InvalidSearchAfter),
+            _ => Err(Error::InvalidSearchAfter),
+        }
+    }
+}
+
 pub struct ContextMut<'h, 'p, 'tag, 'txn, 'q> {
     pub reader: &'h heed::RoTxn<MainT>,
     pub postings_lists: &'p mut SmallArena<'tag, PostingsListView<'txn>>,
@@ -224,7 +298,7 @@ fn multiword_rewrite_matches(
                 let nexts = iter.remainder().linear_group_by_key(|m| m.word_index);
 
                 if let Some(query_index) = replacement.next() {
-                    let word_index = match_.word_index + padding as u16;
+                    let word_index = match_.word_index + padding as u32;
                     let match_ = SimpleMatch { query_index, word_index, ..*match_ };
                     padded_matches.push(match_);
                 }
@@ -235,7 +309,7 @@ fn multiword_rewrite_matches(
                 // corresponding to this padding word, abort the padding
                 'padding: for (x, next_group) in nexts.enumerate() {
                     for (i, query_index) in replacement.clone().enumerate().skip(x) {
-                        let word_index = match_.word_index + padding as u16 + (i + 1) as u16;
+                        let word_index = match_.word_index + padding as u32 + (i + 1) as u32;
                         let padmatch = SimpleMatch { query_index, word_index, ..*match_ };
 
                         for nmatch_ in next_group {
@@ -246,7 +320,7 @@ fn multiword_rewrite_matches(
                                     // if we find a corresponding padding for the
                                     // first time we must push preceding paddings
                                     for (i, query_index) in replacement.clone().enumerate().take(i) {
-                                        let word_index = match_.word_index + padding as u16 + (i + 1) as u16;
+                                        let word_index = match_.word_index + padding as u32 + (i + 1) as u32;
                                         let match_ = SimpleMatch { query_index, word_index, ..*match_ };
                                         padded_matches.push(match_);
                                         biggest = biggest.max(i + 1);
@@ -269,7 +343,7 @@ fn multiword_rewrite_matches(
                     // if no padding was found in the following matches
                     // we must insert the entire padding
                     for (i, query_index) in replacement.enumerate() {
-                        let word_index = match_.word_index + padding as u16 + (i + 1) as u16;
+                        let word_index = match_.word_index + padding as u32 + (i + 1) as u32;
                         let match_ = SimpleMatch { query_index, word_index, ..*match_ };
                         padded_matches.push(match_);
                     }
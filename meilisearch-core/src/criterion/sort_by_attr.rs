@@ -3,7 +3,7 @@ use std::error::Error;
 use std::fmt;
 use meilisearch_schema::{Schema, FieldId};
 use crate::{RankedMap, RawDocument};
-use super::{Criterion, Context};
+use super::{Criterion, CriterionValue, Context};
 
 /// An helper struct that permit to sort documents by
 /// some of their stored attributes.
@@ -108,6 +108,15 @@ impl Criterion for SortByAttr<'_> {
             (None, None) => Ordering::Equal,
         }
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> CriterionValue {
+        let value = self.ranked_map.get(document.id, self.field_id);
+        if self.reversed {
+            CriterionValue::Descending(value)
+        } else {
+            CriterionValue::Ascending(value)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
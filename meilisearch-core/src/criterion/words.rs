@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
-use crate::{RawDocument, MResult};
-use super::{Criterion, Context, ContextMut, prepare_query_distances};
+use crate::{Number, RawDocument, MResult};
+use super::{Criterion, CriterionValue, Context, ContextMut, prepare_query_distances};
 
 pub struct Words;
 
@@ -18,14 +18,19 @@ impl Criterion for Words {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn number_of_query_words(distances: &[Option<u8>]) -> usize {
-            distances.iter().cloned().filter(Option::is_some).count()
-        }
-
         let lhs = number_of_query_words(&lhs.processed_distances);
         let rhs = number_of_query_words(&rhs.processed_distances);
 
         lhs.cmp(&rhs).reverse()
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> CriterionValue {
+        let words = number_of_query_words(&document.processed_distances);
+        CriterionValue::Descending(Some(Number::Unsigned(words as u64)))
+    }
+}
+
+#[inline]
+fn number_of_query_words(distances: &[Option<u8>]) -> usize {
+    distances.iter().cloned().filter(Option::is_some).count()
 }
@@ -1,8 +1,8 @@
 use std::cmp::Ordering;
 use slice_group_by::GroupBy;
 use crate::bucket_sort::SimpleMatch;
-use crate::{RawDocument, MResult};
-use super::{Criterion, Context, ContextMut, prepare_bare_matches};
+use crate::{Number, RawDocument, MResult};
+use super::{Criterion, CriterionValue, Context, ContextMut, prepare_bare_matches};
 
 pub struct WordsPosition;
 
@@ -20,18 +20,23 @@ impl Criterion for WordsPosition {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn sum_words_position(matches: &[SimpleMatch]) -> usize {
-            let mut sum_words_position = 0;
-            for group in matches.linear_group_by_key(|bm| bm.query_index) {
-                sum_words_position += group[0].word_index as usize;
-            }
-            sum_words_position
-        }
-
         let lhs = sum_words_position(&lhs.processed_matches);
         let rhs = sum_words_position(&rhs.processed_matches);
 
         lhs.cmp(&rhs)
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> CriterionValue {
+        let sum = sum_words_position(&document.processed_matches);
+        CriterionValue::Ascending(Some(Number::Unsigned(sum as u64)))
+    }
+}
+
+#[inline]
+fn sum_words_position(matches: &[SimpleMatch]) -> usize {
+    let mut sum_words_position = 0;
+    for group in matches.linear_group_by_key(|bm| bm.query_index) {
+        sum_words_position += group[0].word_index as usize;
+    }
+    sum_words_position
 }
@@ -1,25 +1,31 @@
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::Duration;
 use std::{fs, thread};
 use std::io::{Read, Write, ErrorKind};
 
 use chrono::{DateTime, Utc};
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use heed::CompactionOption;
 use heed::types::{Str, Unit, SerdeBincode};
 use log::{debug, error};
 use meilisearch_schema::Schema;
 use regex::Regex;
 
-use crate::{store, update, Index, MResult, Error};
+use crate::{raw_indexer, store, update, Index, MResult, Error};
 
 pub type BoxUpdateFn = Box<dyn Fn(&str, update::ProcessedUpdateResult) + Send + Sync + 'static>;
 
 type ArcSwapFn = arc_swap::ArcSwapOption<BoxUpdateFn>;
 
+type ArcSwapUid = arc_swap::ArcSwap<String>;
+
+type IndexesMap = HashMap<String, (Index, Arc<ArcSwapUid>, thread::JoinHandle<MResult<()>>)>;
+
 type SerdeDatetime = SerdeBincode<DateTime<Utc>>;
 
 pub type MainWriter<'a> = heed::RwTxn<'a, MainT>;
@@ -34,19 +40,56 @@ const LAST_UPDATE_KEY: &str = "last-update";
 pub struct MainT;
 pub struct UpdateT;
 
+/// The main environment accepts a single writer at a time, the update loops of
+/// the indexes use this gate to let the updates with a high priority go first.
+/// The large additions are processed in batches of `update::DOCUMENTS_BATCH_SIZE`
+/// documents so that they do not hold the main environment for their whole duration.
+#[derive(Default)]
+struct PriorityGate {
+    pending: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl PriorityGate {
+    fn enter_high(&self) {
+        *self.pending.lock().unwrap() += 1;
+    }
+
+    fn leave_high(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        *pending -= 1;
+        if *pending == 0 {
+            self.condvar.notify_all();
+        }
+    }
+
+    fn wait_for_high(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        while *pending != 0 {
+            pending = self.condvar.wait(pending).unwrap();
+        }
+    }
+}
+
 pub struct Database {
     env: heed::Env,
     update_env: heed::Env,
     common_store: heed::PolyDatabase,
-    indexes_store: heed::Database<Str, Unit>,
-    indexes: RwLock<HashMap<String, (Index, thread::JoinHandle<MResult<()>>)>>,
+    indexes_store: heed::Database<Str, Str>,
+    indexes: Arc<RwLock<IndexesMap>>,
+    priority_gate: Arc<PriorityGate>,
+    aliases_store: heed::Database<Str, Str>,
+    aliases: RwLock<HashMap<String, String>>,
     update_fn: Arc<ArcSwapFn>,
     database_version: (u32, u32, u32),
+    max_indexed_words: usize,
 }
 
 pub struct DatabaseOptions {
     pub main_map_size: usize,
     pub update_map_size: usize,
+    /// The number of words indexed in each attribute of a document, the following words are ignored.
+    pub max_indexed_words: usize,
 }
 
 impl Default for DatabaseOptions {
@@ -54,6 +97,7 @@ impl Default for DatabaseOptions {
         DatabaseOptions {
             main_map_size: 100 * 1024 * 1024 * 1024, //100Gb
             update_map_size: 100 * 1024 * 1024 * 1024, //100Gb
+            max_indexed_words: raw_indexer::DEFAULT_WORD_LIMIT,
         }
     }
 }
@@ -73,20 +117,26 @@ macro_rules! r#break_try {
 pub enum UpdateEvent {
     NewUpdate,
     MustClear,
+    MustStop,
 }
 
 pub type UpdateEvents = Receiver<UpdateEvent>;
 pub type UpdateEventsEmitter = Sender<UpdateEvent>;
 
+#[allow(clippy::too_many_arguments)]
 fn update_awaiter(
     receiver: UpdateEvents,
     env: heed::Env,
     update_env: heed::Env,
-    index_uid: &str,
+    index_uid: Arc<ArcSwapUid>,
+    indexes: Arc<RwLock<IndexesMap>>,
+    priority_gate: Arc<PriorityGate>,
     update_fn: Arc<ArcSwapFn>,
     index: Index,
 ) -> MResult<()> {
-    for event in receiver {
+    // an event received while waiting for the source of a clone is handled before the next ones
+    let mut pending_event = None;
+    while let Some(event) = pending_event.take().or_else(|| receiver.recv().ok()) {
 
         // if we receive a *MustClear* event, clear the index and break the loop
         if let UpdateEvent::MustClear = event {
@@ -98,8 +148,13 @@ fn update_awaiter(
             writer.commit()?;
             update_writer.commit()?;
 
-            debug!("store {} cleared", index_uid);
+            debug!("store {} cleared", index_uid.load().as_str());
+
+            break
+        }
 
+        // the updates received before a *MustStop* event have been processed
+        if let UpdateEvent::MustStop = event {
             break
         }
 
@@ -110,7 +165,7 @@ fn update_awaiter(
             let update_reader = break_try!(result, "LMDB read transaction (update) begin failed");
 
             // retrieve the update that needs to be processed
-            let result = index.updates.first_update(&update_reader);
+            let result = index.updates.next_update(&update_reader);
             let (update_id, update) = match break_try!(result, "pop front update failed") {
                 Some(value) => value,
                 None => {
@@ -119,15 +174,63 @@ fn update_awaiter(
                 }
             };
 
+            // some updates read another index, it must be retrieved before opening
+            // the main transaction as the indexes lock is held while creating indexes
+            let source = update.source().and_then(|(uid, after_update)| {
+                let indexes_lock = indexes.read().unwrap();
+                indexes_lock.get(uid).map(|(index, ..)| (index.clone(), after_update))
+            });
+
+            // the source must have processed the updates enqueued before this update,
+            // the following updates of this index wait behind it in the meantime but
+            // the index must still be stopped or cleared when asked to while waiting
+            if let Some((source, Some(after_update))) = &source {
+                let result = source.updates_results.update_result(&update_reader, *after_update);
+                if break_try!(result, "retrieve source update result failed").is_none() {
+                    break_try!(update_reader.abort(), "aborting update transaction failed");
+                    match receiver.recv_timeout(Duration::from_millis(100)) {
+                        Ok(UpdateEvent::NewUpdate) | Err(RecvTimeoutError::Timeout) => continue,
+                        Ok(event) => { pending_event = Some(event); break },
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            }
+            let source = source.map(|(source, _)| source);
+
+            // a large addition is processed in batches, each one in its own main transaction,
+            // the updates of the other indexes waiting for the main transaction go in between
+            let (update, documents_left) = update.split_documents(update::DOCUMENTS_BATCH_SIZE);
+
             // do not keep the reader for too long
             break_try!(update_reader.abort(), "aborting update transaction failed");
 
+            // the updates with a normal priority let the high priority
+            // updates of all the indexes take the main transaction first
+            let is_high_priority = !update.priority.is_normal();
+            if is_high_priority {
+                priority_gate.enter_high();
+            } else {
+                priority_gate.wait_for_high();
+            }
+
             // instantiate a transaction to touch to the main env
             let result = env.typed_write_txn::<MainT>();
-            let mut main_writer = break_try!(result, "LMDB nested write transaction failed");
+            let mut main_writer = match result {
+                Ok(main_writer) => main_writer,
+                Err(e) => {
+                    if is_high_priority {
+                        priority_gate.leave_high();
+                    }
+                    error!("LMDB nested write transaction failed: {}", e);
+                    break;
+                }
+            };
 
             // try to apply the update to the database using the main transaction
-            let result = update::update_task(&mut main_writer, &index, update_id, update);
+            let result = update::update_task(&mut main_writer, &index, source.as_ref(), update_id, update);
+            if is_high_priority {
+                priority_gate.leave_high();
+            }
             let status = break_try!(result, "update task failed");
 
             // commit the main transaction if the update was successful, abort it otherwise
@@ -137,6 +240,20 @@ fn update_awaiter(
                 break_try!(main_writer.abort(), "abborting nested transaction failed");
             }
 
+            // the documents left keep the id of the update, its result is only
+            // written once the last batch is processed or when a batch fails
+            if let (Some(mut documents_left), None) = (documents_left, &status.error) {
+                let result = update_env.typed_write_txn::<UpdateT>();
+                let mut update_writer = break_try!(result, "LMDB write transaction begin failed");
+
+                documents_left.processed.duration = Duration::from_secs_f64(status.duration);
+                let result = index.updates.put_update(&mut update_writer, update_id, &documents_left);
+
+                break_try!(result, "update store put failed");
+                break_try!(update_writer.commit(), "update transaction commit failed");
+                continue;
+            }
+
             // now that the update has been processed we can instantiate
             // a transaction to move the result to the updates-results store
             let result = update_env.typed_write_txn::<UpdateT>();
@@ -153,9 +270,10 @@ fn update_awaiter(
             break_try!(result, "update result store commit failed");
             break_try!(update_writer.commit(), "update transaction commit failed");
 
-            // call the user callback when the update and the result are written consistently
+            // call the user callback when the update and the result are written consistently,
+            // the uid is loaded here as the index could have been swapped in the meantime
             if let Some(ref callback) = *update_fn.load() {
-                (callback)(index_uid, status);
+                (callback)(index_uid.load().as_str(), status);
             }
         }
     }
@@ -165,68 +283,143 @@ fn update_awaiter(
     Ok(())
 }
 
-/// Ensures Meilisearch version is compatible with the database, returns an error versions mismatch.
-/// If create is set to true, a VERSION file is created with the current version.
-fn version_guard(path: &Path, create: bool) -> MResult<(u32, u32, u32)> {
-    let current_version_major = env!("CARGO_PKG_VERSION_MAJOR");
-    let current_version_minor = env!("CARGO_PKG_VERSION_MINOR");
-    let current_version_patch = env!("CARGO_PKG_VERSION_PATCH");
+/// The version of the engine, written in the VERSION file of the databases it creates.
+fn current_version() -> (u32, u32, u32) {
+    // the crate version is always made of numbers, it is safe to unwrap
+    (
+        env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+        env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+        env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+    )
+}
+
+/// The revision of the layout of the stores, bumped when the stores written with the
+/// previous revisions can not be read anymore. The first revision stored the field ids,
+/// the indexed positions and the positions of the words on 16 bits, the second one
+/// did not record the nested fields in the schemas and is upgraded in place.
+pub const STORAGE_FORMAT: u32 = 3;
+
+/// Reads the version of the database at `path` from its VERSION file,
+/// returns `None` if there is no such file.
+pub fn database_version(path: &Path) -> MResult<Option<(u32, u32, u32)>> {
     let version_path = path.join("VERSION");
 
+    let mut version = String::new();
     match File::open(&version_path) {
-        Ok(mut file) => {
-            let mut version = String::new();
-            file.read_to_string(&mut version)?;
-            // Matches strings like XX.XX.XX
-            let re = Regex::new(r"(\d+).(\d+).(\d+)").unwrap();
-
-            // Make sure there is a result
-            let version = re
-                .captures_iter(&version)
-                .next()
-                .ok_or_else(|| Error::VersionMismatch("bad VERSION file".to_string()))?;
-            // the first is always the complete match, safe to unwrap because we have a match
-            let version_major = version.get(1).unwrap().as_str();
-            let version_minor = version.get(2).unwrap().as_str();
-            let version_patch = version.get(3).unwrap().as_str();
-
-            if version_major != current_version_major || version_minor != current_version_minor {
-                Err(Error::VersionMismatch(format!("{}.{}.XX", version_major, version_minor)))
-            } else {
-                Ok((
-                    version_major.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?, 
-                    version_minor.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?, 
-                    version_patch.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?
-                ))
-            }
+        Ok(mut file) => file.read_to_string(&mut version)?,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    // Matches strings like XX.XX.XX
+    let re = Regex::new(r"(\d+).(\d+).(\d+)").unwrap();
+
+    // Make sure there is a result
+    let version = re
+        .captures_iter(&version)
+        .next()
+        .ok_or_else(|| Error::VersionMismatch("bad VERSION file".to_string()))?;
+
+    // the first is always the complete match, safe to unwrap because we have a match
+    let parse = |i: usize| {
+        version.get(i).unwrap().as_str().parse()
+            .map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))
+    };
+
+    Ok(Some((parse(1)?, parse(2)?, parse(3)?)))
+}
+
+/// Whether the database at `path` was written by an engine with another major or minor version.
+pub fn is_outdated(path: &Path) -> MResult<bool> {
+    let (current_major, current_minor, _) = current_version();
+    match database_version(path)? {
+        Some((major, minor, _)) if major != current_major || minor != current_minor => Ok(true),
+        Some(_) => Ok(storage_format(path)? != STORAGE_FORMAT),
+        None => Ok(path.join("main").exists() || path.join("update").exists()),
+    }
+}
+
+/// Reads the storage format revision of the database at `path` from its FORMAT file,
+/// the databases written before this file was introduced use the first revision.
+pub fn storage_format(path: &Path) -> MResult<u32> {
+    match fs::read_to_string(path.join("FORMAT")) {
+        Ok(format) => format.trim().parse()
+            .map_err(|e| Error::VersionMismatch(format!("error parsing database storage format: {}", e))),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(1),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Writes the current storage format revision in the FORMAT file of the database at `path`.
+fn write_format_file(path: &Path) -> MResult<()> {
+    fs::write(path.join("FORMAT"), STORAGE_FORMAT.to_string())?;
+    Ok(())
+}
+
+/// Upgrades in place the database at `path` written with the second storage format,
+/// only the schemas of the indexes changed of layout since.
+fn upgrade_from_format_2(path: &Path, options: &DatabaseOptions) -> MResult<()> {
+    let env = heed::EnvOpenOptions::new()
+        .map_size(options.main_map_size)
+        .max_dbs(3000)
+        .open(path.join("main"))?;
+
+    let mut writer = env.typed_write_txn::<MainT>()?;
+    if let Some(indexes_store) = env.open_database::<Str, Str>(Some("indexes"))? {
+        let mut store_names = Vec::new();
+        for result in indexes_store.iter(&writer)? {
+            let (index_uid, store_name) = result?;
+            let store_name = if store_name.is_empty() { index_uid } else { store_name };
+            store_names.push(store_name.to_owned());
         }
-        Err(error) => {
-            match error.kind() {
-                ErrorKind::NotFound => {
-                    if create {
-                        // when no version file is found, and we've been told to create one,
-                        // create a new file with the current version in it.
-                        let mut version_file = File::create(&version_path)?;
-                        version_file.write_all(format!("{}.{}.{}",
-                                current_version_major,
-                                current_version_minor,
-                                current_version_patch).as_bytes())?;
-
-                        Ok((
-                            current_version_major.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?, 
-                            current_version_minor.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?, 
-                            current_version_patch.parse().map_err(|e| Error::VersionMismatch(format!("error parsing database version: {}", e)))?
-                        ))
-                    } else {
-                        // when no version file is found and we were not told to create one, this
-                        // means that the version is inferior to the one this feature was added in.
-                        Err(Error::VersionMismatch("<0.12.0".to_string()))
-                    }
+
+        for store_name in store_names {
+            if let Some(main) = env.open_poly_database(Some(&format!("store-{}", store_name)))? {
+                let main = store::Main { main };
+                if let Some(schema) = main.schema_v2(&writer)? {
+                    main.put_schema(&mut writer, &schema)?;
                 }
-                _ => Err(error.into())
             }
         }
     }
+    writer.commit()?;
+
+    write_format_file(path)
+}
+
+/// Writes the current engine version in the VERSION file of the database at `path`.
+fn write_version_file(path: &Path) -> MResult<()> {
+    let (major, minor, patch) = current_version();
+    let mut version_file = File::create(path.join("VERSION"))?;
+    version_file.write_all(format!("{}.{}.{}", major, minor, patch).as_bytes())?;
+    Ok(())
+}
+
+/// Ensures Meilisearch version is compatible with the database, returns an error versions mismatch.
+/// If create is set to true, a VERSION file is created with the current version.
+fn version_guard(path: &Path, create: bool, options: &DatabaseOptions) -> MResult<(u32, u32, u32)> {
+    let (current_major, current_minor, _) = current_version();
+
+    match database_version(path)? {
+        Some((major, minor, patch)) if major == current_major && minor == current_minor => {
+            match storage_format(path)? {
+                STORAGE_FORMAT => Ok((major, minor, patch)),
+                2 => upgrade_from_format_2(path, options).map(|_| (major, minor, patch)),
+                format => Err(Error::StorageFormatMismatch(format)),
+            }
+        },
+        Some((major, minor, _)) => Err(Error::VersionMismatch(format!("{}.{}.XX", major, minor))),
+        None if create => {
+            // when no version file is found, and we've been told to create one,
+            // create a new file with the current version in it.
+            write_version_file(path)?;
+            write_format_file(path)?;
+            Ok(current_version())
+        }
+        // when no version file is found and we were not told to create one, this
+        // means that the version is inferior to the one this feature was added in.
+        None => Err(Error::VersionMismatch("<0.12.0".to_string())),
+    }
 }
 
 impl Database {
@@ -238,7 +431,29 @@ impl Database {
         fs::create_dir_all(&path)?;
 
         // create file only if main db wasn't created before (first run)
-        let database_version = version_guard(path.as_ref(), !main_path.exists() && !update_path.exists())?;
+        let database_version = version_guard(path.as_ref(), !main_path.exists() && !update_path.exists(), &options)?;
+
+        Database::open(path.as_ref(), options, database_version)
+    }
+
+    /// Opens a database written by another version of the engine, in order to migrate it.
+    /// Databases older than the VERSION file or written with another storage format can't be opened.
+    pub fn open_outdated(path: impl AsRef<Path>, options: DatabaseOptions) -> MResult<Database> {
+        let database_version = database_version(path.as_ref())?
+            .ok_or_else(|| Error::VersionMismatch("<0.12.0".to_string()))?;
+
+        match storage_format(path.as_ref())? {
+            STORAGE_FORMAT => (),
+            2 => upgrade_from_format_2(path.as_ref(), &options)?,
+            format => return Err(Error::StorageFormatMismatch(format)),
+        }
+
+        Database::open(path.as_ref(), options, database_version)
+    }
+
+    fn open(path: &Path, options: DatabaseOptions, database_version: (u32, u32, u32)) -> MResult<Database> {
+        let main_path = path.join("main");
+        let update_path = path.join("update");
 
         fs::create_dir_all(&main_path)?;
         let env = heed::EnvOpenOptions::new()
@@ -253,24 +468,36 @@ impl Database {
             .open(update_path)?;
 
         let common_store = env.create_poly_database(Some("common"))?;
-        let indexes_store = env.create_database::<Str, Unit>(Some("indexes"))?;
+        let indexes_store = env.create_database::<Str, Str>(Some("indexes"))?;
+        let aliases_store = env.create_database::<Str, Str>(Some("aliases"))?;
         let update_fn = Arc::new(ArcSwapFn::empty());
+        let priority_gate = Arc::new(PriorityGate::default());
 
         // list all indexes that needs to be opened
         let mut must_open = Vec::new();
         let reader = env.read_txn()?;
         for result in indexes_store.iter(&reader)? {
-            let (index_uid, _) = result?;
-            must_open.push(index_uid.to_owned());
+            let (index_uid, store_name) = result?;
+            let store_name = if store_name.is_empty() { index_uid } else { store_name };
+            must_open.push((index_uid.to_owned(), store_name.to_owned()));
+        }
+
+        // load all the aliases in memory
+        let mut aliases = HashMap::new();
+        for result in aliases_store.iter(&reader)? {
+            let (alias, index_uid) = result?;
+            aliases.insert(alias.to_owned(), index_uid.to_owned());
         }
 
         reader.abort()?;
 
-        // open the previously aggregated indexes
-        let mut indexes = HashMap::new();
-        for index_uid in must_open {
+        // open the previously aggregated indexes, the lock is held until all the
+        // indexes are open as pending updates could need to reach another index
+        let indexes_arc = Arc::new(RwLock::new(HashMap::new()));
+        let mut indexes = indexes_arc.write().unwrap();
+        for (index_uid, store_name) in must_open {
             let (sender, receiver) = crossbeam_channel::unbounded();
-            let index = match store::open(&env, &update_env, &index_uid, sender.clone())? {
+            let index = match store::open(&env, &update_env, &store_name, sender.clone(), options.max_indexed_words)? {
                 Some(index) => index,
                 None => {
                     log::warn!(
@@ -284,7 +511,10 @@ impl Database {
             let env_clone = env.clone();
             let update_env_clone = update_env.clone();
             let index_clone = index.clone();
-            let name_clone = index_uid.clone();
+            let uid = Arc::new(ArcSwapUid::from_pointee(index_uid.clone()));
+            let uid_clone = uid.clone();
+            let indexes_clone = indexes_arc.clone();
+            let priority_gate_clone = priority_gate.clone();
             let update_fn_clone = update_fn.clone();
 
             let handle = thread::spawn(move || {
@@ -292,7 +522,9 @@ impl Database {
                     receiver,
                     env_clone,
                     update_env_clone,
-                    &name_clone,
+                    uid_clone,
+                    indexes_clone,
+                    priority_gate_clone,
                     update_fn_clone,
                     index_clone,
                 )
@@ -302,29 +534,41 @@ impl Database {
             // possible pre-boot updates are consumed
             sender.send(UpdateEvent::NewUpdate).unwrap();
 
-            let result = indexes.insert(index_uid, (index, handle));
+            let result = indexes.insert(index_uid, (index, uid, handle));
             assert!(
                 result.is_none(),
                 "The index should not have been already open"
             );
         }
 
+        drop(indexes);
+
         Ok(Database {
             env,
             update_env,
             common_store,
             indexes_store,
-            indexes: RwLock::new(indexes),
+            indexes: indexes_arc,
+            priority_gate,
+            aliases_store,
+            aliases: RwLock::new(aliases),
             update_fn,
             database_version,
+            max_indexed_words: options.max_indexed_words,
         })
     }
 
+    /// Opens the index with the given uid, or the index targeted by the given alias.
     pub fn open_index(&self, name: impl AsRef<str>) -> Option<Index> {
+        let name = name.as_ref();
         let indexes_lock = self.indexes.read().unwrap();
-        match indexes_lock.get(name.as_ref()) {
+        match indexes_lock.get(name) {
             Some((index, ..)) => Some(index.clone()),
-            None => None,
+            None => {
+                let aliases_lock = self.aliases.read().unwrap();
+                let index_uid = aliases_lock.get(name)?;
+                indexes_lock.get(index_uid).map(|(index, ..)| index.clone())
+            }
         }
     }
 
@@ -341,12 +585,21 @@ impl Database {
 
         match indexes_lock.entry(name.to_owned()) {
             Entry::Occupied(_) => Err(crate::Error::IndexAlreadyExists),
+            Entry::Vacant(_) if self.aliases.read().unwrap().contains_key(name) => {
+                Err(crate::Error::AliasAlreadyExists(name.to_owned()))
+            }
             Entry::Vacant(entry) => {
+                // the stores of a swapped index keep their original name,
+                // we must make sure to not reuse the stores of another index
+                let reader = self.env.typed_read_txn::<MainT>()?;
+                let store_name = self.available_store_name(&reader, name)?;
+                reader.abort()?;
+
                 let (sender, receiver) = crossbeam_channel::unbounded();
-                let index = store::create(&self.env, &self.update_env, name, sender)?;
+                let index = store::create(&self.env, &self.update_env, &store_name, sender, self.max_indexed_words)?;
 
                 let mut writer = self.env.typed_write_txn::<MainT>()?;
-                self.indexes_store.put(&mut writer, name, &())?;
+                self.indexes_store.put(&mut writer, name, &store_name)?;
 
                 index.main.put_name(&mut writer, name)?;
                 index.main.put_created_at(&mut writer)?;
@@ -356,7 +609,10 @@ impl Database {
                 let env_clone = self.env.clone();
                 let update_env_clone = self.update_env.clone();
                 let index_clone = index.clone();
-                let name_clone = name.to_owned();
+                let uid = Arc::new(ArcSwapUid::from_pointee(name.to_owned()));
+                let uid_clone = uid.clone();
+                let indexes_clone = self.indexes.clone();
+                let priority_gate_clone = self.priority_gate.clone();
                 let update_fn_clone = self.update_fn.clone();
 
                 let handle = thread::spawn(move || {
@@ -364,35 +620,121 @@ impl Database {
                         receiver,
                         env_clone,
                         update_env_clone,
-                        &name_clone,
+                        uid_clone,
+                        indexes_clone,
+                        priority_gate_clone,
                         update_fn_clone,
                         index_clone,
                     )
                 });
 
                 writer.commit()?;
-                entry.insert((index.clone(), handle));
+                entry.insert((index.clone(), uid, handle));
 
                 Ok(index)
             }
         }
     }
 
+    /// Returns the name of the stores used by the index with the given uid.
+    fn store_name(&self, reader: &heed::RoTxn<MainT>, uid: &str) -> MResult<String> {
+        match self.indexes_store.get(reader, uid)? {
+            Some(store_name) if !store_name.is_empty() => Ok(store_name.to_owned()),
+            Some(_) => Ok(uid.to_owned()),
+            None => Err(Error::IndexNotFound(uid.to_owned())),
+        }
+    }
+
+    /// Returns a store name, based on the given uid, that is not used by any other index.
+    fn available_store_name(&self, reader: &heed::RoTxn<MainT>, uid: &str) -> MResult<String> {
+        let mut used = HashSet::new();
+        for result in self.indexes_store.iter(reader)? {
+            let (index_uid, store_name) = result?;
+            let store_name = if store_name.is_empty() { index_uid } else { store_name };
+            used.insert(store_name.to_owned());
+        }
+
+        let mut store_name = uid.to_owned();
+        let mut suffix = 0;
+        while used.contains(&store_name) {
+            suffix += 1;
+            store_name = format!("{}-swap{}", uid, suffix);
+        }
+
+        Ok(store_name)
+    }
+
+    /// Atomically exchanges the uids of two indexes.
+    ///
+    /// Both the stores and the pending updates follow the data, the names of the indexes are
+    /// exchanged too so that they stay attached to the uids.
+    pub fn swap_indexes(&self, first: impl AsRef<str>, second: impl AsRef<str>) -> MResult<()> {
+        let (first, second) = (first.as_ref(), second.as_ref());
+        let mut indexes_lock = self.indexes.write().unwrap();
+
+        let (first_index, second_index) = match (indexes_lock.get(first), indexes_lock.get(second)) {
+            (Some((first_index, ..)), Some((second_index, ..))) => (first_index.clone(), second_index.clone()),
+            (None, _) => return Err(Error::IndexNotFound(first.to_owned())),
+            (_, None) => return Err(Error::IndexNotFound(second.to_owned())),
+        };
+
+        if first == second {
+            return Ok(());
+        }
+
+        let mut writer = self.env.typed_write_txn::<MainT>()?;
+
+        let first_store_name = self.store_name(&writer, first)?;
+        let second_store_name = self.store_name(&writer, second)?;
+        self.indexes_store.put(&mut writer, first, &second_store_name)?;
+        self.indexes_store.put(&mut writer, second, &first_store_name)?;
+
+        let first_name = first_index.main.name(&writer)?;
+        let second_name = second_index.main.name(&writer)?;
+        if let Some(name) = first_name {
+            second_index.main.put_name(&mut writer, &name)?;
+        }
+        if let Some(name) = second_name {
+            first_index.main.put_name(&mut writer, &name)?;
+        }
+
+        writer.commit()?;
+
+        // the in-memory map is only modified once the swap is durable, the write lock
+        // ensures that no one can open one of these indexes in the meantime
+        let first_entry = indexes_lock.remove(first).unwrap();
+        let second_entry = indexes_lock.remove(second).unwrap();
+        first_entry.1.store(Arc::new(second.to_owned()));
+        second_entry.1.store(Arc::new(first.to_owned()));
+        indexes_lock.insert(first.to_owned(), second_entry);
+        indexes_lock.insert(second.to_owned(), first_entry);
+
+        Ok(())
+    }
+
     pub fn delete_index(&self, name: impl AsRef<str>) -> MResult<bool> {
         let name = name.as_ref();
         let mut indexes_lock = self.indexes.write().unwrap();
 
         match indexes_lock.remove_entry(name) {
-            Some((name, (index, handle))) => {
-                // remove the index name from the list of indexes
-                // and clear all the LMDB dbi
+            Some((name, (index, _, handle))) => {
+                let mut aliases_lock = self.aliases.write().unwrap();
+
+                // remove the index name from the list of indexes,
+                // the aliases targeting it and clear all the LMDB dbi
                 let mut writer = self.env.write_txn()?;
                 self.indexes_store.delete(&mut writer, &name)?;
+                for (alias, _) in aliases_lock.iter().filter(|(_, uid)| **uid == name) {
+                    self.aliases_store.delete(&mut writer, alias)?;
+                }
                 writer.commit()?;
 
+                aliases_lock.retain(|_, uid| *uid != name);
+
                 // send a stop event to the update loop of the index
                 index.updates_notifier.send(UpdateEvent::MustClear).unwrap();
 
+                drop(aliases_lock);
                 drop(indexes_lock);
 
                 // join the update loop thread to ensure it is stopped
@@ -404,11 +746,104 @@ impl Database {
         }
     }
 
+    /// Returns all the aliases associated with the uid of the index they target.
+    pub fn aliases(&self) -> BTreeMap<String, String> {
+        let aliases_lock = self.aliases.read().unwrap();
+        aliases_lock.iter().map(|(a, u)| (a.clone(), u.clone())).collect()
+    }
+
+    /// Returns the uid of the index targeted by the given alias.
+    pub fn alias(&self, alias: impl AsRef<str>) -> Option<String> {
+        let aliases_lock = self.aliases.read().unwrap();
+        aliases_lock.get(alias.as_ref()).cloned()
+    }
+
+    pub fn create_alias(&self, alias: impl AsRef<str>, index_uid: impl AsRef<str>) -> MResult<()> {
+        let (alias, index_uid) = (alias.as_ref(), index_uid.as_ref());
+        let indexes_lock = self.indexes.read().unwrap();
+        let mut aliases_lock = self.aliases.write().unwrap();
+
+        if aliases_lock.contains_key(alias) {
+            return Err(Error::AliasAlreadyExists(alias.to_owned()));
+        }
+        if indexes_lock.contains_key(alias) {
+            return Err(Error::InvalidAlias(format!("{} is already an index uid", alias)));
+        }
+        if !indexes_lock.contains_key(index_uid) {
+            return Err(Error::IndexNotFound(index_uid.to_owned()));
+        }
+
+        let mut writer = self.env.typed_write_txn::<MainT>()?;
+        self.aliases_store.put(&mut writer, alias, index_uid)?;
+        writer.commit()?;
+
+        aliases_lock.insert(alias.to_owned(), index_uid.to_owned());
+
+        Ok(())
+    }
+
+    /// Makes an existing alias target another index.
+    pub fn update_alias(&self, alias: impl AsRef<str>, index_uid: impl AsRef<str>) -> MResult<()> {
+        let (alias, index_uid) = (alias.as_ref(), index_uid.as_ref());
+        let indexes_lock = self.indexes.read().unwrap();
+        let mut aliases_lock = self.aliases.write().unwrap();
+
+        if !aliases_lock.contains_key(alias) {
+            return Err(Error::AliasNotFound(alias.to_owned()));
+        }
+        if !indexes_lock.contains_key(index_uid) {
+            return Err(Error::IndexNotFound(index_uid.to_owned()));
+        }
+
+        let mut writer = self.env.typed_write_txn::<MainT>()?;
+        self.aliases_store.put(&mut writer, alias, index_uid)?;
+        writer.commit()?;
+
+        aliases_lock.insert(alias.to_owned(), index_uid.to_owned());
+
+        Ok(())
+    }
+
+    pub fn delete_alias(&self, alias: impl AsRef<str>) -> MResult<bool> {
+        let alias = alias.as_ref();
+        let mut aliases_lock = self.aliases.write().unwrap();
+
+        if !aliases_lock.contains_key(alias) {
+            return Ok(false);
+        }
+
+        let mut writer = self.env.typed_write_txn::<MainT>()?;
+        self.aliases_store.delete(&mut writer, alias)?;
+        writer.commit()?;
+
+        aliases_lock.remove(alias);
+
+        Ok(true)
+    }
+
     pub fn set_update_callback(&self, update_fn: BoxUpdateFn) {
         let update_fn = Some(Arc::new(update_fn));
         self.update_fn.swap(update_fn);
     }
 
+    /// Stops the update loops of the indexes once the updates they received are processed
+    /// and unsets the update callback, the environments are closed when the database is dropped.
+    pub fn close(&self) -> MResult<()> {
+        let indexes: Vec<_> = self.indexes.write().unwrap().drain().collect();
+        for (_, (index, ..)) in &indexes {
+            index.updates_notifier.send(UpdateEvent::MustStop).unwrap();
+        }
+
+        // join the update loop threads to ensure they are stopped
+        for (_, (_, _, handle)) in indexes {
+            handle.join().unwrap()?;
+        }
+
+        self.unset_update_callback();
+
+        Ok(())
+    }
+
     pub fn unset_update_callback(&self) {
         self.update_fn.swap(None);
     }
@@ -496,6 +931,7 @@ impl Database {
                 current_version_major,
                 current_version_minor,
                 current_version_patch).as_bytes())?;
+        write_format_file(path)?;
 
         let env_path = env_path.join("data.mdb");
         let env_file = self.env.copy_to_path(&env_path, CompactionOption::Enabled)?;
@@ -597,7 +1033,7 @@ mod tests {
 
     use crate::bucket_sort::SortResult;
     use crate::criterion::{self, CriteriaBuilder};
-    use crate::update::{ProcessedUpdateResult, UpdateStatus};
+    use crate::update::{ProcessedUpdateResult, UpdatePriority, UpdateStatus};
     use crate::settings::Settings;
     use crate::{Document, DocumentId};
     use serde::de::IgnoredAny;
@@ -1317,4 +1753,401 @@ mod tests {
         );
         assert_matches!(iter.next(), None);
     }
+
+    #[test]
+    fn swap_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let products = database.create_index("products").unwrap();
+        let products_tmp = database.create_index("products_tmp").unwrap();
+
+        let mut writer = db.main_write_txn().unwrap();
+        products.main.put_schema(&mut writer, &Schema::with_primary_key("id")).unwrap();
+        products_tmp.main.put_schema(&mut writer, &Schema::with_primary_key("uid")).unwrap();
+        writer.commit().unwrap();
+
+        database.swap_indexes("products", "products_tmp").unwrap();
+
+        let primary_key = |db: &Database, uid: &str| {
+            let index = db.open_index(uid).unwrap();
+            let reader = db.main_read_txn().unwrap();
+            let schema = index.main.schema(&reader).unwrap().unwrap();
+            let name = index.main.name(&reader).unwrap().unwrap();
+            assert_eq!(name, uid);
+            schema.primary_key().map(ToOwned::to_owned)
+        };
+
+        assert_eq!(primary_key(db, "products").as_deref(), Some("uid"));
+        assert_eq!(primary_key(db, "products_tmp").as_deref(), Some("id"));
+
+        // the swap must survive a restart
+        drop(database);
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        assert_eq!(primary_key(&database, "products").as_deref(), Some("uid"));
+        assert_eq!(primary_key(&database, "products_tmp").as_deref(), Some("id"));
+
+        // a new index must not reuse the stores of a swapped one
+        assert!(database.delete_index("products_tmp").unwrap());
+        let products_tmp = database.create_index("products_tmp").unwrap();
+        let reader = database.main_read_txn().unwrap();
+        assert!(products_tmp.main.schema(&reader).unwrap().unwrap().primary_key().is_none());
+        reader.abort().unwrap();
+        assert_eq!(primary_key(&database, "products").as_deref(), Some("uid"));
+
+        let result = database.swap_indexes("products", "unknown");
+        assert_matches!(result, Err(Error::IndexNotFound(uid)) if uid == "unknown");
+    }
+
+    #[test]
+    fn close_database() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let mut update_writer = db.update_write_txn().unwrap();
+        let first = index.customs_update(&mut update_writer, vec![1]).unwrap();
+        let second = index.customs_update(&mut update_writer, vec![2]).unwrap();
+        update_writer.commit().unwrap();
+
+        // the updates received before closing are processed
+        database.close().unwrap();
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![first, second]);
+        assert!(database.open_index("test").is_none());
+
+        let reader = db.update_read_txn().unwrap();
+        assert!(index.updates_results.update_result(&reader, second).unwrap().is_some());
+        reader.abort().unwrap();
+        drop(database);
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let index = database.open_index("test").unwrap();
+        let reader = database.main_read_txn().unwrap();
+        assert_eq!(index.main.customs(&reader).unwrap(), Some(&[2][..]));
+    }
+
+    #[test]
+    fn high_priority_updates_first() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send((update.update_id, update.priority)).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        // the updates are enqueued in the same transaction to
+        // be sure that none of them is processed before the others
+        let mut update_writer = db.update_write_txn().unwrap();
+        let first = index.customs_update(&mut update_writer, vec![1]).unwrap();
+        let second = index.customs_update(&mut update_writer, vec![2]).unwrap();
+        let third = index.customs_update(&mut update_writer, vec![3]).unwrap();
+        index.set_update_priority(&mut update_writer, third, UpdatePriority::High).unwrap();
+        update_writer.commit().unwrap();
+
+        let processed: Vec<_> = receiver.iter().take(3).collect();
+        assert_eq!(processed, vec![
+            (third, UpdatePriority::High),
+            (first, UpdatePriority::Normal),
+            (second, UpdatePriority::Normal),
+        ]);
+
+        let reader = db.main_read_txn().unwrap();
+        assert_eq!(index.main.customs(&reader).unwrap(), Some(&[2][..]));
+    }
+
+    #[test]
+    fn documents_addition_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let mut writer = db.main_write_txn().unwrap();
+        index.main.put_schema(&mut writer, &Schema::with_primary_key("id")).unwrap();
+        writer.commit().unwrap();
+
+        let number = update::DOCUMENTS_BATCH_SIZE + 1;
+        let mut additions = index.documents_addition();
+        for id in 0..number {
+            additions.update_document(serde_json::json!({ "id": id, "name": "Marvin" }));
+        }
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // the result is only sent once, after the last batch
+        assert_eq!(receiver.recv().unwrap(), update_id);
+        assert!(receiver.try_recv().is_err());
+
+        let update_reader = db.update_read_txn().unwrap();
+        match index.update_status(&update_reader, update_id).unwrap() {
+            Some(UpdateStatus::Processed { content }) => {
+                assert!(content.error.is_none());
+                assert_matches!(content.update_type, update::UpdateType::DocumentsAddition { number: n } if n == number);
+            }
+            status => panic!("unexpected update status {:?}", status),
+        }
+        update_reader.abort().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        assert_eq!(index.main.number_of_documents(&reader).unwrap(), number as u64);
+    }
+
+    #[test]
+    fn partial_update_replaces_nested_objects() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let mut writer = db.main_write_txn().unwrap();
+        index.main.put_schema(&mut writer, &Schema::with_primary_key("id")).unwrap();
+        writer.commit().unwrap();
+
+        let mut additions = index.documents_addition();
+        additions.update_document(serde_json::json!({
+            "id": 1,
+            "name": "Marvin",
+            "address": { "city": "Paris", "zip": "75001" },
+        }));
+
+        let mut writer = db.update_write_txn().unwrap();
+        additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        let mut additions = index.documents_partial_addition();
+        additions.update_document(serde_json::json!({
+            "id": 1,
+            "address": { "city": "Lyon" },
+        }));
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // block until the transaction is processed
+        let _ = receiver.iter().find(|id| *id == update_id);
+
+        let update_reader = db.update_read_txn().unwrap();
+        let result = index.update_status(&update_reader, update_id).unwrap();
+        assert_matches!(result, Some(UpdateStatus::Processed { content }) if content.error.is_none());
+        update_reader.abort().unwrap();
+
+        // the fields that are not in the update are kept, the nested object is replaced
+        let reader = db.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        let document: Option<indexmap::IndexMap<String, serde_json::Value>> = index
+            .document(&reader, None, DocumentId(0))
+            .unwrap();
+        let document = crate::update::unflatten_document(document.unwrap(), &schema);
+        assert_eq!(document.get("name"), Some(&serde_json::json!("Marvin")));
+        assert_eq!(document.get("address"), Some(&serde_json::json!({ "city": "Lyon" })));
+    }
+
+    #[test]
+    fn outdated_database() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("VERSION"), "0.1.0").unwrap();
+
+        assert_eq!(database_version(dir.path()).unwrap(), Some((0, 1, 0)));
+        assert!(is_outdated(dir.path()).unwrap());
+
+        let result = Database::open_or_create(dir.path(), DatabaseOptions::default());
+        assert_matches!(result.err(), Some(Error::VersionMismatch(_)));
+
+        let database = Database::open_outdated(dir.path(), DatabaseOptions::default()).unwrap();
+        assert_eq!(database.version(), (0, 1, 0));
+    }
+
+    #[test]
+    fn legacy_storage_format() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        assert_eq!(storage_format(dir.path()).unwrap(), STORAGE_FORMAT);
+        assert!(!is_outdated(dir.path()).unwrap());
+        drop(database);
+
+        // the databases written before the FORMAT file use 16 bits field ids
+        fs::remove_file(dir.path().join("FORMAT")).unwrap();
+        assert_eq!(storage_format(dir.path()).unwrap(), 1);
+        assert!(is_outdated(dir.path()).unwrap());
+
+        let result = Database::open_or_create(dir.path(), DatabaseOptions::default());
+        assert_matches!(result.err(), Some(Error::StorageFormatMismatch(1)));
+
+        let result = Database::open_outdated(dir.path(), DatabaseOptions::default());
+        assert_matches!(result.err(), Some(Error::StorageFormatMismatch(1)));
+    }
+
+    #[test]
+    fn upgrade_storage_format_2() {
+        use meilisearch_schema::{FieldId, FieldsMap, IndexedPos};
+        use serde::Serialize;
+
+        // the layout of the schema written with the second storage format
+        #[derive(Serialize)]
+        #[allow(dead_code)]
+        enum StoredOptionAll<T> {
+            All,
+            Some(T),
+            None,
+        }
+
+        #[derive(Serialize)]
+        struct StoredSchema {
+            fields_map: FieldsMap,
+            primary_key: Option<FieldId>,
+            ranked: HashSet<FieldId>,
+            displayed: StoredOptionAll<HashSet<FieldId>>,
+            indexed: StoredOptionAll<Vec<FieldId>>,
+            indexed_map: HashMap<FieldId, IndexedPos>,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let index = database.create_index("test").unwrap();
+
+        let mut fields_map = FieldsMap::default();
+        let id = fields_map.insert("id").unwrap();
+        let schema = StoredSchema {
+            fields_map,
+            primary_key: Some(id),
+            ranked: HashSet::new(),
+            displayed: StoredOptionAll::All,
+            indexed: StoredOptionAll::All,
+            indexed_map: vec![(id, IndexedPos(0))].into_iter().collect(),
+        };
+
+        let mut writer = database.main_write_txn().unwrap();
+        index.main.main.put::<_, Str, SerdeBincode<StoredSchema>>(&mut writer, "schema", &schema).unwrap();
+        writer.commit().unwrap();
+
+        database.close().unwrap();
+        drop(index);
+        drop(database);
+
+        fs::write(dir.path().join("FORMAT"), "2").unwrap();
+        assert!(is_outdated(dir.path()).unwrap());
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        assert_eq!(storage_format(dir.path()).unwrap(), STORAGE_FORMAT);
+
+        let index = database.open_index("test").unwrap();
+        let reader = database.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        assert_eq!(schema.primary_key(), Some("id"));
+        assert!(!schema.is_nested("id"));
+    }
+
+    #[test]
+    fn nested_documents_fields() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let database = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        let db = &database;
+
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let update_fn = move |_name: &str, update: ProcessedUpdateResult| {
+            sender.send(update.update_id).unwrap()
+        };
+        let index = database.create_index("test").unwrap();
+
+        database.set_update_callback(Box::new(update_fn));
+
+        let mut writer = db.main_write_txn().unwrap();
+        index.main.put_schema(&mut writer, &Schema::with_primary_key("id")).unwrap();
+        writer.commit().unwrap();
+
+        let mut additions = index.documents_addition();
+        additions.update_document(serde_json::json!({
+            "id": 1,
+            "name": "Marvin",
+            "address": { "city": "Paris", "geo": { "zip": "75001" } },
+            "tags": [{ "kind": "robot" }],
+        }));
+        additions.update_document(serde_json::json!({
+            "id": 2,
+            "name": "Kevin",
+            "address": { "city": "Lyon", "geo": { "zip": "69001" } },
+            "origin.country": "France",
+        }));
+
+        let mut writer = db.update_write_txn().unwrap();
+        let update_id = additions.finalize(&mut writer).unwrap();
+        writer.commit().unwrap();
+
+        // block until the transaction is processed
+        let _ = receiver.iter().find(|id| *id == update_id);
+
+        let update_reader = db.update_read_txn().unwrap();
+        let result = index.update_status(&update_reader, update_id).unwrap();
+        assert_matches!(result, Some(UpdateStatus::Processed { content }) if content.error.is_none());
+        update_reader.abort().unwrap();
+
+        let reader = db.main_read_txn().unwrap();
+        let schema = index.main.schema(&reader).unwrap().unwrap();
+        assert!(schema.contains("address.city"));
+        assert!(schema.contains("address.geo.zip"));
+        assert!(!schema.contains("address"));
+        assert!(schema.is_nested("address.geo.zip"));
+        assert!(!schema.is_nested("origin.country"));
+
+        // the fields are stored flattened and can be nested back
+        let attributes = ["address"].iter().cloned().collect();
+        let document: Option<indexmap::IndexMap<String, serde_json::Value>> = index
+            .document(&reader, Some(&attributes), DocumentId(0))
+            .unwrap();
+        let document = crate::update::unflatten_document(document.unwrap(), &schema);
+        let expected = serde_json::json!({ "city": "Paris", "geo": { "zip": "75001" } });
+        assert_eq!(document.len(), 1);
+        assert_eq!(document.get("address"), Some(&expected));
+
+        // the dotted names sent as is are not nested
+        let attributes = ["origin.country"].iter().cloned().collect();
+        let document: Option<indexmap::IndexMap<String, serde_json::Value>> = index
+            .document(&reader, Some(&attributes), DocumentId(1))
+            .unwrap();
+        let document = crate::update::unflatten_document(document.unwrap(), &schema);
+        assert_eq!(document.get("origin.country"), Some(&serde_json::json!("France")));
+
+        let filter = crate::Filter::parse("address.city = Lyon", &schema).unwrap();
+        assert!(!filter.test(&reader, &index, DocumentId(0)).unwrap());
+        assert!(filter.test(&reader, &index, DocumentId(1)).unwrap());
+    }
 }
@@ -1,10 +1,12 @@
 use hashbrown::HashMap;
 use std::hash::Hash;
+use std::ops::Range;
 
 pub struct DistinctMap<K> {
     inner: HashMap<K, usize>,
     limit: usize,
     len: usize,
+    groups_len: usize,
 }
 
 impl<K: Hash + Eq> DistinctMap<K> {
@@ -13,18 +15,24 @@ impl<K: Hash + Eq> DistinctMap<K> {
             inner: HashMap::new(),
             limit,
             len: 0,
+            groups_len: 0,
         }
     }
 
     pub fn len(&self) -> usize {
         self.len
     }
+
+    pub fn groups_len(&self) -> usize {
+        self.groups_len
+    }
 }
 
 pub struct BufferedDistinctMap<'a, K> {
     internal: &'a mut DistinctMap<K>,
     inner: HashMap<K, usize>,
     len: usize,
+    groups_len: usize,
 }
 
 impl<'a, K: Hash + Eq> BufferedDistinctMap<'a, K> {
@@ -33,6 +41,7 @@ impl<'a, K: Hash + Eq> BufferedDistinctMap<'a, K> {
             internal,
             inner: HashMap::new(),
             len: 0,
+            groups_len: 0,
         }
     }
 
@@ -42,6 +51,9 @@ impl<'a, K: Hash + Eq> BufferedDistinctMap<'a, K> {
         let seen = *internal_seen + *inner_seen;
 
         if seen < self.internal.limit {
+            if seen == 0 {
+                self.groups_len += 1;
+            }
             *inner_seen += 1;
             self.len += 1;
             true
@@ -52,6 +64,7 @@ impl<'a, K: Hash + Eq> BufferedDistinctMap<'a, K> {
 
     pub fn register_without_key(&mut self) -> bool {
         self.len += 1;
+        self.groups_len += 1;
         true
     }
 
@@ -62,12 +75,70 @@ impl<'a, K: Hash + Eq> BufferedDistinctMap<'a, K> {
         }
 
         self.internal.len += self.len;
+        self.internal.groups_len += self.groups_len;
         self.len = 0;
+        self.groups_len = 0;
     }
 
     pub fn len(&self) -> usize {
         self.internal.len() + self.len
     }
+
+    /// The number of distinct keys seen, each document without key being its own group.
+    pub fn groups_len(&self) -> usize {
+        self.internal.groups_len() + self.groups_len
+    }
+}
+
+/// Follows the groups of a requested range of groups, the groups being ranked by their first
+/// registered document, to know whether they contain all their documents kept by the distinct map.
+pub struct GroupsRange<K> {
+    range: Range<usize>,
+    limit: usize,
+    counts: HashMap<K, usize>,
+    incomplete: usize,
+}
+
+impl<K: Hash + Eq> GroupsRange<K> {
+    pub fn new(range: Range<usize>, limit: usize) -> GroupsRange<K> {
+        GroupsRange { range, limit, counts: HashMap::new(), incomplete: 0 }
+    }
+
+    /// Registers a document accepted by the distinct map along with the rank of its
+    /// group when it is the first document of the group, returns whether the group
+    /// of the document is in the requested range.
+    pub fn register(&mut self, key: Option<K>, new_group_rank: Option<usize>) -> bool {
+        match (key, new_group_rank) {
+            // a document without key is a complete group by itself
+            (None, Some(rank)) => self.range.contains(&rank),
+            (Some(key), Some(rank)) => {
+                let in_range = self.range.contains(&rank);
+                if in_range {
+                    self.counts.insert(key, 1);
+                    if self.limit > 1 {
+                        self.incomplete += 1;
+                    }
+                }
+                in_range
+            }
+            (Some(key), None) => match self.counts.get_mut(&key) {
+                Some(count) => {
+                    *count += 1;
+                    if *count == self.limit {
+                        self.incomplete -= 1;
+                    }
+                    true
+                }
+                None => false,
+            },
+            (None, None) => false,
+        }
+    }
+
+    /// Whether all the groups of the requested range have been seen with all their documents.
+    pub fn is_complete(&self, groups_len: usize) -> bool {
+        groups_len >= self.range.end && self.incomplete == 0
+    }
 }
 
 #[cfg(test)]
@@ -99,5 +170,32 @@ mod tests {
 
         buffered.transfert_to_internal();
         assert_eq!(map.len(), 5);
+        assert_eq!(map.groups_len(), 3);
+    }
+
+    #[test]
+    fn groups_range() {
+        let mut map = DistinctMap::new(2);
+        let mut buffered = BufferedDistinctMap::new(&mut map);
+        let mut groups = GroupsRange::new(1..3, 2);
+
+        let mut register = |key: Option<u32>| {
+            let groups_len = buffered.groups_len();
+            let accepted = match key {
+                Some(key) => buffered.register(key),
+                None => buffered.register_without_key(),
+            };
+            let new_group_rank = Some(groups_len).filter(|_| buffered.groups_len() > groups_len);
+            let in_range = accepted && groups.register(key, new_group_rank);
+            (in_range, groups.is_complete(buffered.groups_len()))
+        };
+
+        assert_eq!(register(Some(1)), (false, false));
+        assert_eq!(register(None), (true, false));
+        assert_eq!(register(Some(2)), (true, false));
+        assert_eq!(register(Some(1)), (false, false));
+        assert_eq!(register(Some(1)), (false, false));
+        assert_eq!(register(Some(3)), (false, false));
+        assert_eq!(register(Some(2)), (true, true));
     }
 }
@@ -15,6 +15,8 @@ pub type MResult<T> = Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
+    AliasAlreadyExists(String),
+    AliasNotFound(String),
     Bincode(bincode::Error),
     Deserializer(DeserializerError),
     FacetError(FacetError),
@@ -22,6 +24,10 @@ pub enum Error {
     Fst(fst::Error),
     Heed(heed::Error),
     IndexAlreadyExists,
+    IndexNotFound(String),
+    InvalidAlias(String),
+    InvalidDocumentFieldType(Vec<String>),
+    InvalidSearchAfter,
     Io(io::Error),
     MaxFieldsLimitExceeded,
     MissingDocumentId,
@@ -30,6 +36,7 @@ pub enum Error {
     SchemaMissing,
     SerdeJson(SerdeJsonError),
     Serializer(SerializerError),
+    StorageFormatMismatch(u32),
     VersionMismatch(String),
     WordIndexMissing,
 }
@@ -39,9 +46,15 @@ impl ErrorCode for Error {
         use Error::*;
 
         match self {
+            AliasAlreadyExists(_) => Code::AliasAlreadyExists,
+            AliasNotFound(_) => Code::AliasNotFound,
+            InvalidAlias(_) => Code::InvalidAlias,
+            InvalidDocumentFieldType(_) => Code::InvalidDocumentFieldType,
+            InvalidSearchAfter => Code::BadRequest,
             FacetError(_) => Code::Facet,
             FilterParseError(_) => Code::Filter,
             IndexAlreadyExists => Code::IndexAlreadyExists,
+            IndexNotFound(_) => Code::IndexNotFound,
             MissingPrimaryKey => Code::MissingPrimaryKey,
             MissingDocumentId => Code::MissingDocumentId,
             MaxFieldsLimitExceeded => Code::MaxFieldsLimitExceeded,
@@ -54,6 +67,7 @@ impl ErrorCode for Error {
             | Bincode(_)
             | Serializer(_)
             | Deserializer(_)
+            | StorageFormatMismatch(_)
             | VersionMismatch(_)
             | Io(_) => Code::Internal,
         }
@@ -80,6 +94,12 @@ impl From<PestError<Rule>> for Error {
                 Rule::eq => "field = value",
                 Rule::leq => "field <= value",
                 Rule::geq => "field >= value",
+                Rule::in_ => "field IN [value, ...]",
+                Rule::between => "field value TO value",
+                Rule::exists => "field EXISTS",
+                Rule::is_null => "field IS NULL",
+                Rule::is_empty => "field IS EMPTY",
+                Rule::contains => "field CONTAINS value",
                 Rule::key => "key",
                 _ => "other",
             };
@@ -143,6 +163,8 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Error::*;
         match self {
+            AliasAlreadyExists(alias) => write!(f, "alias {} already exists", alias),
+            AliasNotFound(alias) => write!(f, "alias {} not found", alias),
             Bincode(e) => write!(f, "bincode error; {}", e),
             Deserializer(e) => write!(f, "deserializer error; {}", e),
             FacetError(e) => write!(f, "error processing facet filter: {}", e),
@@ -150,6 +172,10 @@ impl fmt::Display for Error {
             Fst(e) => write!(f, "fst error; {}", e),
             Heed(e) => write!(f, "heed error; {}", e),
             IndexAlreadyExists => write!(f, "index already exists"),
+            IndexNotFound(uid) => write!(f, "index {} not found", uid),
+            InvalidAlias(e) => write!(f, "invalid alias; {}", e),
+            InvalidDocumentFieldType(errors) => write!(f, "documents do not match the declared field types; {}", errors.join("; ")),
+            InvalidSearchAfter => write!(f, "the search after cursor is not a cursor of this search"),
             Io(e) => write!(f, "{}", e),
             MaxFieldsLimitExceeded => write!(f, "maximum number of fields in a document exceeded"),
             MissingDocumentId => write!(f, "document id is missing"),
@@ -158,6 +184,10 @@ impl fmt::Display for Error {
             SchemaMissing => write!(f, "this index does not have a schema"),
             SerdeJson(e) => write!(f, "serde json error; {}", e),
             Serializer(e) => write!(f, "serializer error; {}", e),
+            StorageFormatMismatch(format) => write!(f, "Cannot open database, it was written with the storage format {} \
+                while this engine uses the storage format {}; migrate it with --migrate-db",
+                format,
+                crate::database::STORAGE_FORMAT),
             VersionMismatch(version) => write!(f, "Cannot open database, expected MeiliSearch engine version: {}, current engine version: {}.{}.{}",
                 version,
                 env!("CARGO_PKG_VERSION_MAJOR"),
@@ -217,7 +247,7 @@ impl fmt::Display for FacetError {
             InvalidFormat(found) => write!(f, "invalid facet: {}, facets should be \"facetName:facetValue\"", found),
             AttributeNotFound(attr) => write!(f, "unknown {:?} attribute", attr),
             AttributeNotSet { found, expected } => write!(f, "`{}` is not set as a faceted attribute. available facet attributes: {}", found, expected.join(", ")),
-            InvalidDocumentAttribute(attr) => write!(f, "invalid document attribute {}, accepted types: String and [String]", attr),
+            InvalidDocumentAttribute(attr) => write!(f, "invalid document attribute {}, accepted types: String, Number, Boolean and arrays of them", attr),
             NoAttributesForFaceting => write!(f, "impossible to perform faceted search, no attributes for faceting are set"),
         }
     }
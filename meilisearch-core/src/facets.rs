@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::ops::Deref;
@@ -7,14 +8,17 @@ use cow_utils::CowUtils;
 use either::Either;
 use heed::types::{Str, OwnedType};
 use indexmap::IndexMap;
+use levenshtein_automata::{Distance, DFA};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use meilisearch_schema::{FieldId, Schema};
 use meilisearch_types::DocumentId;
 
+use crate::automaton::{build_prefix_dfa, normalize_str};
 use crate::database::MainT;
 use crate::error::{FacetError, MResult};
-use crate::store::BEU16;
+use crate::store::BEU32;
 
 /// Data structure used to represent a boolean expression in the form of nested arrays.
 /// Values in the outer array are and-ed together, values in the inner arrays are or-ed together.
@@ -77,10 +81,14 @@ impl FacetFilter {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[repr(C)]
 pub struct FacetKey(FieldId, String);
 
+/// The numbers are faceted apart from the strings, under their canonical representation
+/// prefixed by this character, `10` and `10.0` are then the same value, which `"10"` is not.
+const NUMBER_PREFIX: char = '\u{1}';
+
 impl FacetKey {
     pub fn new(field_id: FieldId, value: String) -> Self {
         let value = match value.cow_to_lowercase() {
@@ -90,6 +98,24 @@ impl FacetKey {
         Self(field_id, value)
     }
 
+    /// The key of a number, `FacetKey::new` is the key of its textual representation.
+    pub fn number(field_id: FieldId, number: f64) -> Self {
+        Self(field_id, format!("{}{}", NUMBER_PREFIX, number))
+    }
+
+    /// Returns the number of a key created with `FacetKey::number`.
+    pub fn as_number(&self) -> Option<f64> {
+        self.1.strip_prefix(NUMBER_PREFIX).and_then(facet_number)
+    }
+
+    /// Returns the key of the number that the value of this key represents, if any.
+    pub fn number_key(&self) -> Option<Self> {
+        match self.as_number() {
+            Some(_) => None,
+            None => facet_number(&self.1).map(|number| Self::number(self.0, number)),
+        }
+    }
+
     pub fn key(&self) -> FieldId {
         self.0
     }
@@ -104,11 +130,7 @@ impl FacetKey {
         schema: &Schema,
         attributes_for_faceting: &[FieldId],
     ) -> Result<Self, FacetError> {
-        let mut split = s.splitn(2, ':');
-        let key = split
-            .next()
-            .ok_or_else(|| FacetError::InvalidFormat(s.to_string()))?
-            .trim();
+        let (key, value) = split_facet(s)?;
         let field_id = schema
             .id(key)
             .ok_or_else(|| FacetError::AttributeNotFound(key.to_string()))?;
@@ -122,27 +144,38 @@ impl FacetKey {
                     .collect::<Vec<_>>(),
                     key))
         }
-        let value = split
-            .next()
-            .ok_or_else(|| FacetError::InvalidFormat(s.to_string()))?
-            .trim();
-        // unquoting the string if need be:
-        let mut indices = value.char_indices();
-        let value =  match (indices.next(), indices.last()) {
-            (Some((s, '\'')), Some((e, '\''))) |
-            (Some((s, '\"')), Some((e, '\"'))) => value[s + 1..e].to_string(),
-            _ => value.to_string(),
-        };
         Ok(Self::new(field_id, value))
     }
 }
 
+/// Splits a `facetName:facetValue` string into the name of the attribute and its
+/// value, unquoted if need be.
+pub(crate) fn split_facet(s: &str) -> Result<(&str, String), FacetError> {
+    let mut split = s.splitn(2, ':');
+    let key = split
+        .next()
+        .ok_or_else(|| FacetError::InvalidFormat(s.to_string()))?
+        .trim();
+    let value = split
+        .next()
+        .ok_or_else(|| FacetError::InvalidFormat(s.to_string()))?
+        .trim();
+    // unquoting the string if need be:
+    let mut indices = value.char_indices();
+    let value =  match (indices.next(), indices.last()) {
+        (Some((s, '\'')), Some((e, '\''))) |
+        (Some((s, '\"')), Some((e, '\"'))) => value[s + 1..e].to_string(),
+        _ => value.to_string(),
+    };
+    Ok((key, value))
+}
+
 impl<'a> heed::BytesEncode<'a> for FacetKey {
     type EItem = FacetKey;
 
     fn bytes_encode(item: &'a Self::EItem) -> Option<Cow<'a, [u8]>> {
-        let mut buffer = Vec::with_capacity(2 + item.1.len());
-        let id = BEU16::new(item.key().into());
+        let mut buffer = Vec::with_capacity(4 + item.1.len());
+        let id = BEU32::new(item.key().into());
         let id_bytes = OwnedType::bytes_encode(&id)?;
         let value_bytes = Str::bytes_encode(item.value())?;
         buffer.extend_from_slice(id_bytes.as_ref());
@@ -155,27 +188,153 @@ impl<'a> heed::BytesDecode<'a> for FacetKey {
     type DItem = FacetKey;
 
     fn bytes_decode(bytes: &'a [u8]) -> Option<Self::DItem> {
-        let (id_bytes, value_bytes) = bytes.split_at(2);
-        let id = OwnedType::<BEU16>::bytes_decode(id_bytes)?;
+        let (id_bytes, value_bytes) = bytes.split_at(4);
+        let id = OwnedType::<BEU32>::bytes_decode(id_bytes)?;
         let id = id.get().into();
         let string = Str::bytes_decode(&value_bytes)?;
         Some(FacetKey(id, string.to_string()))
     }
 }
 
+/// A bucket of numbers for which to count the documents, `0-50` is a range from 0 included
+/// to 50 excluded and `100+` contains all the numbers greater or equal to 100.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetRange {
+    pub name: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FacetRange {
+    pub fn parse(s: &str) -> Option<FacetRange> {
+        let name = s.trim().to_string();
+        let (min, max) = match name.strip_suffix('+') {
+            Some(min) => (Some(min.trim().parse().ok()?), None),
+            None => {
+                // the first character is skipped as it can be the sign of the lower bound
+                let first_len = name.chars().next()?.len_utf8();
+                let separator = first_len + name[first_len..].find('-')?;
+                let min: f64 = name[..separator].trim().parse().ok()?;
+                let max: f64 = name[separator + 1..].trim().parse().ok()?;
+                if min > max {
+                    return None;
+                }
+                (Some(min), Some(max))
+            }
+        };
+        Some(FacetRange { name, min, max })
+    }
+
+    pub fn contains(&self, number: f64) -> bool {
+        self.min.map_or(true, |min| number >= min) && self.max.map_or(true, |max| number < max)
+    }
+}
+
+/// The smallest and the biggest numbers of a facet in the matching documents.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FacetStats {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Parses the textual representation of a facet value into a number.
+pub fn facet_number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// The order in which the values of a facet distribution are returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetSort {
+    /// the values contained in the most documents first
+    Count,
+    /// the values in alphanumerical order
+    Alpha,
+}
+
+impl Default for FacetSort {
+    fn default() -> FacetSort {
+        FacetSort::Count
+    }
+}
+
+/// How the values of the facets distribution are returned.
+#[derive(Debug, Clone, Default)]
+pub struct FacetDistributionOptions {
+    /// the maximum number of values returned for each facet
+    pub max_values: Option<usize>,
+    pub sort_by: FacetSort,
+    /// the facets whose values are paths, associated with the separator of their levels
+    pub hierarchies: HashMap<String, String>,
+}
+
+/// Orders the counted values of a facet and keeps the first ones, numbers are ordered by
+/// value and strings regardless of their case when ordered alphanumerically.
+pub fn sort_facet_values(counts: HashMap<String, usize>, options: &FacetDistributionOptions) -> IndexMap<String, usize> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    let alpha = |a: &str, b: &str| match (facet_number(a), facet_number(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cow_to_lowercase().cmp(&b.cow_to_lowercase()).then_with(|| a.cmp(b)),
+    };
+    match options.sort_by {
+        FacetSort::Count => counts.sort_unstable_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| alpha(a, b))
+        }),
+        FacetSort::Alpha => counts.sort_unstable_by(|(a, _), (b, _)| alpha(a, b)),
+    }
+    if let Some(max_values) = options.max_values {
+        counts.truncate(max_values);
+    }
+    counts.into_iter().collect()
+}
+
+/// A prefix and typo tolerant query on the values of a facet, a value matches when the
+/// query matches its beginning or the beginning of one of its words.
+pub struct FacetQuery {
+    dfa: Option<DFA>,
+}
+
+impl FacetQuery {
+    pub fn new(query: &str) -> FacetQuery {
+        let query = normalize_str(query.trim());
+        let dfa = if query.is_empty() { None } else { Some(build_prefix_dfa(&query)) };
+        FacetQuery { dfa }
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        let dfa = match &self.dfa {
+            Some(dfa) => dfa,
+            None => return true,
+        };
+        let value = normalize_str(value);
+        let word_starts = value
+            .match_indices(|c: char| !c.is_alphanumeric())
+            .map(|(i, separator)| i + separator.len());
+        std::iter::once(0).chain(word_starts).any(|start| match dfa.eval(&value[start..]) {
+            Distance::Exact(_) => true,
+            Distance::AtLeast(_) => false,
+        })
+    }
+}
+
 pub fn add_to_facet_map(
     facet_map: &mut HashMap<FacetKey, (String, Vec<DocumentId>)>,
     field_id: FieldId,
     value: Value,
     document_id: DocumentId,
 ) -> Result<(), FacetError> {
-    let value = match value {
-        Value::String(s) => s,
+    let (key, value) = match value {
+        Value::String(s) => (FacetKey::new(field_id, s.clone()), s),
+        // numbers are faceted by value and booleans by their textual representation
+        Value::Number(n) => match n.as_f64() {
+            Some(number) => (FacetKey::number(field_id, number), number.to_string()),
+            None => return Err(FacetError::InvalidDocumentAttribute(n.to_string())),
+        },
+        Value::Bool(b) => (FacetKey::new(field_id, b.to_string()), b.to_string()),
         // ignore null
         Value::Null => return Ok(()),
         value => return Err(FacetError::InvalidDocumentAttribute(value.to_string())),
     };
-    let key = FacetKey::new(field_id, value.clone());
     facet_map.entry(key).or_insert_with(|| (value, Vec::new())).1.push(document_id);
     Ok(())
 }
@@ -283,6 +442,60 @@ mod test {
         assert!(FacetKey::from_str("toto:12", &schema, &facet_list).is_err());
     }
 
+    #[test]
+    fn test_facet_range() {
+        let range = FacetRange::parse("0-50").unwrap();
+        assert_eq!((range.min, range.max), (Some(0.0), Some(50.0)));
+        assert!(range.contains(0.0) && range.contains(49.9) && !range.contains(50.0));
+
+        let range = FacetRange::parse("-10.5-0").unwrap();
+        assert_eq!((range.min, range.max), (Some(-10.5), Some(0.0)));
+
+        let range = FacetRange::parse("100+").unwrap();
+        assert_eq!((range.min, range.max), (Some(100.0), None));
+        assert!(range.contains(1e9) && !range.contains(99.0));
+
+        assert!(FacetRange::parse("50-0").is_none());
+        assert!(FacetRange::parse("cheap").is_none());
+        assert!(FacetRange::parse("10").is_none());
+        assert!(FacetRange::parse("+").is_none());
+    }
+
+    #[test]
+    fn test_sort_facet_values() {
+        let counts: HashMap<_, _> = vec![("b", 2), ("A", 1), ("c", 3), ("a", 3)]
+            .into_iter()
+            .map(|(v, c)| (v.to_string(), c))
+            .collect();
+
+        let options = FacetDistributionOptions::default();
+        let sorted = sort_facet_values(counts.clone(), &options);
+        assert_eq!(sorted.keys().collect::<Vec<_>>(), ["a", "c", "b", "A"]);
+
+        let options = FacetDistributionOptions { max_values: Some(2), sort_by: FacetSort::Alpha, ..Default::default() };
+        let sorted = sort_facet_values(counts, &options);
+        assert_eq!(sorted.into_iter().collect::<Vec<_>>(), [("A".to_string(), 1), ("a".to_string(), 3)]);
+
+        let counts = vec![("10".to_string(), 1), ("9".to_string(), 1)].into_iter().collect();
+        let options = FacetDistributionOptions { sort_by: FacetSort::Alpha, ..Default::default() };
+        assert_eq!(sort_facet_values(counts, &options).keys().collect::<Vec<_>>(), ["9", "10"]);
+    }
+
+    #[test]
+    fn test_facet_query() {
+        let query = FacetQuery::new("sams");
+        assert!(query.matches("Samsung"));
+        assert!(query.matches("Galaxy by Samsung"));
+        assert!(!query.matches("Apple"));
+
+        // long enough queries tolerate typos
+        let query = FacetQuery::new("samsnug");
+        assert!(query.matches("Samsung Electronics"));
+
+        let query = FacetQuery::new("  ");
+        assert!(query.matches("Apple"));
+    }
+
     #[test]
     fn test_parse_facet_array() {
         use either::Either::{Left, Right};
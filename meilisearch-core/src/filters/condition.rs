@@ -1,16 +1,22 @@
+use std::borrow::Cow;
 use std::str::FromStr;
 use std::cmp::Ordering;
+use std::ops::{Bound, Deref};
 
-use crate::error::Error;
-use crate::{store::Index, DocumentId, MainT};
+use crate::error::{Error, FacetError};
+use crate::facets::{split_facet, FacetKey};
+use crate::store::{FilterKey, Index};
+use crate::{DocumentId, MainT, MResult};
 use heed::RoTxn;
-use meilisearch_schema::{FieldId, Schema};
+use sdset::{Set, SetBuf, SetOperation};
+use meilisearch_schema::{FieldId, FieldType, Schema};
 use pest::error::{Error as PestError, ErrorVariant};
 use pest::iterators::Pair;
 use serde_json::{Value, Number};
 use super::parser::Rule;
+use crate::update::{parse_date, parse_date_expression};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 enum ConditionType {
     Greater,
     Less,
@@ -22,32 +28,36 @@ enum ConditionType {
 
 /// We need to infer type when the filter is constructed
 /// and match every possible types it can be parsed into.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct ConditionValue<'a> {
-    string: &'a str,
+    string: Cow<'a, str>,
     boolean: Option<bool>,
-    number: Option<Number>
+    number: Option<Number>,
+    /// The timestamp of the value when it is a date or a relative date like `now-7d`.
+    date: Option<i64>,
 }
 
 impl<'a> ConditionValue<'a> {
     pub fn new(value: &Pair<'a, Rule>) -> Self {
         match value.as_rule() {
-            Rule::string | Rule::word => {
-                let string =  value.as_str();
-                let boolean = match value.as_str() {
-                    "true" => Some(true),
-                    "false" => Some(false),
-                    _ => None,
-                };
-                let number = Number::from_str(value.as_str()).ok();
-                ConditionValue { string, boolean, number }
-            },
+            Rule::string | Rule::word => Self::from_string(Cow::Borrowed(value.as_str())),
             _ => unreachable!(),
         }
     }
 
+    fn from_string(string: Cow<'a, str>) -> Self {
+        let boolean = match string.as_ref() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        let number = Number::from_str(&string).ok();
+        let date = parse_date_expression(&string);
+        ConditionValue { string, boolean, number, date }
+    }
+
     pub fn as_str(&self) -> &str {
-        self.string
+        &self.string
     }
 
     pub fn as_number(&self) -> Option<&Number> {
@@ -57,31 +67,88 @@ impl<'a> ConditionValue<'a> {
     pub fn as_bool(&self) -> Option<bool> {
         self.boolean
     }
+
+    /// Checks that the value can be compared with a field of the declared type,
+    /// instead of being guessed the value is then only interpreted with this type.
+    fn check_type(&mut self, field_type: &FieldType) -> Result<(), String> {
+        match field_type.element_type() {
+            FieldType::Number if self.number.is_none() => Err(format!("`{}` is not a number", self.string)),
+            FieldType::Boolean if self.boolean.is_none() => Err(format!("`{}` is not a boolean", self.string)),
+            FieldType::Date if self.date.is_none() => Err(format!("`{}` is not a date", self.string)),
+            FieldType::Number | FieldType::Boolean => {
+                self.date = None;
+                Ok(())
+            },
+            FieldType::String => {
+                self.number = None;
+                self.boolean = None;
+                self.date = None;
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
 }
 
-#[derive(Debug)]
+/// The operation applied to the values of the attribute of a condition.
+#[derive(Debug, Clone)]
+enum Operator<'a> {
+    /// `=`, `!=`, `<`, `>`, `<=` and `>=`.
+    Compare(ConditionType, ConditionValue<'a>),
+    /// `color IN [red, blue]`, matches any of the values.
+    In(Vec<ConditionValue<'a>>),
+    /// `price 10 TO 20`, both bounds are inclusive.
+    Between(ConditionValue<'a>, ConditionValue<'a>),
+    /// `title CONTAINS pro`, a case insensitive substring match.
+    Contains(ConditionValue<'a>),
+    /// `discount EXISTS`, the attribute is in the document, even when null.
+    Exists,
+    /// `discount IS NULL`.
+    IsNull,
+    /// `description IS EMPTY`, an empty string, array or object.
+    IsEmpty,
+}
+
+#[derive(Debug, Clone)]
 pub struct Condition<'a> {
     field: FieldId,
-    condition: ConditionType,
-    value: ConditionValue<'a>
+    operator: Operator<'a>,
+}
+
+fn custom_error(message: String, pair: &Pair<Rule>) -> Error {
+    PestError::new_from_span(ErrorVariant::CustomError { message }, pair.as_span()).into()
+}
+
+fn get_field(schema: &Schema, key: &Pair<Rule>) -> Result<FieldId, Error> {
+    schema.id(key.as_str()).ok_or_else(|| custom_error(
+        format!(
+            "attribute `{}` not found, available attributes are: {}",
+            key.as_str(),
+            schema.names().collect::<Vec<_>>().join(", ")
+        ),
+        key,
+    ))
+}
+
+fn get_value<'a>(schema: &Schema, field: FieldId, key: &Pair<Rule>, pair: &Pair<'a, Rule>) -> Result<ConditionValue<'a>, Error> {
+    let mut value = ConditionValue::new(pair);
+
+    if let Some(field_type) = schema.field_type(field) {
+        value.check_type(field_type).map_err(|message| custom_error(
+            format!("{}, attribute `{}` is declared as {}", message, key.as_str(), field_type),
+            pair,
+        ))?;
+    }
+
+    Ok(value)
 }
 
 fn get_field_value<'a>(schema: &Schema, pair: Pair<'a, Rule>) -> Result<(FieldId, ConditionValue<'a>), Error> {
     let mut items = pair.into_inner();
-    // lexing ensures that we at least have a key
+    // lexing ensures that we at least have a key and a value
     let key = items.next().unwrap();
-    let field = schema
-        .id(key.as_str())
-        .ok_or_else(|| PestError::new_from_span(
-                ErrorVariant::CustomError {
-                    message: format!(
-                                 "attribute `{}` not found, available attributes are: {}",
-                                 key.as_str(),
-                                 schema.names().collect::<Vec<_>>().join(", ")
-                             ),
-                },
-                key.as_span()))?;
-    let value = ConditionValue::new(&items.next().unwrap());
+    let field = get_field(schema, &key)?;
+    let value = get_value(schema, field, &key, &items.next().unwrap())?;
     Ok((field, value))
 }
 
@@ -98,58 +165,239 @@ fn compare_numbers(lhs: &Number, rhs: &Number) -> Option<Ordering> {
 }
 
 impl<'a> Condition<'a> {
-    pub fn less(
+    pub fn field(&self) -> FieldId {
+        self.field
+    }
+
+    fn compare(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
+        condition: ConditionType,
     ) -> Result<Self, Error> {
         let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::Less;
-        Ok(Self { field, condition, value })
+        Ok(Self { field, operator: Operator::Compare(condition, value) })
+    }
+
+    pub fn less(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        Self::compare(item, schema, ConditionType::Less)
     }
 
     pub fn greater(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
     ) -> Result<Self, Error> {
-        let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::Greater;
-        Ok(Self { field, condition, value })
+        Self::compare(item, schema, ConditionType::Greater)
     }
 
     pub fn neq(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
     ) -> Result<Self, Error> {
-        let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::NotEqual;
-        Ok(Self { field, condition, value })
+        Self::compare(item, schema, ConditionType::NotEqual)
     }
 
     pub fn geq(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
     ) -> Result<Self, Error> {
-        let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::GreaterEqual;
-        Ok(Self { field, condition, value })
+        Self::compare(item, schema, ConditionType::GreaterEqual)
     }
 
     pub fn leq(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
     ) -> Result<Self, Error> {
-        let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::LessEqual;
-        Ok(Self { field, condition, value })
+        Self::compare(item, schema, ConditionType::LessEqual)
     }
 
     pub fn eq(
         item: Pair<'a, Rule>,
         schema: &'a Schema,
     ) -> Result<Self, Error> {
-        let (field, value) = get_field_value(schema, item)?;
-        let condition = ConditionType::Equal;
-        Ok(Self { field, condition, value })
+        Self::compare(item, schema, ConditionType::Equal)
+    }
+
+    pub fn in_(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let mut items = item.into_inner();
+        let key = items.next().unwrap();
+        let field = get_field(schema, &key)?;
+        let values = items
+            .map(|pair| get_value(schema, field, &key, &pair))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { field, operator: Operator::In(values) })
+    }
+
+    pub fn between(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let mut items = item.into_inner();
+        let key = items.next().unwrap();
+        let field = get_field(schema, &key)?;
+        let (from, to) = (items.next().unwrap(), items.next().unwrap());
+
+        let from_value = get_value(schema, field, &key, &from)?;
+        let to_value = get_value(schema, field, &key, &to)?;
+        if from_value.as_number().is_none() && from_value.date.is_none() {
+            return Err(custom_error(format!("`{}` is not a number nor a date", from.as_str()), &from));
+        }
+        if to_value.as_number().is_none() && to_value.date.is_none() {
+            return Err(custom_error(format!("`{}` is not a number nor a date", to.as_str()), &to));
+        }
+
+        Ok(Self { field, operator: Operator::Between(from_value, to_value) })
+    }
+
+    pub fn contains(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let mut items = item.into_inner();
+        let key = items.next().unwrap();
+        let field = get_field(schema, &key)?;
+
+        if let Some(field_type) = schema.field_type(field) {
+            if field_type.element_type() != &FieldType::String {
+                let message = format!("CONTAINS can only be used on strings, attribute `{}` is declared as {}", key.as_str(), field_type);
+                return Err(custom_error(message, &key));
+            }
+        }
+
+        let value = ConditionValue::new(&items.next().unwrap());
+        Ok(Self { field, operator: Operator::Contains(value) })
+    }
+
+    pub fn exists(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let field = get_field(schema, &item.into_inner().next().unwrap())?;
+        Ok(Self { field, operator: Operator::Exists })
+    }
+
+    pub fn is_null(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let field = get_field(schema, &item.into_inner().next().unwrap())?;
+        Ok(Self { field, operator: Operator::IsNull })
+    }
+
+    pub fn is_empty(
+        item: Pair<'a, Rule>,
+        schema: &'a Schema,
+    ) -> Result<Self, Error> {
+        let field = get_field(schema, &item.into_inner().next().unwrap())?;
+        Ok(Self { field, operator: Operator::IsEmpty })
+    }
+
+    /// A condition written as `attribute:value` in the array syntax, an equality.
+    pub fn from_facet(s: &str, schema: &'a Schema) -> Result<Self, Error> {
+        let (key, value) = split_facet(s)?;
+        let field = schema.id(key).ok_or_else(|| FacetError::AttributeNotFound(key.to_string()))?;
+
+        let mut value = ConditionValue::from_string(Cow::Owned(value));
+        if let Some(field_type) = schema.field_type(field) {
+            value.check_type(field_type).map_err(|message| FacetError::ParsingError(
+                format!("{}, attribute `{}` is declared as {}", message, key, field_type),
+            ))?;
+        }
+
+        Ok(Self { field, operator: Operator::Compare(ConditionType::Equal, value) })
+    }
+
+    /// Computes the documents matching the condition with the filters store, or with the
+    /// facets store for the attributes for faceting, returns `None` when the condition
+    /// can only be evaluated document by document.
+    pub fn docids(
+        &self,
+        reader: &RoTxn<MainT>,
+        index: &Index,
+        filterable_attributes: &Set<FieldId>,
+        attributes_for_faceting: &Set<FieldId>,
+    ) -> MResult<Option<SetBuf<DocumentId>>> {
+        if filterable_attributes.binary_search(&self.field).is_err() {
+            if attributes_for_faceting.binary_search(&self.field).is_ok() {
+                return self.facet_docids(reader, index);
+            }
+            return Ok(None);
+        }
+
+        match &self.operator {
+            Operator::Compare(ConditionType::Equal, value) => value.equal_docids(reader, index, self.field),
+            Operator::Compare(ConditionType::NotEqual, _) => Ok(None),
+            Operator::Compare(condition, value) => {
+                if value.date.is_some() {
+                    return Ok(None);
+                }
+                let number = match value.as_number().and_then(Number::as_f64) {
+                    Some(number) => number,
+                    // only numbers can be greater or lower than a value that is not a number
+                    None => return Ok(Some(SetBuf::default())),
+                };
+                let range = match condition {
+                    ConditionType::Greater => (Bound::Excluded(number), Bound::Unbounded),
+                    ConditionType::GreaterEqual => (Bound::Included(number), Bound::Unbounded),
+                    ConditionType::Less => (Bound::Unbounded, Bound::Excluded(number)),
+                    _ => (Bound::Unbounded, Bound::Included(number)),
+                };
+                Ok(Some(index.filters.number_range_document_ids(reader, self.field, range)?))
+            },
+            Operator::In(values) => {
+                let mut sets = Vec::with_capacity(values.len());
+                for value in values {
+                    match value.equal_docids(reader, index, self.field)? {
+                        Some(docids) => sets.push(docids),
+                        None => return Ok(None),
+                    }
+                }
+                let sets = sets.iter().map(Deref::deref).collect();
+                Ok(Some(sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf()))
+            },
+            Operator::Between(from, to) => {
+                if from.date.is_some() || to.date.is_some() {
+                    return Ok(None);
+                }
+                match (from.as_number().and_then(Number::as_f64), to.as_number().and_then(Number::as_f64)) {
+                    (Some(from), Some(to)) => {
+                        let range = (Bound::Included(from), Bound::Included(to));
+                        Ok(Some(index.filters.number_range_document_ids(reader, self.field, range)?))
+                    },
+                    _ => Ok(None),
+                }
+            },
+            Operator::Contains(_) | Operator::Exists | Operator::IsNull | Operator::IsEmpty => Ok(None),
+        }
+    }
+
+    /// The facets store can only be used for the equalities with values that are neither
+    /// numbers nor dates, which are compared by value document by document.
+    fn facet_docids(&self, reader: &RoTxn<MainT>, index: &Index) -> MResult<Option<SetBuf<DocumentId>>> {
+        let values = match &self.operator {
+            Operator::Compare(ConditionType::Equal, value) => std::slice::from_ref(value),
+            Operator::In(values) => values.as_slice(),
+            _ => return Ok(None),
+        };
+
+        let mut sets = Vec::with_capacity(values.len());
+        for value in values {
+            if value.number.is_some() || value.date.is_some() {
+                return Ok(None);
+            }
+            let key = FacetKey::new(self.field, value.as_str().to_string());
+            if let Some((_, docids)) = index.facets.facet_document_ids(reader, &key)? {
+                sets.push(docids);
+            }
+        }
+
+        let sets = sets.iter().map(Deref::deref).collect();
+        Ok(Some(sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf()))
     }
 
     pub fn test(
@@ -158,41 +406,100 @@ impl<'a> Condition<'a> {
         index: &Index,
         document_id: DocumentId,
     ) -> Result<bool, Error> {
-        match index.document_attribute::<Value>(reader, document_id, self.field)? {
-            Some(Value::Array(values)) => Ok(values.iter().any(|v| self.match_value(Some(v)))),
-            other => Ok(self.match_value(other.as_ref())),
-        }
+        let value = index.document_attribute::<Value>(reader, document_id, self.field)?;
+
+        // these operators apply to the value as a whole and not to each value of an array
+        let result = match (&self.operator, value) {
+            (Operator::Exists, value) => value.is_some(),
+            (Operator::IsNull, value) => value == Some(Value::Null),
+            (Operator::IsEmpty, Some(Value::String(s))) => s.is_empty(),
+            (Operator::IsEmpty, Some(Value::Array(values))) => values.is_empty(),
+            (Operator::IsEmpty, Some(Value::Object(object))) => object.is_empty(),
+            (Operator::IsEmpty, _) => false,
+            (_, value) => self.match_value(value.as_ref()),
+        };
+
+        Ok(result)
     }
 
     fn match_value(&self, value: Option<&Value>) -> bool {
+        if let Some(Value::Array(values)) = value {
+            return values.iter().any(|v| self.match_value(Some(v)));
+        }
+
+        match &self.operator {
+            Operator::Compare(condition, expected) => expected.matches(condition, value),
+            Operator::In(values) => values.iter().any(|expected| expected.matches(&ConditionType::Equal, value)),
+            Operator::Between(from, to) => {
+                from.matches(&ConditionType::GreaterEqual, value) && to.matches(&ConditionType::LessEqual, value)
+            },
+            Operator::Contains(expected) => match value {
+                Some(Value::String(s)) => s.to_lowercase().contains(&expected.as_str().to_lowercase()),
+                _ => false,
+            },
+            Operator::Exists | Operator::IsNull | Operator::IsEmpty => false,
+        }
+    }
+}
+
+impl ConditionValue<'_> {
+    /// The documents in which a value of the field is equal to this value,
+    /// the strings, numbers and booleans it can be interpreted as are looked up.
+    fn equal_docids(&self, reader: &RoTxn<MainT>, index: &Index, field: FieldId) -> MResult<Option<SetBuf<DocumentId>>> {
+        // dates are compared with all the date formats, this is not something the store can do
+        if self.date.is_some() {
+            return Ok(None);
+        }
+
+        let mut keys = vec![FilterKey::string(field, self.as_str())];
+        if let Some(boolean) = self.as_bool() {
+            keys.push(FilterKey::boolean(field, boolean));
+        }
+
+        let mut sets = Vec::new();
+        for key in &keys {
+            if let Some(docids) = index.filters.document_ids(reader, key)? {
+                sets.push(docids.into_owned());
+            }
+        }
+        if let Some(number) = self.as_number().and_then(Number::as_f64) {
+            let range = (Bound::Included(number), Bound::Included(number));
+            sets.push(index.filters.number_range_document_ids(reader, field, range)?);
+        }
+
+        let sets = sets.iter().map(Deref::deref).collect();
+        Ok(Some(sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf()))
+    }
+
+    /// Whether the value of a document satisfies the condition compared to this value.
+    fn matches(&self, condition: &ConditionType, value: Option<&Value>) -> bool {
+        // dates are compared as timestamps, the other values are compared as usual
+        if let (Some(date), Some(value)) = (self.date, value) {
+            if let Some(value) = parse_date(value) {
+                return condition.match_ordering(value.cmp(&date));
+            }
+        }
+
         match value {
             Some(Value::String(s)) => {
-                let value = self.value.as_str();
-                match self.condition {
+                let value = self.as_str();
+                match condition {
                     ConditionType::Equal => unicase::eq(value, &s),
                     ConditionType::NotEqual => !unicase::eq(value, &s),
                     _ => false
                 }
             },
-            Some(Value::Number(n)) => { 
-                if let Some(value) = self.value.as_number() {
+            Some(Value::Number(n)) => {
+                if let Some(value) = self.as_number() {
                     if let Some(ord) = compare_numbers(&n, value) {
-                        let res =  match self.condition {
-                            ConditionType::Equal => ord == Ordering::Equal,
-                            ConditionType::NotEqual => ord != Ordering::Equal,
-                            ConditionType::GreaterEqual => ord != Ordering::Less,
-                            ConditionType::LessEqual => ord != Ordering::Greater,
-                            ConditionType::Greater => ord == Ordering::Greater,
-                            ConditionType::Less => ord == Ordering::Less,
-                        };
-                        return res
-                    } 
-                } 
+                        return condition.match_ordering(ord)
+                    }
+                }
                 false
             },
             Some(Value::Bool(b)) => {
-                if let Some(value) = self.value.as_bool() {
-                    let res = match self.condition {
+                if let Some(value) = self.as_bool() {
+                    let res = match condition {
                         ConditionType::Equal => *b == value,
                         ConditionType::NotEqual => *b != value,
                         _ => false
@@ -203,7 +510,20 @@ impl<'a> Condition<'a> {
             },
             // if field is not supported (or not found), all values are different from it,
             // so != should always return true in this case.
-            _ => self.condition == ConditionType::NotEqual,
+            _ => *condition == ConditionType::NotEqual,
+        }
+    }
+}
+
+impl ConditionType {
+    fn match_ordering(&self, ord: Ordering) -> bool {
+        match self {
+            ConditionType::Equal => ord == Ordering::Equal,
+            ConditionType::NotEqual => ord != Ordering::Equal,
+            ConditionType::GreaterEqual => ord != Ordering::Less,
+            ConditionType::LessEqual => ord != Ordering::Greater,
+            ConditionType::Greater => ord == Ordering::Greater,
+            ConditionType::Less => ord == Ordering::Less,
         }
     }
 }
@@ -273,4 +593,74 @@ mod test {
         assert_eq!(Some(Ordering::Greater), compare_numbers(&n1, &n2));
         assert_eq!(Some( Ordering::Less ), compare_numbers(&n2, &n1));
     }
+
+    fn date_condition(condition: ConditionType, string: &str) -> Condition {
+        let value = ConditionValue {
+            string: Cow::Borrowed(string),
+            boolean: None,
+            number: Number::from_str(string).ok(),
+            date: parse_date_expression(string),
+        };
+        Condition { field: FieldId(0), operator: Operator::Compare(condition, value) }
+    }
+
+    #[test]
+    fn test_date_comp() {
+        let condition = date_condition(ConditionType::Greater, "2024-01-01");
+        assert!(condition.match_value(Some(&Value::from("2024-03-01T12:00:00Z"))));
+        assert!(!condition.match_value(Some(&Value::from("2023-12-31T23:59:59+00:00"))));
+        assert!(condition.match_value(Some(&Value::from(vec!["2020-01-01", "2025-01-01"]))));
+
+        let condition = date_condition(ConditionType::Equal, "2024-01-01");
+        assert!(condition.match_value(Some(&Value::from("2024-01-01T00:00:00Z"))));
+
+        // relative dates are computed from the current time
+        let now = chrono::Utc::now();
+        let condition = date_condition(ConditionType::Greater, "now-7d");
+        assert!(condition.match_value(Some(&Value::from((now - chrono::Duration::days(1)).to_rfc3339()))));
+        assert!(!condition.match_value(Some(&Value::from((now - chrono::Duration::days(8)).to_rfc3339()))));
+
+        // the values that are not dates are compared as usual
+        let condition = date_condition(ConditionType::Equal, "2024-01-01");
+        assert!(!condition.match_value(Some(&Value::from("tomorrow"))));
+
+        assert!(parse_date_expression("now+2h").is_some());
+        assert!(parse_date_expression("now-7x").is_none());
+        assert!(parse_date_expression("nowhere").is_none());
+    }
+
+    fn value(string: &str) -> ConditionValue {
+        ConditionValue {
+            string: Cow::Borrowed(string),
+            boolean: None,
+            number: Number::from_str(string).ok(),
+            date: parse_date_expression(string),
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        let condition = Condition { field: FieldId(0), operator: Operator::In(vec![value("red"), value("blue")]) };
+        assert!(condition.match_value(Some(&Value::from("Red"))));
+        assert!(condition.match_value(Some(&Value::from(vec!["green", "blue"]))));
+        assert!(!condition.match_value(Some(&Value::from("green"))));
+        assert!(!condition.match_value(None));
+
+        let condition = Condition { field: FieldId(0), operator: Operator::Between(value("10"), value("20")) };
+        assert!(condition.match_value(Some(&Value::from(10))));
+        assert!(condition.match_value(Some(&Value::from(15.5))));
+        assert!(condition.match_value(Some(&Value::from(20))));
+        assert!(!condition.match_value(Some(&Value::from(21))));
+        assert!(!condition.match_value(Some(&Value::from("15"))));
+
+        let condition = Condition { field: FieldId(0), operator: Operator::Between(value("2024-01-01"), value("2024-12-31")) };
+        assert!(condition.match_value(Some(&Value::from("2024-06-01"))));
+        assert!(!condition.match_value(Some(&Value::from("2025-06-01"))));
+
+        let condition = Condition { field: FieldId(0), operator: Operator::Contains(value("pro")) };
+        assert!(condition.match_value(Some(&Value::from("MacBook Pro"))));
+        assert!(condition.match_value(Some(&Value::from(vec!["air", "professional"]))));
+        assert!(!condition.match_value(Some(&Value::from("MacBook Air"))));
+        assert!(!condition.match_value(Some(&Value::from(12))));
+    }
 }
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use meilisearch_schema::FieldId;
+use meilisearch_types::DocumentId;
+use serde_json::Value;
+
+use crate::database::MainT;
+use crate::store::FilterKey;
+use crate::MResult;
+
+pub type FilterMap = HashMap<FilterKey, Vec<DocumentId>>;
+
+fn add_to_filter_map(
+    filter_map: &mut FilterMap,
+    field_id: FieldId,
+    value: &Value,
+    document_id: DocumentId,
+) {
+    let key = match value {
+        Value::String(s) => FilterKey::string(field_id, s),
+        Value::Number(n) => match n.as_f64() {
+            Some(n) => FilterKey::number(field_id, n),
+            None => return,
+        },
+        Value::Bool(b) => FilterKey::boolean(field_id, *b),
+        Value::Array(values) => {
+            for value in values {
+                add_to_filter_map(filter_map, field_id, value, document_id);
+            }
+            return;
+        },
+        // null and objects can only be filtered document by document
+        Value::Null | Value::Object(_) => return,
+    };
+    filter_map.entry(key).or_insert_with(Vec::new).push(document_id);
+}
+
+/// Collects the values of the filterable attributes of the given documents.
+pub fn filter_map_from_docids(
+    rtxn: &heed::RoTxn<MainT>,
+    index: &crate::Index,
+    document_ids: &[DocumentId],
+    filterable_attributes: &[FieldId],
+) -> MResult<FilterMap> {
+    let mut filter_map = HashMap::new();
+    for document_id in document_ids {
+        for result in index.documents_fields.document_fields(rtxn, *document_id)? {
+            let (field_id, bytes) = result?;
+            if filterable_attributes.contains(&field_id) {
+                let value: Value = serde_json::from_slice(bytes)?;
+                add_to_filter_map(&mut filter_map, field_id, &value, *document_id);
+            }
+        }
+    }
+    Ok(filter_map)
+}
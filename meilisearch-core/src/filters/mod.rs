@@ -1,22 +1,26 @@
 mod parser;
 mod condition;
+mod index;
 
 pub(crate) use parser::Rule;
+pub(crate) use index::filter_map_from_docids;
 
 use std::ops::Not;
 
 use condition::Condition;
-use crate::error::Error;
-use crate::{DocumentId, MainT, store::Index};
+use crate::error::{Error, FacetError};
+use crate::{DocumentId, MainT, MResult, store::Index};
 use heed::RoTxn;
-use meilisearch_schema::Schema;
+use meilisearch_schema::{FieldId, Schema};
 use parser::{PREC_CLIMBER, FilterParser};
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
+use sdset::{Set, SetBuf, SetOperation};
+use serde_json::Value;
 
 type FilterResult<'a> = Result<Filter<'a>, Error>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Filter<'a> {
     Condition(Condition<'a>),
     Or(Box<Self>, Box<Self>),
@@ -25,7 +29,12 @@ pub enum Filter<'a> {
 }
 
 impl<'a> Filter<'a> {
+    /// Parses a filter expression, or the array syntax of the facet filters where
+    /// `[["color:red", "color:blue"], "brand:apple"]` is `(color = red OR color = blue) AND brand = apple`.
     pub fn parse(expr: &'a str, schema: &'a Schema) -> FilterResult<'a> {
+        if expr.trim_start().starts_with('[') {
+            return Self::parse_array(expr, schema);
+        }
         let mut lexed = FilterParser::parse(Rule::prgm, expr)?;
         Self::build(lexed.next().unwrap().into_inner(), schema)
     }
@@ -49,6 +58,136 @@ impl<'a> Filter<'a> {
         }
     }
 
+    /// Computes the documents matching the filter with the filters and facets stores, returns
+    /// `None` when a condition is neither on a filterable attribute nor on a facet or can only be
+    /// evaluated document by document, the filter must then be tested on each document with `Filter::test`.
+    pub fn docids(
+        &self,
+        reader: &RoTxn<MainT>,
+        index: &Index,
+        filterable_attributes: &Set<FieldId>,
+        attributes_for_faceting: &Set<FieldId>,
+    ) -> MResult<Option<SetBuf<DocumentId>>> {
+        use Filter::*;
+        let docids = match self {
+            Condition(c) => return c.docids(reader, index, filterable_attributes, attributes_for_faceting),
+            Or(lhs, rhs) | And(lhs, rhs) => {
+                let lhs = match lhs.docids(reader, index, filterable_attributes, attributes_for_faceting)? {
+                    Some(docids) => docids,
+                    None => return Ok(None),
+                };
+                let rhs = match rhs.docids(reader, index, filterable_attributes, attributes_for_faceting)? {
+                    Some(docids) => docids,
+                    None => return Ok(None),
+                };
+                let op = sdset::duo::OpBuilder::new(lhs.as_set(), rhs.as_set());
+                match self {
+                    Or(..) => op.union().into_set_buf(),
+                    _ => op.intersection().into_set_buf(),
+                }
+            },
+            Not(op) => match op.docids(reader, index, filterable_attributes, attributes_for_faceting)? {
+                Some(docids) => {
+                    let all_docids = index.main.internal_docids(reader)?;
+                    sdset::duo::OpBuilder::new(all_docids.as_ref(), docids.as_set()).difference().into_set_buf()
+                },
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(docids))
+    }
+
+    /// Splits the filter in the documents matching its top level conjunctions that can be computed
+    /// with the filters and facets stores, `None` when none can, and the filter made of the other
+    /// conjunctions, `None` when none remains, that must be tested on each document with `Filter::test`.
+    pub fn split_docids(
+        &self,
+        reader: &RoTxn<MainT>,
+        index: &Index,
+        filterable_attributes: &Set<FieldId>,
+        attributes_for_faceting: &Set<FieldId>,
+    ) -> MResult<(Option<SetBuf<DocumentId>>, Option<Filter<'a>>)> {
+        match self {
+            Filter::And(lhs, rhs) => {
+                let (lhs_docids, lhs_rest) = lhs.split_docids(reader, index, filterable_attributes, attributes_for_faceting)?;
+                let (rhs_docids, rhs_rest) = rhs.split_docids(reader, index, filterable_attributes, attributes_for_faceting)?;
+                let docids = match (lhs_docids, rhs_docids) {
+                    (Some(lhs), Some(rhs)) => {
+                        Some(sdset::duo::OpBuilder::new(lhs.as_set(), rhs.as_set()).intersection().into_set_buf())
+                    },
+                    (lhs, rhs) => lhs.or(rhs),
+                };
+                let rest = match (lhs_rest, rhs_rest) {
+                    (Some(lhs), Some(rhs)) => Some(Filter::And(Box::new(lhs), Box::new(rhs))),
+                    (lhs, rhs) => lhs.or(rhs),
+                };
+                Ok((docids, rest))
+            },
+            filter => match filter.docids(reader, index, filterable_attributes, attributes_for_faceting)? {
+                Some(docids) => Ok((Some(docids), None)),
+                None => Ok((None, Some(filter.clone()))),
+            },
+        }
+    }
+
+    /// Returns the filter without its top level conjunctions that only apply to the given
+    /// attribute, or `None` when nothing remains. A disjunctive facet is counted on this filter.
+    pub fn without_attribute(&self, field: FieldId) -> Option<Filter<'a>> {
+        match self {
+            Filter::And(lhs, rhs) => match (lhs.without_attribute(field), rhs.without_attribute(field)) {
+                (Some(lhs), Some(rhs)) => Some(Filter::And(Box::new(lhs), Box::new(rhs))),
+                (lhs, rhs) => lhs.or(rhs),
+            },
+            filter if filter.only_on_attribute(field) => None,
+            filter => Some(filter.clone()),
+        }
+    }
+
+    fn only_on_attribute(&self, field: FieldId) -> bool {
+        match self {
+            Filter::Condition(c) => c.field() == field,
+            Filter::Or(lhs, rhs) | Filter::And(lhs, rhs) => {
+                lhs.only_on_attribute(field) && rhs.only_on_attribute(field)
+            },
+            Filter::Not(op) => op.only_on_attribute(field),
+        }
+    }
+
+    fn parse_array(expr: &str, schema: &'a Schema) -> FilterResult<'a> {
+        let array = match serde_json::from_str(expr).map_err(|e| FacetError::ParsingError(e.to_string()))? {
+            Value::Array(array) => array,
+            bad_value => return Err(FacetError::unexpected_token(&["Array"], bad_value).into()),
+        };
+
+        let mut filter = None;
+        for item in array {
+            let item = match item {
+                Value::String(s) => Filter::Condition(Condition::from_facet(&s, schema)?),
+                Value::Array(values) => {
+                    let mut or = None;
+                    for value in values {
+                        let condition = match value {
+                            Value::String(s) => Filter::Condition(Condition::from_facet(&s, schema)?),
+                            bad_value => return Err(FacetError::unexpected_token(&["String"], bad_value).into()),
+                        };
+                        or = Some(match or {
+                            Some(lhs) => Filter::Or(Box::new(lhs), Box::new(condition)),
+                            None => condition,
+                        });
+                    }
+                    or.ok_or(FacetError::EmptyArray)?
+                },
+                bad_value => return Err(FacetError::unexpected_token(&["String", "[String]"], bad_value).into()),
+            };
+            filter = Some(match filter {
+                Some(lhs) => Filter::And(Box::new(lhs), Box::new(item)),
+                None => item,
+            });
+        }
+
+        Ok(filter.ok_or(FacetError::EmptyArray)?)
+    }
+
     fn build(expression: Pairs<'a, Rule>, schema: &'a Schema) -> FilterResult<'a> {
         PREC_CLIMBER.climb(
             expression,
@@ -59,6 +198,12 @@ impl<'a> Filter<'a> {
                 Rule::neq => Ok(Filter::Condition(Condition::neq(pair, schema)?)),
                 Rule::geq => Ok(Filter::Condition(Condition::geq(pair, schema)?)),
                 Rule::leq => Ok(Filter::Condition(Condition::leq(pair, schema)?)),
+                Rule::in_ => Ok(Filter::Condition(Condition::in_(pair, schema)?)),
+                Rule::between => Ok(Filter::Condition(Condition::between(pair, schema)?)),
+                Rule::exists => Ok(Filter::Condition(Condition::exists(pair, schema)?)),
+                Rule::is_null => Ok(Filter::Condition(Condition::is_null(pair, schema)?)),
+                Rule::is_empty => Ok(Filter::Condition(Condition::is_empty(pair, schema)?)),
+                Rule::contains => Ok(Filter::Condition(Condition::contains(pair, schema)?)),
                 Rule::prgm => Self::build(pair.into_inner(), schema),
                 Rule::term => Self::build(pair.into_inner(), schema),
                 Rule::not => Ok(Filter::Not(Box::new(Self::build(
@@ -97,6 +242,13 @@ mod test {
         assert!(FilterParser::parse(Rule::prgm, "hello world=1").is_err());
         assert!(FilterParser::parse(Rule::prgm, "").is_err());
         assert!(FilterParser::parse(Rule::prgm, r#"((((((hello=world)))))"#).is_err());
+        assert!(FilterParser::parse(Rule::prgm, "color IN []").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "color IN [red, ]").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "color IN red").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "price 10 TO").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "price TO 20").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "discount IS").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "title CONTAINS").is_err());
     }
 
     #[test]
@@ -123,5 +275,54 @@ mod test {
         assert!(FilterParser::parse(Rule::prgm, r#"'foo bar' <= 10"#).is_ok());
         assert!(FilterParser::parse(Rule::prgm, r#"'foo bar' != 10"#).is_ok());
         assert!(FilterParser::parse(Rule::prgm, r#"bar != 10"#).is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "color IN [red]").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, r#"color IN [red, "light blue"]"#).is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "price 10 TO 20").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "release_date now-7d TO now").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "discount EXISTS AND NOT discount IS NULL").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "description IS EMPTY").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, r#"title CONTAINS "pro" OR title CONTAINS max"#).is_ok());
+    }
+
+    #[test]
+    fn array_syntax() {
+        let mut schema = Schema::new();
+        schema.insert_and_index("color").unwrap();
+        schema.insert_and_index("brand").unwrap();
+
+        let filter = Filter::parse(r#"[["color:red", "color:'light blue'"], "brand:apple"]"#, &schema).unwrap();
+        match filter {
+            Filter::And(lhs, rhs) => {
+                assert_matches!(*lhs, Filter::Or(..));
+                assert_matches!(*rhs, Filter::Condition(_));
+            },
+            _ => panic!("expected a conjunction"),
+        }
+
+        assert!(Filter::parse(r#"["color:red"]"#, &schema).is_ok());
+        assert!(Filter::parse("[]", &schema).is_err());
+        assert!(Filter::parse("[[]]", &schema).is_err());
+        assert!(Filter::parse("[12]", &schema).is_err());
+        assert!(Filter::parse(r#"["red"]"#, &schema).is_err());
+        assert!(Filter::parse(r#"["size:12"]"#, &schema).is_err());
+        assert!(Filter::parse(r#"["color:red""#, &schema).is_err());
+    }
+
+    #[test]
+    fn without_attribute() {
+        let mut schema = Schema::new();
+        schema.insert_and_index("color").unwrap();
+        schema.insert_and_index("brand").unwrap();
+        let color = schema.id("color").unwrap();
+
+        let filter = Filter::parse("(color = red OR color = blue) AND brand = apple", &schema).unwrap();
+        assert_matches!(filter.without_attribute(color), Some(Filter::Condition(_)));
+
+        // a disjunction with another attribute is kept as a whole
+        let filter = Filter::parse("color = red OR brand = apple", &schema).unwrap();
+        assert_matches!(filter.without_attribute(color), Some(Filter::Or(..)));
+
+        let filter = Filter::parse("NOT color = red", &schema).unwrap();
+        assert!(filter.without_attribute(color).is_none());
     }
 }
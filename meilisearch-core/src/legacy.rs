@@ -0,0 +1,273 @@
+//! A reader of the databases written with the first storage format, where the field ids,
+//! the indexed positions and the positions of the words were stored on 16 bits.
+//!
+//! Only the schema, the settings and the documents of the indexes can be read, this is
+//! what is needed to dump these databases and import them in a database of the current format.
+
+use std::iter::Peekable;
+use std::path::Path;
+
+use heed::types::{ByteSlice, OwnedType, Str};
+use indexmap::IndexMap;
+use meilisearch_schema::{FieldId, Schema};
+use zerocopy::{AsBytes, FromBytes};
+
+use crate::database::{database_version, MainReader, MainT};
+use crate::store::{Main, Synonyms, BEU32};
+use crate::{DatabaseOptions, Error, MResult};
+
+type BEU16 = zerocopy::U16<byteorder::BigEndian>;
+
+#[derive(Debug, Copy, Clone, AsBytes, FromBytes)]
+#[repr(C)]
+struct LegacyDocumentFieldStoredKey {
+    docid: BEU32,
+    field_id: BEU16,
+}
+
+pub struct LegacyDatabase {
+    env: heed::Env,
+    indexes_store: heed::Database<Str, Str>,
+    database_version: (u32, u32, u32),
+}
+
+impl LegacyDatabase {
+    /// Opens the database written with the first storage format at `path`.
+    pub fn open(path: impl AsRef<Path>, options: &DatabaseOptions) -> MResult<LegacyDatabase> {
+        let path = path.as_ref();
+        let database_version = database_version(path)?
+            .ok_or_else(|| Error::VersionMismatch("<0.12.0".to_string()))?;
+
+        let env = heed::EnvOpenOptions::new()
+            .map_size(options.main_map_size)
+            .max_dbs(3000)
+            .open(path.join("main"))?;
+
+        let indexes_store = env.create_database::<Str, Str>(Some("indexes"))?;
+
+        Ok(LegacyDatabase { env, indexes_store, database_version })
+    }
+
+    pub fn main_read_txn(&self) -> MResult<MainReader> {
+        Ok(self.env.typed_read_txn::<MainT>()?)
+    }
+
+    pub fn indexes_uids(&self, reader: &MainReader) -> MResult<Vec<String>> {
+        let mut uids = Vec::new();
+        for result in self.indexes_store.iter(reader)? {
+            let (index_uid, _) = result?;
+            uids.push(index_uid.to_owned());
+        }
+        Ok(uids)
+    }
+
+    /// Opens the index with the given uid, returns `None` if it doesn't exist
+    /// or has not all the databases.
+    pub fn open_index(&self, reader: &MainReader, uid: &str) -> MResult<Option<LegacyIndex>> {
+        let store_name = match self.indexes_store.get(reader, uid)? {
+            Some(store_name) if !store_name.is_empty() => store_name,
+            Some(_) => uid,
+            None => return Ok(None),
+        };
+
+        let main = match self.env.open_poly_database(Some(&format!("store-{}", store_name)))? {
+            Some(main) => main,
+            None => return Ok(None),
+        };
+        let documents_fields = match self.env.open_database(Some(&format!("store-{}-documents-fields", store_name)))? {
+            Some(documents_fields) => documents_fields,
+            None => return Ok(None),
+        };
+        let synonyms = match self.env.open_database(Some(&format!("store-{}-synonyms", store_name)))? {
+            Some(synonyms) => synonyms,
+            None => return Ok(None),
+        };
+
+        Ok(Some(LegacyIndex {
+            main: Main { main },
+            synonyms: Synonyms { synonyms },
+            documents_fields,
+        }))
+    }
+
+    pub fn version(&self) -> (u32, u32, u32) { self.database_version }
+}
+
+/// An index of a database written with the first storage format, the stores that did
+/// not change of layout are read with the current readers.
+pub struct LegacyIndex {
+    pub main: Main,
+    pub synonyms: Synonyms,
+    documents_fields: heed::Database<OwnedType<LegacyDocumentFieldStoredKey>, ByteSlice>,
+}
+
+impl LegacyIndex {
+    pub fn schema(&self, reader: &MainReader) -> MResult<Option<Schema>> {
+        self.main.legacy_schema(reader)
+    }
+
+    pub fn distinct_attribute(&self, reader: &MainReader) -> MResult<Option<FieldId>> {
+        self.main.legacy_distinct_attribute(reader)
+    }
+
+    pub fn attributes_for_faceting(&self, reader: &MainReader) -> MResult<Vec<FieldId>> {
+        self.main.legacy_attributes_for_faceting(reader)
+    }
+
+    pub fn filterable_attributes(&self, reader: &MainReader) -> MResult<Vec<FieldId>> {
+        self.main.legacy_filterable_attributes(reader)
+    }
+
+    /// Iterates over the stored documents in the order of their internal ids,
+    /// the fields are named with the given schema.
+    pub fn documents<'txn>(&self, reader: &'txn MainReader, schema: Schema) -> MResult<LegacyDocumentsIter<'txn>> {
+        let iter = self.documents_fields.iter(reader)?.peekable();
+        Ok(LegacyDocumentsIter { iter, schema })
+    }
+}
+
+pub struct LegacyDocumentsIter<'txn> {
+    iter: Peekable<heed::RoIter<'txn, OwnedType<LegacyDocumentFieldStoredKey>, ByteSlice>>,
+    schema: Schema,
+}
+
+impl Iterator for LegacyDocumentsIter<'_> {
+    type Item = MResult<IndexMap<String, serde_json::Value>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut document = IndexMap::new();
+        let mut current = None;
+
+        loop {
+            // the fields of a document are contiguous, stop at the first field of the next one
+            match self.iter.peek() {
+                Some(Ok((key, _))) if current.map_or(false, |docid| docid != key.docid.get()) => break,
+                Some(Err(_)) if current.is_some() => break,
+                Some(_) => (),
+                None => break,
+            }
+
+            let (key, bytes) = match self.iter.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => break,
+            };
+            current = Some(key.docid.get());
+
+            let field_id = FieldId(u32::from(key.field_id.get()));
+            if let Some(name) = self.schema.name(field_id) {
+                match serde_json::from_slice(bytes) {
+                    Ok(value) => { document.insert(name.to_string(), value); },
+                    Err(e) => return Some(Err(e.into())),
+                }
+            }
+        }
+
+        current.map(|_| Ok(document))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+
+    use heed::types::SerdeBincode;
+    use serde::Serialize;
+
+    // the layout of the schema written with the first storage format
+    #[derive(Serialize)]
+    struct StoredFieldsMap {
+        name_map: HashMap<String, u16>,
+        id_map: HashMap<u16, String>,
+        next_id: u16,
+    }
+
+    #[derive(Serialize)]
+    #[allow(dead_code)]
+    enum StoredOptionAll<T> {
+        All,
+        Some(T),
+        None,
+    }
+
+    #[derive(Serialize)]
+    struct StoredSchema {
+        fields_map: StoredFieldsMap,
+        primary_key: Option<u16>,
+        ranked: HashSet<u16>,
+        displayed: StoredOptionAll<HashSet<u16>>,
+        indexed: StoredOptionAll<Vec<u16>>,
+        indexed_map: HashMap<u16, u16>,
+    }
+
+    fn stored_key(docid: u32, field_id: u16) -> LegacyDocumentFieldStoredKey {
+        LegacyDocumentFieldStoredKey { docid: BEU32::new(docid), field_id: BEU16::new(field_id) }
+    }
+
+    #[test]
+    fn read_legacy_database() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("VERSION"), "0.15.0").unwrap();
+
+        let fields = vec![(0, "id"), (1, "title"), (2, "genre")];
+        let schema = StoredSchema {
+            fields_map: StoredFieldsMap {
+                name_map: fields.iter().map(|&(id, name)| (name.to_string(), id)).collect(),
+                id_map: fields.iter().map(|&(id, name)| (id, name.to_string())).collect(),
+                next_id: 3,
+            },
+            primary_key: Some(0),
+            ranked: HashSet::new(),
+            displayed: StoredOptionAll::All,
+            indexed: StoredOptionAll::Some(vec![1, 0]),
+            indexed_map: vec![(1, 0), (0, 1), (2, 2)].into_iter().collect(),
+        };
+
+        {
+            let main_path = dir.path().join("main");
+            fs::create_dir_all(&main_path).unwrap();
+            let env = heed::EnvOpenOptions::new().max_dbs(3000).open(main_path).unwrap();
+
+            let mut writer = env.typed_write_txn::<MainT>().unwrap();
+            let indexes = env.create_database::<Str, Str>(Some("indexes")).unwrap();
+            indexes.put(&mut writer, "movies", "").unwrap();
+
+            let main = env.create_poly_database(Some("store-movies")).unwrap();
+            main.put::<_, Str, SerdeBincode<StoredSchema>>(&mut writer, "schema", &schema).unwrap();
+            main.put::<_, Str, OwnedType<u16>>(&mut writer, "distinct-attribute", &2).unwrap();
+
+            let documents_fields = env
+                .create_database::<OwnedType<LegacyDocumentFieldStoredKey>, ByteSlice>(Some("store-movies-documents-fields"))
+                .unwrap();
+            documents_fields.put(&mut writer, &stored_key(0, 0), b"1").unwrap();
+            documents_fields.put(&mut writer, &stored_key(0, 1), br#""Carol""#).unwrap();
+            documents_fields.put(&mut writer, &stored_key(70_000, 0), b"2").unwrap();
+            documents_fields.put(&mut writer, &stored_key(70_000, 2), br#""drama""#).unwrap();
+
+            env.create_database::<ByteSlice, ByteSlice>(Some("store-movies-synonyms")).unwrap();
+            writer.commit().unwrap();
+        }
+
+        let db = LegacyDatabase::open(dir.path(), &DatabaseOptions::default()).unwrap();
+        assert_eq!(db.version(), (0, 15, 0));
+
+        let reader = db.main_read_txn().unwrap();
+        assert_eq!(db.indexes_uids(&reader).unwrap(), vec!["movies".to_string()]);
+        assert!(db.open_index(&reader, "books").unwrap().is_none());
+
+        let index = db.open_index(&reader, "movies").unwrap().unwrap();
+        let schema = index.schema(&reader).unwrap().unwrap();
+        assert_eq!(schema.primary_key(), Some("id"));
+        assert_eq!(schema.indexed_name(), vec!["title", "id"]);
+        assert_eq!(index.distinct_attribute(&reader).unwrap(), schema.id("genre"));
+
+        let documents: Vec<_> = index.documents(&reader, schema).unwrap().map(Result::unwrap).collect();
+        assert_eq!(documents, vec![
+            vec![("id".to_string(), serde_json::json!(1)), ("title".to_string(), serde_json::json!("Carol"))].into_iter().collect(),
+            vec![("id".to_string(), serde_json::json!(2)), ("genre".to_string(), serde_json::json!("drama"))].into_iter().collect::<IndexMap<_, _>>(),
+        ]);
+    }
+}
@@ -12,6 +12,7 @@ mod database;
 mod distinct_map;
 mod error;
 mod filters;
+mod legacy;
 mod levenshtein;
 mod number;
 mod query_builder;
@@ -29,13 +30,15 @@ pub mod store;
 pub mod update;
 
 pub use self::database::{BoxUpdateFn, Database, DatabaseOptions, MainT, UpdateT, MainWriter, MainReader, UpdateWriter, UpdateReader};
+pub use self::database::{database_version, is_outdated, storage_format, STORAGE_FORMAT};
 pub use self::error::{Error, HeedError, FstError, MResult, pest_error, FacetError};
 pub use self::filters::Filter;
+pub use self::legacy::{LegacyDatabase, LegacyDocumentsIter, LegacyIndex};
 pub use self::number::{Number, ParseNumberError};
 pub use self::ranked_map::RankedMap;
 pub use self::raw_document::RawDocument;
 pub use self::store::Index;
-pub use self::update::{EnqueuedUpdateResult, ProcessedUpdateResult, UpdateStatus, UpdateType};
+pub use self::update::{EnqueuedUpdateResult, ProcessedUpdateResult, UpdatePriority, UpdateStatus, UpdateType};
 pub use meilisearch_types::{DocIndex, DocumentId, Highlight};
 pub use meilisearch_schema::Schema;
 pub use query_words_mapper::QueryWordsMapper;
@@ -86,9 +89,9 @@ fn highlights_from_raw_document<'a, 'tag, 'txn>(
                     } else {
                         prefix_damerau_levenshtein(query.as_bytes(), input).1
                     };
-                    u16::try_from(len).unwrap_or(u16::max_value())
+                    u32::try_from(len).unwrap_or(u32::max_value())
                 },
-                _ => di.char_length,
+                _ => u32::from(di.char_length),
             };
 
             let attribute = searchable_attrs
@@ -197,6 +200,6 @@ mod tests {
 
     #[test]
     fn docindex_mem_size() {
-        assert_eq!(mem::size_of::<DocIndex>(), 12);
+        assert_eq!(mem::size_of::<DocIndex>(), 18);
     }
 }
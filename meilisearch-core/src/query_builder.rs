@@ -1,5 +1,7 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::mem;
 use std::ops::{Deref, Range};
 use std::time::Duration;
 
@@ -8,23 +10,36 @@ use sdset::{SetOperation, SetBuf, Set};
 
 use meilisearch_schema::FieldId;
 
-use crate::bucket_sort::{bucket_sort, bucket_sort_with_distinct, SortResult, placeholder_document_sort, facet_count};
+use crate::bucket_sort::{bucket_sort, bucket_sort_with_distinct, SortResult, placeholder_document_sort, facet_count, count_hits, count_groups, group_sizes, FacetCountDocids};
+use crate::bucket_sort::{placeholder_ranking_rules, placeholder_document_values, placeholder_rank_cmp, placeholder_cursor_matches};
 use crate::database::MainT;
-use crate::facets::FacetFilter;
-use crate::distinct_map::{DistinctMap, BufferedDistinctMap};
+use crate::facets::{FacetDistributionOptions, FacetFilter, FacetKey, FacetQuery, FacetRange};
+use crate::distinct_map::{DistinctMap, BufferedDistinctMap, GroupsRange};
 use crate::Document;
-use crate::{criterion::Criteria, DocumentId};
-use crate::{reordered_attrs::ReorderedAttrs, store, MResult, MainReader};
+use crate::criterion::{Criteria, SearchAfter};
+use crate::DocumentId;
+use crate::{reordered_attrs::ReorderedAttrs, store, Error, MResult, MainReader};
 
 pub struct QueryBuilder<'c, 'f, 'd, 'i> {
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
     filter: Option<Box<dyn Fn(DocumentId) -> bool + 'f>>,
+    filter_docids: Option<SetBuf<DocumentId>>,
     distinct: Option<(Box<dyn Fn(DocumentId) -> Option<u64> + 'd>, usize)>,
     timeout: Option<Duration>,
     index: &'i store::Index,
     facet_filter: Option<FacetFilter>,
     facets: Option<Vec<(FieldId, String)>>,
+    facet_ranges: HashMap<String, Vec<FacetRange>>,
+    facet_options: FacetDistributionOptions,
+    facet_query: Option<FacetQuery>,
+    disjunctive_facets: bool,
+    disjunctive_docids: HashMap<String, Option<SetBuf<DocumentId>>>,
+    disjunctive_filter_docids: HashMap<FieldId, Option<SetBuf<DocumentId>>>,
+    exhaustive_hits_count: Option<usize>,
+    search_after: Option<SearchAfter>,
+    group_sizes: bool,
+    group_hits: bool,
 }
 
 impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
@@ -42,16 +57,69 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         self.facets = facets;
     }
 
+    /// sets the ranges of numbers for which to return the count instead of the values of a facet
+    pub fn set_facet_ranges(&mut self, ranges: HashMap<String, Vec<FacetRange>>) {
+        self.facet_ranges = ranges;
+    }
+
+    /// sets the maximum number of hits to count exactly, after the filter and the distinct rule
+    pub fn set_exhaustive_hits_count(&mut self, cap: Option<usize>) {
+        self.exhaustive_hits_count = cap;
+    }
+
+    /// sets the cursor of the hit after which the requested range starts, the last hit of the previous page
+    pub fn set_search_after(&mut self, search_after: Option<SearchAfter>) {
+        self.search_after = search_after;
+    }
+
+    /// sets whether the number of hits sharing the distinct value of each document is returned
+    pub fn set_group_sizes(&mut self, group_sizes: bool) {
+        self.group_sizes = group_sizes;
+    }
+
+    /// sets whether the requested range is a range of groups of hits sharing a distinct value,
+    /// all the hits kept by the distinct rule for the groups of the range are returned
+    pub fn set_group_hits(&mut self, group_hits: bool) {
+        self.group_hits = group_hits;
+    }
+
+    /// sets whether each facet is counted without the facet filters on its own attribute
+    pub fn set_disjunctive_facets(&mut self, disjunctive: bool) {
+        self.disjunctive_facets = disjunctive;
+    }
+
+    /// sets how the values of the facets distribution are ordered, limited and grouped in levels
+    pub fn set_facet_distribution_options(&mut self, options: FacetDistributionOptions) {
+        self.facet_options = options;
+    }
+
+    /// sets the query that the counted values of the facets must match, the other
+    /// values are skipped while walking the facets store
+    pub fn set_facet_query(&mut self, query: Option<FacetQuery>) {
+        self.facet_query = query;
+    }
+
     pub fn with_criteria(index: &'i store::Index, criteria: Criteria<'c>) -> Self {
         QueryBuilder {
             criteria,
             searchable_attrs: None,
             filter: None,
+            filter_docids: None,
             distinct: None,
             timeout: None,
             index,
             facet_filter: None,
             facets: None,
+            facet_ranges: HashMap::new(),
+            facet_options: FacetDistributionOptions::default(),
+            facet_query: None,
+            disjunctive_facets: false,
+            disjunctive_docids: HashMap::new(),
+            disjunctive_filter_docids: HashMap::new(),
+            exhaustive_hits_count: None,
+            search_after: None,
+            group_sizes: false,
+            group_hits: false,
         }
     }
 
@@ -62,6 +130,18 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         self.filter = Some(Box::new(function))
     }
 
+    /// restricts the search to the given documents, the documents matching a filter
+    /// that could be computed from the filters store
+    pub fn with_filter_docids(&mut self, docids: SetBuf<DocumentId>) {
+        self.filter_docids = Some(docids)
+    }
+
+    /// restricts the documents on which a disjunctive facet is counted to the documents matching
+    /// the filter without its conditions on the attribute of the facet, `None` when none remains
+    pub fn with_disjunctive_filter_docids(&mut self, field_id: FieldId, docids: Option<SetBuf<DocumentId>>) {
+        self.disjunctive_filter_docids.insert(field_id, docids);
+    }
+
     pub fn with_fetch_timeout(&mut self, timeout: Duration) {
         self.timeout = Some(timeout)
     }
@@ -73,59 +153,77 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         self.distinct = Some((Box::new(function), size))
     }
 
-    pub fn add_searchable_attribute(&mut self, attribute: u16) {
+    pub fn add_searchable_attribute(&mut self, attribute: u32) {
         let reorders = self.searchable_attrs.get_or_insert_with(ReorderedAttrs::new);
         reorders.insert_attribute(attribute);
     }
 
-    /// returns the documents ids associated with a facet filter by computing the union and
-    /// intersection of the document sets
-    fn facets_docids(&self, reader: &MainReader) -> MResult<Option<SetBuf<DocumentId>>> {
-        let facet_docids = match self.facet_filter {
-            Some(ref facets) => {
-                let mut ands = Vec::with_capacity(facets.len());
-                let mut ors = Vec::new();
-                for f in facets.deref() {
-                    match f {
-                        Either::Left(keys) => {
-                            ors.reserve(keys.len());
-                            for key in keys {
-                                let docids = self
-                                    .index
-                                    .facets
-                                    .facet_document_ids(reader, &key)?
-                                    .unwrap_or_default();
-                                ors.push(docids);
-                            }
-                            let sets: Vec<_> = ors.iter().map(|(_, i)| i).map(Cow::deref).collect();
-                            let or_result = sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf();
-                            ands.push(Cow::Owned(or_result));
-                            ors.clear();
-                        }
-                        Either::Right(key) => {
-                            match self.index.facets.facet_document_ids(reader, &key)? {
-                                Some((_name, docids)) => ands.push(docids),
-                                // no candidates for search, early return.
-                                None => return Ok(Some(SetBuf::default())),
-                            }
+    /// returns the documents ids associated with each group of the facet filter, the union of the
+    /// documents of its facets, along with the attribute of the group when all its facets are on it
+    fn facet_groups_docids(&self, reader: &MainReader) -> MResult<Vec<(Option<FieldId>, SetBuf<DocumentId>)>> {
+        let mut groups = Vec::new();
+        if let Some(ref facets) = self.facet_filter {
+            for f in facets.deref() {
+                let keys = match f {
+                    Either::Left(keys) => keys.as_slice(),
+                    Either::Right(key) => std::slice::from_ref(key),
+                };
+                let mut ors = Vec::with_capacity(keys.len());
+                for key in keys {
+                    // a value which is a number also matches the documents with this number
+                    let number_key = key.number_key();
+                    for key in std::iter::once(key).chain(number_key.as_ref()) {
+                        if let Some((_name, docids)) = self.index.facets.facet_document_ids(reader, key)? {
+                            ors.push(docids);
                         }
-                    };
+                    }
                 }
-                let ands: Vec<_> = ands.iter().map(Cow::deref).collect();
-                Some(
-                    sdset::multi::OpBuilder::from_vec(ands)
-                    .intersection()
-                    .into_set_buf(),
-                )
+                let sets: Vec<_> = ors.iter().map(Cow::deref).collect();
+                let docids = sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf();
+                let field = keys.first().map(FacetKey::key).filter(|field| keys.iter().all(|key| key.key() == *field));
+                groups.push((field, docids));
             }
-            None => None,
+        }
+        Ok(groups)
+    }
+
+    /// returns the documents allowed by both the facet filter and the filter docids, the documents
+    /// allowed for each disjunctive facet, without its own facet filters and filter conditions,
+    /// are kept for the facet count
+    fn candidates_docids(&mut self, reader: &MainReader) -> MResult<Option<SetBuf<DocumentId>>> {
+        let groups = self.facet_groups_docids(reader)?;
+        let filter_docids = self.filter_docids.take();
+
+        let intersection = |sets: Vec<&Set<DocumentId>>| match sets.len() {
+            0 => None,
+            1 => Some(sets[0].to_owned()),
+            _ => Some(sdset::multi::OpBuilder::from_vec(sets).intersection().into_set_buf()),
         };
-        Ok(facet_docids)
+
+        if self.disjunctive_facets {
+            for (field_id, field_name) in self.facets.iter().flatten() {
+                let mut sets: Vec<_> = groups
+                    .iter()
+                    .filter(|(field, _)| *field != Some(*field_id))
+                    .map(|(_, docids)| docids.as_set())
+                    .collect();
+                match self.disjunctive_filter_docids.get(field_id) {
+                    Some(docids) => sets.extend(docids.as_deref()),
+                    None => sets.extend(filter_docids.as_deref()),
+                }
+                self.disjunctive_docids.insert(field_name.clone(), intersection(sets));
+            }
+        }
+
+        let mut sets: Vec<_> = groups.iter().map(|(_, docids)| docids.as_set()).collect();
+        sets.extend(filter_docids.as_deref());
+        Ok(intersection(sets))
     }
 
-    fn standard_query(self, reader: &MainReader, query: &str, range: Range<usize>) -> MResult<SortResult> {
-        let facets_docids = match self.facets_docids(reader)? {
-            Some(ids) if ids.is_empty() => return Ok(SortResult::default()),
+    fn standard_query(mut self, reader: &MainReader, query: &str, range: Range<usize>) -> MResult<SortResult> {
+        let facets_docids = match self.candidates_docids(reader)? {
+            // the disjunctive facets can still be counted when no document is allowed
+            Some(ids) if ids.is_empty() && !self.disjunctive_facets => return Ok(SortResult::default()),
             other => other
         };
         // for each field to retrieve the count for, create an HashMap associating the attribute
@@ -143,6 +241,10 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 self.filter,
                 distinct,
                 distinct_size,
+                self.exhaustive_hits_count,
+                self.search_after,
+                self.group_sizes,
+                self.group_hits,
                 self.criteria,
                 self.searchable_attrs,
                 self.index,
@@ -154,6 +256,8 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 facets_docids,
                 facet_count_docids,
                 self.filter,
+                self.exhaustive_hits_count,
+                self.search_after,
                 self.criteria,
                 self.searchable_attrs,
                 self.index,
@@ -161,23 +265,32 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         }
     }
 
-    fn placeholder_query(self, reader: &heed::RoTxn<MainT>, range: Range<usize>) -> MResult<SortResult> {
-        match self.facets_docids(reader)? {
+    fn placeholder_query(mut self, reader: &heed::RoTxn<MainT>, range: Range<usize>) -> MResult<SortResult> {
+        match self.candidates_docids(reader)? {
             Some(docids) => {
                 // We sort the docids from facets according to the criteria set by the user
                 let mut sorted_docids = docids.clone().into_vec();
                 let mut sort_result = match self.index.main.ranked_map(reader)? {
                     Some(ranked_map) => {
                         placeholder_document_sort(&mut sorted_docids, self.index, reader, &ranked_map)?;
-                        self.sort_result_from_docids(&sorted_docids, range)
+                        self.sort_result_from_docids(reader, &sorted_docids, range)?
                     },
                     // if we can't perform a sort, we return documents unordered
-                    None => self.sort_result_from_docids(&docids, range),
+                    None => self.sort_result_from_docids(reader, &docids, range)?,
                 };
 
                 if let Some(f) = self.facet_count_docids(reader)? {
                     sort_result.exhaustive_facets_count = Some(true);
-                    sort_result.facets = Some(facet_count(f, &docids));
+                    // the disjunctive facets are counted on all the documents allowed by the other filters
+                    let all_docids = if self.disjunctive_facets {
+                        self.index.main.internal_docids(reader)?
+                    } else {
+                        Cow::Borrowed(docids.as_set())
+                    };
+                    let (facets, hierarchical_facets, facets_stats) = facet_count(f, &docids, &all_docids);
+                    sort_result.facets = Some(facets);
+                    sort_result.hierarchical_facets = Some(hierarchical_facets);
+                    sort_result.facets_stats = Some(facets_stats);
                 }
 
                 Ok(sort_result)
@@ -186,13 +299,16 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 match self.index.main.sorted_document_ids_cache(reader)? {
                     // build result from cached document ids
                     Some(docids) => {
-                        let mut sort_result = self.sort_result_from_docids(&docids, range);
+                        let mut sort_result = self.sort_result_from_docids(reader, &docids, range)?;
 
                         if let Some(f) = self.facet_count_docids(reader)? {
                             sort_result.exhaustive_facets_count = Some(true);
                             // document ids are not sorted in natural order, we need to construct a new set
                             let document_set = SetBuf::from_dirty(Vec::from(docids));
-                            sort_result.facets = Some(facet_count(f, &document_set));
+                            let (facets, hierarchical_facets, facets_stats) = facet_count(f, &document_set, &document_set);
+                            sort_result.facets = Some(facets);
+                            sort_result.hierarchical_facets = Some(hierarchical_facets);
+                            sort_result.facets_stats = Some(facets_stats);
                         }
 
                         Ok(sort_result)
@@ -204,7 +320,7 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         }
     }
 
-    fn facet_count_docids<'a>(&self, reader: &'a MainReader) -> MResult<Option<HashMap<String, HashMap<String, (&'a str, Cow<'a, Set<DocumentId>>)>>>> {
+    fn facet_count_docids<'a>(&mut self, reader: &'a MainReader) -> MResult<Option<FacetCountDocids<'a>>> {
         match self.facets {
             Some(ref field_ids) => {
                 let mut facet_count_map = HashMap::new();
@@ -212,52 +328,126 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                     let mut key_map = HashMap::new();
                     for pair in self.index.facets.field_document_ids(reader, *field_id)? {
                         let (facet_key, document_ids) = pair?;
-                        let value = facet_key.value();
-                        key_map.insert(value.to_string(), document_ids);
+                        if self.facet_query.as_ref().map_or(true, |query| query.matches(document_ids.0)) {
+                            key_map.insert(facet_key, document_ids);
+                        }
                     }
                     facet_count_map.insert(field_name.clone(), key_map);
                 }
-                Ok(Some(facet_count_map))
+                Ok(Some(FacetCountDocids {
+                    values: facet_count_map,
+                    ranges: self.facet_ranges.clone(),
+                    options: self.facet_options.clone(),
+                    disjunctive: mem::take(&mut self.disjunctive_docids),
+                }))
             }
             None => Ok(None),
         }
     }
 
-    fn sort_result_from_docids(&self, docids: &[DocumentId], range: Range<usize>) -> SortResult {
+    fn sort_result_from_docids(&self, reader: &MainReader, docids: &[DocumentId], range: Range<usize>) -> MResult<SortResult> {
         let mut sort_result = SortResult::default();
-        let mut result = match self.filter {
-            Some(ref filter) => docids
-                .iter()
-                .filter(|item| (filter)(**item))
-                .skip(range.start)
-                .take(range.end - range.start)
-                .map(|&id| Document::from_highlights(id, &[]))
-                .collect::<Vec<_>>(),
-            None => docids
-                .iter()
-                .skip(range.start)
-                .take(range.end - range.start)
-                .map(|&id| Document::from_highlights(id, &[]))
-                .collect::<Vec<_>>(),
+        let filter = self.filter.as_ref().map(|f| f.as_ref() as &dyn Fn(DocumentId) -> bool);
+        let distinct = self.distinct.as_ref().map(|(d, size)| (d.as_ref() as &dyn Fn(DocumentId) -> Option<u64>, *size));
+
+        // the distinct rule is applied to all the hits before the requested range,
+        // which keep at most distinct size hits for each distinct value
+        let mut distinct_map = DistinctMap::new(distinct.map_or(1, |(_, size)| size));
+        let mut distinct_map = BufferedDistinctMap::new(&mut distinct_map);
+        let mut accepted = |id: DocumentId| {
+            filter.map_or(true, |filter| filter(id)) && match distinct.and_then(|(distinct, _)| distinct(id)) {
+                Some(key) => distinct_map.register(key),
+                None => distinct_map.register_without_key(),
+            }
+        };
+
+        let ranking_rules = placeholder_ranking_rules(self.index, reader)?.unwrap_or_default();
+        let ranked_map = self.index.main.ranked_map(reader)?.unwrap_or_default();
+        let values = |id| placeholder_document_values(id, &ranking_rules, &ranked_map);
+
+        // the hits start after the documents ranked like the hit of the search after
+        // cursor, the documents are sorted, the first of them ranked after it is searched.
+        // The distinct values of the previous hits are already seen
+        let hits = match &self.search_after {
+            Some(search_after) => {
+                if !placeholder_cursor_matches(search_after.values(), &ranking_rules) {
+                    return Err(Error::InvalidSearchAfter);
+                }
+                let position = docids.binary_search_by(|&id| {
+                    match placeholder_rank_cmp(&values(id), search_after.values()) {
+                        Some(Ordering::Greater) => Ordering::Greater,
+                        _ => Ordering::Less,
+                    }
+                }).unwrap_or_else(|position| position);
+                if distinct.is_some() {
+                    docids[..position].iter().for_each(|&id| { accepted(id); });
+                }
+                &docids[position..]
+            },
+            None => docids,
         };
 
-        // distinct is set, remove duplicates with disctinct function
-        if let Some((distinct, distinct_size)) = &self.distinct {
-            let mut distinct_map = DistinctMap::new(*distinct_size);
-            let mut distinct_map = BufferedDistinctMap::new(&mut distinct_map);
-            result.retain(|doc| {
-                let id = doc.id;
-                let key = (distinct)(id);
-                match key {
+        let result = if self.group_hits {
+            // the requested range is a range of groups of hits sharing a distinct value,
+            // the hits are read until these groups contain all their hits
+            let mut range_groups = GroupsRange::new(range.clone(), distinct.map_or(1, |(_, size)| size));
+            let mut result = Vec::new();
+            for &id in hits {
+                if range_groups.is_complete(distinct_map.groups_len()) {
+                    break;
+                }
+                if !filter.map_or(true, |filter| filter(id)) {
+                    continue;
+                }
+
+                let key = distinct.and_then(|(distinct, _)| distinct(id));
+                let groups_len = distinct_map.groups_len();
+                let distinct_accepted = match key {
                     Some(key) => distinct_map.register(key),
                     None => distinct_map.register_without_key(),
+                };
+                let new_group_rank = Some(groups_len).filter(|_| distinct_map.groups_len() > groups_len);
+                if distinct_accepted && range_groups.register(key, new_group_rank) {
+                    result.push(Document::from_highlights(id, &[]));
                 }
-            });
+            }
+            result
+        } else {
+            hits.iter()
+                .filter(|&&id| accepted(id))
+                .skip(range.start)
+                .take(range.end - range.start)
+                .map(|&id| Document::from_highlights(id, &[]))
+                .collect::<Vec<_>>()
+        };
+
+        if !self.group_hits && !range.is_empty() && result.len() == range.len() {
+            let last = result[result.len() - 1].id;
+            sort_result.search_after = Some(SearchAfter::new(values(last)));
+        }
+
+        if self.group_sizes {
+            if let Some((distinct, _)) = distinct {
+                sort_result.group_sizes = Some(group_sizes(docids, &result, filter, distinct));
+            }
+        }
+
+        if self.group_hits {
+            if let Some((distinct, _)) = distinct {
+                sort_result.nb_groups = Some(count_groups(docids, filter, distinct));
+            }
         }
 
         sort_result.documents = result;
         sort_result.nb_hits = docids.len();
-        sort_result
+
+        if let Some(cap) = self.exhaustive_hits_count {
+            let (nb_hits, exhaustive) = count_hits(docids, filter, distinct, cap);
+            sort_result.nb_hits = nb_hits;
+            sort_result.exhaustive_nb_hit = exhaustive;
+        }
+
+        Ok(sort_result)
     }
 
     pub fn query(
@@ -322,7 +512,7 @@ mod tests {
         builder.into_set()
     }
 
-    const fn doc_index(document_id: u32, word_index: u16) -> DocIndex {
+    const fn doc_index(document_id: u32, word_index: u32) -> DocIndex {
         DocIndex {
             document_id: DocumentId(document_id),
             attribute: 0,
@@ -332,7 +522,7 @@ mod tests {
         }
     }
 
-    const fn doc_char_index(document_id: u32, word_index: u16, char_index: u16) -> DocIndex {
+    const fn doc_char_index(document_id: u32, word_index: u32, char_index: u32) -> DocIndex {
         DocIndex {
             document_id: DocumentId(document_id),
             attribute: 0,
@@ -396,7 +586,7 @@ mod tests {
 
             let mut words_fst = BTreeSet::new();
             let mut postings_lists = HashMap::new();
-            let mut fields_counts = HashMap::<_, u16>::new();
+            let mut fields_counts = HashMap::<_, u32>::new();
 
             let mut schema = Schema::with_primary_key("id");
 
@@ -1364,6 +1554,66 @@ mod tests {
         assert_matches!(iter.next(), None);
     }
 
+    #[test]
+    fn distinct_range() {
+        let indexes: Vec<_> = (0..6).map(|id| doc_index(id, 0)).collect();
+        let store = TempDatabase::from_iter(vec![("hello", &indexes[..])]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let ids = |range: Range<usize>| {
+            let mut builder = store.query_builder();
+            builder.with_distinct(1, |id| Some(u64::from(id.0 % 3)));
+            let SortResult { documents, .. } = builder.query(&reader, Some("hello"), range).unwrap();
+            documents.into_iter().map(|d| d.id.0).collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(0..20), vec![0, 1, 2]);
+        assert_eq!(ids(0..2), vec![0, 1]);
+        assert_eq!(ids(1..3), vec![1, 2]);
+        assert_eq!(ids(2..2), Vec::<u32>::new());
+        assert_eq!(ids(3..5), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn search_after_cursor() {
+        let indexes: Vec<_> = (0..6).map(|id| doc_index(id, 0)).collect();
+        let store = TempDatabase::from_iter(vec![("hello", &indexes[..])]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let query = |search_after: Option<SearchAfter>, removed: Option<u32>| {
+            let mut builder = store.query_builder();
+            if let Some(removed) = removed {
+                builder.with_filter(move |id| id.0 != removed);
+            }
+            builder.set_search_after(search_after);
+            builder.query(&reader, Some("hello"), 0..2).unwrap()
+        };
+        let ids = |result: &SortResult| result.documents.iter().map(|d| d.id.0).collect::<Vec<_>>();
+
+        let first = query(None, None);
+        assert_eq!(ids(&first), vec![0, 1]);
+        let second = query(first.search_after.clone(), None);
+        assert_eq!(ids(&second), vec![2, 3]);
+
+        // the hit of the cursor does not have to be a hit of the next search
+        let second_without_cursor_hit = query(first.search_after.clone(), Some(1));
+        assert_eq!(ids(&second_without_cursor_hit), vec![2, 3]);
+
+        let third = query(second.search_after, None);
+        assert_eq!(ids(&third), vec![4, 5]);
+        assert!(query(third.search_after, None).documents.is_empty());
+
+        // the cursors are decoded with the version they were encoded with
+        let cursor = first.search_after.unwrap();
+        assert_eq!(SearchAfter::decode(&cursor.encode()).unwrap(), cursor);
+        let unversioned = base64::encode_config(bincode::serialize(&cursor).unwrap(), base64::URL_SAFE_NO_PAD);
+        assert!(SearchAfter::decode(&unversioned).is_err());
+    }
+
     #[test]
     fn harder_phrase_query_splitting() {
         let store = TempDatabase::from_iter(vec![
@@ -515,8 +515,8 @@ pub fn traverse_query_tree<'o, 'txn>(
                     let second = ctx.postings_lists.postings_list(reader, second.as_bytes())?.unwrap_or_default();
 
                     let iter = merge_join_by(first.matches.as_slice(), second.matches.as_slice(), |a, b| {
-                        let x = (a.document_id, a.attribute, (a.word_index as u32) + 1);
-                        let y = (b.document_id, b.attribute, b.word_index as u32);
+                        let x = (a.document_id, a.attribute, a.word_index + 1);
+                        let y = (b.document_id, b.attribute, b.word_index);
                         x.cmp(&y)
                     });
 
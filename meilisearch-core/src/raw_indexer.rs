@@ -12,6 +12,9 @@ use crate::FstSetCow;
 
 const WORD_LENGTH_LIMIT: usize = 80;
 
+/// The default number of words indexed in each attribute of a document.
+pub const DEFAULT_WORD_LIMIT: usize = 1000;
+
 type Word = Vec<u8>; // TODO make it be a SmallVec
 
 pub struct RawIndexer<A> {
@@ -28,7 +31,7 @@ pub struct Indexed<'a> {
 
 impl<A> RawIndexer<A> {
     pub fn new(stop_words: fst::Set<A>) -> RawIndexer<A> {
-        RawIndexer::with_word_limit(stop_words, 1000)
+        RawIndexer::with_word_limit(stop_words, DEFAULT_WORD_LIMIT)
     }
 
     pub fn with_word_limit(stop_words: fst::Set<A>, limit: usize) -> RawIndexer<A> {
@@ -169,8 +172,8 @@ where A: AsRef<[u8]>,
 }
 
 fn token_to_docindex(id: DocumentId, indexed_pos: IndexedPos, token: Token) -> Option<DocIndex> {
-    let word_index = u16::try_from(token.word_index).ok()?;
-    let char_index = u16::try_from(token.char_index).ok()?;
+    let word_index = u32::try_from(token.word_index).ok()?;
+    let char_index = u32::try_from(token.char_index).ok()?;
     let char_length = u16::try_from(token.word.chars().count()).ok()?;
 
     let docindex = DocIndex {
@@ -309,4 +312,23 @@ mod tests {
         assert!(words_doc_indexes.get(&"less".to_owned().into_bytes()).is_some());
         assert!(words_doc_indexes.get(&"more".to_owned().into_bytes()).is_none());
     }
+
+    #[test]
+    fn words_over_index_65535_indexed_with_higher_limit() {
+        let mut indexer = RawIndexer::with_word_limit(fst::Set::default(), 100_000);
+        let indexed_pos = IndexedPos(0);
+        let docid = DocumentId(0);
+        let mut text = String::with_capacity(400_000);
+        for _ in 0..70_000 {
+            text.push_str("less ");
+        }
+        text.push_str("more");
+        indexer.index_text(docid, indexed_pos, &text);
+        let Indexed {
+            words_doc_indexes, ..
+        } = indexer.build();
+        let more = &words_doc_indexes[&"more".to_owned().into_bytes()];
+        assert_eq!({ more[0].word_index }, 70_000);
+        assert_eq!({ more[0].char_index }, 350_000);
+    }
 }
@@ -2,8 +2,8 @@ use std::cmp;
 
 #[derive(Default, Clone)]
 pub struct ReorderedAttrs {
-    reorders: Vec<Option<u16>>,
-    reverse: Vec<u16>,
+    reorders: Vec<Option<u32>>,
+    reverse: Vec<u32>,
 }
 
 impl ReorderedAttrs {
@@ -11,21 +11,21 @@ impl ReorderedAttrs {
         ReorderedAttrs { reorders: Vec::new(), reverse: Vec::new() }
     }
 
-    pub fn insert_attribute(&mut self, attribute: u16) {
+    pub fn insert_attribute(&mut self, attribute: u32) {
         let new_len = cmp::max(attribute as usize + 1, self.reorders.len());
         self.reorders.resize(new_len, None);
-        self.reorders[attribute as usize] = Some(self.reverse.len() as u16);
+        self.reorders[attribute as usize] = Some(self.reverse.len() as u32);
         self.reverse.push(attribute);
     }
 
-    pub fn get(&self, attribute: u16) -> Option<u16> {
+    pub fn get(&self, attribute: u32) -> Option<u32> {
         match self.reorders.get(attribute as usize)? {
             Some(attribute) => Some(*attribute),
             None => None,
         }
     }
 
-    pub fn reverse(&self, attribute: u16) -> Option<u16> {
+    pub fn reverse(&self, attribute: u32) -> Option<u32> {
         self.reverse.get(attribute as usize).copied()
     }
 }
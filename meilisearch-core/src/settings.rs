@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 use std::iter::IntoIterator;
 
+use meilisearch_schema::FieldType;
 use serde::{Deserialize, Deserializer, Serialize};
 use once_cell::sync::Lazy;
 
@@ -30,6 +31,10 @@ pub struct Settings {
     pub synonyms: Option<Option<BTreeMap<String, Vec<String>>>>,
     #[serde(default, deserialize_with = "deserialize_some")]
     pub attributes_for_faceting: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub filterable_attributes: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub field_types: Option<Option<BTreeMap<String, FieldType>>>,
 }
 
 // Any value that is present is considered Some value, including null.
@@ -59,6 +64,8 @@ impl Settings {
             stop_words: settings.stop_words.into(),
             synonyms: settings.synonyms.into(),
             attributes_for_faceting: settings.attributes_for_faceting.into(),
+            filterable_attributes: settings.filterable_attributes.into(),
+            field_types: settings.field_types.into(),
         })
     }
 }
@@ -156,6 +163,7 @@ impl RankingRule {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsUpdate {
     pub ranking_rules: UpdateState<Vec<RankingRule>>,
     pub distinct_attribute: UpdateState<String>,
@@ -165,6 +173,8 @@ pub struct SettingsUpdate {
     pub stop_words: UpdateState<BTreeSet<String>>,
     pub synonyms: UpdateState<BTreeMap<String, Vec<String>>>,
     pub attributes_for_faceting: UpdateState<Vec<String>>,
+    pub filterable_attributes: UpdateState<Vec<String>>,
+    pub field_types: UpdateState<BTreeMap<String, FieldType>>,
 }
 
 impl Default for SettingsUpdate {
@@ -178,6 +188,8 @@ impl Default for SettingsUpdate {
             stop_words: UpdateState::Nothing,
             synonyms: UpdateState::Nothing,
             attributes_for_faceting: UpdateState::Nothing,
+            filterable_attributes: UpdateState::Nothing,
+            field_types: UpdateState::Nothing,
         }
     }
 }
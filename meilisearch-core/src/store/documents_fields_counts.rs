@@ -8,7 +8,7 @@ use crate::MResult;
 
 #[derive(Copy, Clone)]
 pub struct DocumentsFieldsCounts {
-    pub(crate) documents_fields_counts: heed::Database<OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
+    pub(crate) documents_fields_counts: heed::Database<OwnedType<DocumentFieldIndexedKey>, OwnedType<u32>>,
 }
 
 impl DocumentsFieldsCounts {
@@ -17,7 +17,7 @@ impl DocumentsFieldsCounts {
         writer: &mut heed::RwTxn<MainT>,
         document_id: DocumentId,
         attribute: IndexedPos,
-        value: u16,
+        value: u32,
     ) -> ZResult<()> {
         let key = DocumentFieldIndexedKey::new(document_id, attribute);
         self.documents_fields_counts.put(writer, &key, &value)
@@ -42,7 +42,7 @@ impl DocumentsFieldsCounts {
         reader: &heed::RoTxn<MainT>,
         document_id: DocumentId,
         attribute: IndexedPos,
-    ) -> ZResult<Option<u16>> {
+    ) -> ZResult<Option<u32>> {
         let key = DocumentFieldIndexedKey::new(document_id, attribute);
         match self.documents_fields_counts.get(reader, &key)? {
             Some(count) => Ok(Some(count)),
@@ -79,11 +79,11 @@ impl DocumentsFieldsCounts {
 }
 
 pub struct DocumentFieldsCountsIter<'txn> {
-    iter: heed::RoRange<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
+    iter: heed::RoRange<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u32>>,
 }
 
 impl Iterator for DocumentFieldsCountsIter<'_> {
-    type Item = ZResult<(IndexedPos, u16)>;
+    type Item = ZResult<(IndexedPos, u32)>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.iter.next() {
@@ -99,7 +99,7 @@ impl Iterator for DocumentFieldsCountsIter<'_> {
 
 pub struct DocumentsIdsIter<'txn> {
     last_seen_id: Option<DocumentId>,
-    iter: heed::RoIter<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
+    iter: heed::RoIter<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u32>>,
 }
 
 impl Iterator for DocumentsIdsIter<'_> {
@@ -123,11 +123,11 @@ impl Iterator for DocumentsIdsIter<'_> {
 }
 
 pub struct AllDocumentsFieldsCountsIter<'txn> {
-    iter: heed::RoIter<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
+    iter: heed::RoIter<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u32>>,
 }
 
 impl Iterator for AllDocumentsFieldsCountsIter<'_> {
-    type Item = ZResult<(DocumentId, IndexedPos, u16)>;
+    type Item = ZResult<(DocumentId, IndexedPos, u32)>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.iter.next() {
@@ -0,0 +1,155 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::ops::{Bound, Deref};
+
+use cow_utils::CowUtils;
+use heed::{RwTxn, RoTxn, BytesEncode, BytesDecode};
+use sdset::{SetBuf, Set, SetOperation};
+
+use meilisearch_types::DocumentId;
+use meilisearch_schema::FieldId;
+
+use crate::MResult;
+use crate::database::MainT;
+use super::cow_set::CowSet;
+
+/// The value of a filterable attribute, strings are lowercased and numbers
+/// are encoded in a way that keeps them ordered by value once in bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FilterKey {
+    String(FieldId, String),
+    Number(FieldId, u64),
+    Boolean(FieldId, bool),
+}
+
+impl FilterKey {
+    pub fn string(field_id: FieldId, value: &str) -> Self {
+        FilterKey::String(field_id, value.cow_to_lowercase().into_owned())
+    }
+
+    pub fn number(field_id: FieldId, value: f64) -> Self {
+        // -0.0 and 0.0 must be the same key
+        let bits = (value + 0.0).to_bits();
+        let ordered = if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) };
+        FilterKey::Number(field_id, ordered)
+    }
+
+    pub fn boolean(field_id: FieldId, value: bool) -> Self {
+        FilterKey::Boolean(field_id, value)
+    }
+
+    fn field_id(&self) -> FieldId {
+        match self {
+            FilterKey::String(field_id, _) | FilterKey::Number(field_id, _) | FilterKey::Boolean(field_id, _) => *field_id,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            FilterKey::String(..) => 0,
+            FilterKey::Number(..) => 1,
+            FilterKey::Boolean(..) => 2,
+        }
+    }
+}
+
+impl<'a> BytesEncode<'a> for FilterKey {
+    type EItem = FilterKey;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Option<Cow<'a, [u8]>> {
+        let mut buffer = Vec::with_capacity(4 + 1 + 8);
+        buffer.extend_from_slice(&item.field_id().0.to_be_bytes());
+        buffer.push(item.tag());
+        match item {
+            FilterKey::String(_, value) => buffer.extend_from_slice(value.as_bytes()),
+            FilterKey::Number(_, value) => buffer.extend_from_slice(&value.to_be_bytes()),
+            FilterKey::Boolean(_, value) => buffer.push(*value as u8),
+        }
+        Some(Cow::Owned(buffer))
+    }
+}
+
+impl<'a> BytesDecode<'a> for FilterKey {
+    type DItem = FilterKey;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Option<Self::DItem> {
+        let field_id = FieldId(u32::from_be_bytes(bytes.get(0..4)?.try_into().ok()?));
+        let value = bytes.get(5..)?;
+        match bytes.get(4)? {
+            0 => Some(FilterKey::String(field_id, std::str::from_utf8(value).ok()?.to_string())),
+            1 => Some(FilterKey::Number(field_id, u64::from_be_bytes(value.try_into().ok()?))),
+            2 => Some(FilterKey::Boolean(field_id, *value.first()? != 0)),
+            _ => None,
+        }
+    }
+}
+
+/// contains the values of the filterable attributes and the documents they appear in
+#[derive(Clone, Copy)]
+pub struct Filters {
+    pub(crate) filters: heed::Database<FilterKey, CowSet<DocumentId>>,
+}
+
+impl Filters {
+    pub fn document_ids<'txn>(&self, reader: &'txn RoTxn<MainT>, key: &FilterKey) -> MResult<Option<Cow<'txn, Set<DocumentId>>>> {
+        Ok(self.filters.get(reader, key)?)
+    }
+
+    /// Returns the documents in which one of the numbers of the field is in the given range.
+    pub fn number_range_document_ids(
+        &self,
+        reader: &RoTxn<MainT>,
+        field_id: FieldId,
+        range: (Bound<f64>, Bound<f64>),
+    ) -> MResult<SetBuf<DocumentId>> {
+        let key_bound = |bound, unbounded| match bound {
+            Bound::Included(n) => Bound::Included(FilterKey::number(field_id, n)),
+            Bound::Excluded(n) => Bound::Excluded(FilterKey::number(field_id, n)),
+            Bound::Unbounded => Bound::Included(FilterKey::number(field_id, unbounded)),
+        };
+        let range = (key_bound(range.0, f64::NEG_INFINITY), key_bound(range.1, f64::INFINITY));
+
+        let mut sets = Vec::new();
+        for result in self.filters.range(reader, &range)? {
+            let (_, docids) = result?;
+            sets.push(docids);
+        }
+
+        let sets = sets.iter().map(Cow::deref).collect();
+        Ok(sdset::multi::OpBuilder::from_vec(sets).union().into_set_buf())
+    }
+
+    /// Adds the documents to the filterable values they contain.
+    pub fn add(&self, writer: &mut RwTxn<MainT>, filter_map: HashMap<FilterKey, Vec<DocumentId>>) -> MResult<()> {
+        for (key, document_ids) in filter_map {
+            let to_add = SetBuf::from_dirty(document_ids);
+            let new = match self.filters.get(writer, &key)? {
+                Some(old) => sdset::duo::OpBuilder::new(old.as_ref(), to_add.as_set()).union().into_set_buf(),
+                None => to_add,
+            };
+            self.filters.put(writer, &key, new.as_set())?;
+        }
+        Ok(())
+    }
+
+    /// Removes the documents from the filterable values they contain.
+    pub fn remove(&self, writer: &mut RwTxn<MainT>, filter_map: HashMap<FilterKey, Vec<DocumentId>>) -> MResult<()> {
+        for (key, document_ids) in filter_map {
+            if let Some(old) = self.filters.get(writer, &key)? {
+                let to_remove = SetBuf::from_dirty(document_ids);
+                let new = sdset::duo::OpBuilder::new(old.as_ref(), to_remove.as_set()).difference().into_set_buf();
+                if new.is_empty() {
+                    self.filters.delete(writer, &key)?;
+                } else {
+                    self.filters.put(writer, &key, new.as_set())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear(self, writer: &mut heed::RwTxn<MainT>) -> MResult<()> {
+        Ok(self.filters.clear(writer)?)
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 
 use chrono::{DateTime, Utc};
 use heed::types::{ByteSlice, OwnedType, SerdeBincode, Str, CowSlice};
-use meilisearch_schema::{FieldId, Schema};
+use meilisearch_schema::{FieldId, FieldType, LegacySchema, Schema, SchemaV2};
 use meilisearch_types::DocumentId;
 use sdset::Set;
 
@@ -19,6 +19,8 @@ const CUSTOMS_KEY: &str = "customs";
 const DISTINCT_ATTRIBUTE_KEY: &str = "distinct-attribute";
 const EXTERNAL_DOCIDS_KEY: &str = "external-docids";
 const FIELDS_DISTRIBUTION_KEY: &str = "fields-distribution";
+const FIELD_TYPES_KEY: &str = "field-types";
+const FILTERABLE_ATTRIBUTES_KEY: &str = "filterable-attributes";
 const INTERNAL_DOCIDS_KEY: &str = "internal-docids";
 const NAME_KEY: &str = "name";
 const NUMBER_OF_DOCUMENTS_KEY: &str = "number-of-documents";
@@ -178,8 +180,18 @@ impl Main {
         Ok(self.main.put::<_, Str, SerdeBincode<Schema>>(writer, SCHEMA_KEY, schema)?)
     }
 
+    /// Returns the schema along with the types declared for its fields.
     pub fn schema(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Schema>> {
-        Ok(self.main.get::<_, Str, SerdeBincode<Schema>>(reader, SCHEMA_KEY)?)
+        let schema = self.main.get::<_, Str, SerdeBincode<Schema>>(reader, SCHEMA_KEY)?;
+        match schema {
+            Some(mut schema) => {
+                if let Some(field_types) = self.field_types(reader)? {
+                    schema.set_field_types(field_types);
+                }
+                Ok(Some(schema))
+            }
+            None => Ok(None),
+        }
     }
 
     pub fn delete_schema(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
@@ -283,6 +295,30 @@ impl Main {
         Ok(self.main.delete::<_, Str>(writer, ATTRIBUTES_FOR_FACETING_KEY)?)
     }
 
+    pub fn filterable_attributes<'txn>(&self, reader: &'txn heed::RoTxn<MainT>) -> MResult<Option<Cow<'txn, Set<FieldId>>>> {
+        Ok(self.main.get::<_, Str, CowSet<FieldId>>(reader, FILTERABLE_ATTRIBUTES_KEY)?)
+    }
+
+    pub fn put_filterable_attributes(self, writer: &mut heed::RwTxn<MainT>, attributes: &Set<FieldId>) -> MResult<()> {
+        Ok(self.main.put::<_, Str, CowSet<FieldId>>(writer, FILTERABLE_ATTRIBUTES_KEY, attributes)?)
+    }
+
+    pub fn delete_filterable_attributes(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
+        Ok(self.main.delete::<_, Str>(writer, FILTERABLE_ATTRIBUTES_KEY)?)
+    }
+
+    pub fn field_types(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<BTreeMap<String, FieldType>>> {
+        Ok(self.main.get::<_, Str, SerdeBincode<BTreeMap<String, FieldType>>>(reader, FIELD_TYPES_KEY)?)
+    }
+
+    pub fn put_field_types(self, writer: &mut heed::RwTxn<MainT>, field_types: &BTreeMap<String, FieldType>) -> MResult<()> {
+        Ok(self.main.put::<_, Str, SerdeBincode<BTreeMap<String, FieldType>>>(writer, FIELD_TYPES_KEY, field_types)?)
+    }
+
+    pub fn delete_field_types(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
+        Ok(self.main.delete::<_, Str>(writer, FIELD_TYPES_KEY)?)
+    }
+
     pub fn ranking_rules(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Vec<RankingRule>>> {
         Ok(self.main.get::<_, Str, SerdeBincode<Vec<RankingRule>>>(reader, RANKING_RULES_KEY)?)
     }
@@ -296,14 +332,14 @@ impl Main {
     }
 
     pub fn distinct_attribute(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<FieldId>> {
-        match self.main.get::<_, Str, OwnedType<u16>>(reader, DISTINCT_ATTRIBUTE_KEY)? {
+        match self.main.get::<_, Str, OwnedType<u32>>(reader, DISTINCT_ATTRIBUTE_KEY)? {
             Some(value) => Ok(Some(FieldId(value.to_owned()))),
             None => Ok(None),
         }
     }
 
     pub fn put_distinct_attribute(self, writer: &mut heed::RwTxn<MainT>, value: FieldId) -> MResult<()> {
-        Ok(self.main.put::<_, Str, OwnedType<u16>>(writer, DISTINCT_ATTRIBUTE_KEY, &value.0)?)
+        Ok(self.main.put::<_, Str, OwnedType<u32>>(writer, DISTINCT_ATTRIBUTE_KEY, &value.0)?)
     }
 
     pub fn delete_distinct_attribute(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
@@ -317,4 +353,44 @@ impl Main {
     pub fn customs<'txn>(self, reader: &'txn heed::RoTxn<MainT>) -> MResult<Option<&'txn [u8]>> {
         Ok(self.main.get::<_, Str, ByteSlice>(reader, CUSTOMS_KEY)?)
     }
+
+    /// Returns the schema of a store written with the second storage format, where the
+    /// fields produced by flattening the nested objects were not recorded.
+    pub(crate) fn schema_v2(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Schema>> {
+        let schema = self.main.get::<_, Str, SerdeBincode<SchemaV2>>(reader, SCHEMA_KEY)?;
+        Ok(schema.map(Schema::from))
+    }
+
+    /// Returns the schema of a store written with the first storage format, where the
+    /// field ids and the indexed positions were stored on 16 bits.
+    pub(crate) fn legacy_schema(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Schema>> {
+        let schema = self.main.get::<_, Str, SerdeBincode<LegacySchema>>(reader, SCHEMA_KEY)?;
+        match schema {
+            Some(schema) => {
+                let mut schema = Schema::from(schema);
+                if let Some(field_types) = self.field_types(reader)? {
+                    schema.set_field_types(field_types);
+                }
+                Ok(Some(schema))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn legacy_distinct_attribute(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<FieldId>> {
+        match self.main.get::<_, Str, OwnedType<u16>>(reader, DISTINCT_ATTRIBUTE_KEY)? {
+            Some(value) => Ok(Some(FieldId(u32::from(value)))),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn legacy_attributes_for_faceting(self, reader: &heed::RoTxn<MainT>) -> MResult<Vec<FieldId>> {
+        let attributes = self.main.get::<_, Str, CowSet<u16>>(reader, ATTRIBUTES_FOR_FACETING_KEY)?;
+        Ok(attributes.map_or_else(Vec::new, |set| set.iter().map(|&id| FieldId(u32::from(id))).collect()))
+    }
+
+    pub(crate) fn legacy_filterable_attributes(self, reader: &heed::RoTxn<MainT>) -> MResult<Vec<FieldId>> {
+        let attributes = self.main.get::<_, Str, CowSet<u16>>(reader, FILTERABLE_ATTRIBUTES_KEY)?;
+        Ok(attributes.map_or_else(Vec::new, |set| set.iter().map(|&id| FieldId(u32::from(id))).collect()))
+    }
 }
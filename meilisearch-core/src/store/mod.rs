@@ -4,6 +4,7 @@ mod documents_ids;
 mod documents_fields;
 mod documents_fields_counts;
 mod facets;
+mod filters;
 mod main;
 mod postings_lists;
 mod prefix_documents_cache;
@@ -18,6 +19,7 @@ pub use self::documents_fields::{DocumentFieldsIter, DocumentsFields};
 pub use self::documents_fields_counts::{DocumentFieldsCountsIter, DocumentsFieldsCounts, DocumentsIdsIter};
 pub use self::documents_ids::{DocumentsIds, DiscoverIds};
 pub use self::facets::Facets;
+pub use self::filters::{Filters, FilterKey};
 pub use self::main::Main;
 pub use self::postings_lists::PostingsLists;
 pub use self::prefix_documents_cache::PrefixDocumentsCache;
@@ -44,22 +46,21 @@ use crate::serde::Deserializer;
 use crate::settings::SettingsUpdate;
 use crate::{query_builder::QueryBuilder, update, DocIndex, DocumentId, Error, MResult};
 
-type BEU32 = zerocopy::U32<byteorder::BigEndian>;
+pub type BEU32 = zerocopy::U32<byteorder::BigEndian>;
 type BEU64 = zerocopy::U64<byteorder::BigEndian>;
-pub type BEU16 = zerocopy::U16<byteorder::BigEndian>;
 
 #[derive(Debug, Copy, Clone, AsBytes, FromBytes)]
 #[repr(C)]
 pub struct DocumentFieldIndexedKey {
     docid: BEU32,
-    indexed_pos: BEU16,
+    indexed_pos: BEU32,
 }
 
 impl DocumentFieldIndexedKey {
     fn new(docid: DocumentId, indexed_pos: IndexedPos) -> DocumentFieldIndexedKey {
         DocumentFieldIndexedKey {
             docid: BEU32::new(docid.0),
-            indexed_pos: BEU16::new(indexed_pos.0),
+            indexed_pos: BEU32::new(indexed_pos.0),
         }
     }
 }
@@ -68,14 +69,14 @@ impl DocumentFieldIndexedKey {
 #[repr(C)]
 pub struct DocumentFieldStoredKey {
     docid: BEU32,
-    field_id: BEU16,
+    field_id: BEU32,
 }
 
 impl DocumentFieldStoredKey {
     fn new(docid: DocumentId, field_id: FieldId) -> DocumentFieldStoredKey {
         DocumentFieldStoredKey {
             docid: BEU32::new(docid.0),
-            field_id: BEU16::new(field_id.0),
+            field_id: BEU32::new(field_id.0),
         }
     }
 }
@@ -197,6 +198,10 @@ fn updates_name(name: &str) -> String {
     format!("store-{}-updates", name)
 }
 
+fn updates_priorities_name(name: &str) -> String {
+    format!("store-{}-updates-priorities", name)
+}
+
 fn updates_results_name(name: &str) -> String {
     format!("store-{}-updates-results", name)
 }
@@ -205,6 +210,10 @@ fn facets_name(name: &str) -> String {
     format!("store-{}-facets", name)
 }
 
+fn filters_name(name: &str) -> String {
+    format!("store-{}-filters", name)
+}
+
 #[derive(Clone)]
 pub struct Index {
     pub main: Main,
@@ -212,6 +221,7 @@ pub struct Index {
     pub documents_fields: DocumentsFields,
     pub documents_fields_counts: DocumentsFieldsCounts,
     pub facets: Facets,
+    pub filters: Filters,
     pub synonyms: Synonyms,
     pub docs_words: DocsWords,
     pub prefix_documents_cache: PrefixDocumentsCache,
@@ -220,6 +230,8 @@ pub struct Index {
     pub updates: Updates,
     pub updates_results: UpdatesResults,
     pub(crate) updates_notifier: UpdateEventsEmitter,
+    // the number of words indexed in each attribute of a document
+    pub(crate) word_limit: usize,
 }
 
 impl Index {
@@ -232,8 +244,9 @@ impl Index {
         let schema = self.main.schema(reader)?;
         let schema = schema.ok_or(Error::SchemaMissing)?;
 
+        // the fields nested under an attribute are retrieved along with it
         let attributes = match attributes {
-            Some(attributes) => Some(attributes.iter().filter_map(|name| schema.id(*name)).collect()),
+            Some(attributes) => Some(attributes.iter().flat_map(|name| schema.nested_ids(name)).collect()),
             None => None,
         };
 
@@ -288,6 +301,14 @@ impl Index {
         Ok(update::push_settings_update(writer, self.updates, self.updates_results, update)?)
     }
 
+    pub fn settings_update_impact(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        update: &SettingsUpdate,
+    ) -> MResult<update::SettingsUpdateImpact> {
+        update::settings_update_impact(reader, self, update)
+    }
+
     pub fn documents_addition<D>(&self) -> update::DocumentsAddition<D> {
         update::DocumentsAddition::new(
             self.updates,
@@ -317,6 +338,29 @@ impl Index {
         update::push_clear_all(writer, self.updates, self.updates_results)
     }
 
+    /// Enqueues the copy of the settings and the documents of the index with the `source` uid
+    /// into this index, once the source has processed its `after_update` update.
+    pub fn index_clone(&self, writer: &mut heed::RwTxn<UpdateT>, source: String, after_update: Option<u64>) -> MResult<u64> {
+        let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
+        update::push_index_clone(writer, self.updates, self.updates_results, source, after_update)
+    }
+
+    /// Changes the priority of an enqueued update, the updates with a high priority
+    /// are processed before the other updates of the index.
+    pub fn set_update_priority(
+        &self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        update_id: u64,
+        priority: update::UpdatePriority,
+    ) -> MResult<()> {
+        if let Some(mut update) = self.updates.get(writer, update_id)? {
+            update.priority = priority;
+            self.updates.put_update(writer, update_id, &update)?;
+            self.updates.put_priority(writer, update_id, priority)?;
+        }
+        Ok(())
+    }
+
     pub fn current_update_id(&self, reader: &heed::RoTxn<UpdateT>) -> MResult<Option<u64>> {
         match self.updates.last_update(reader)? {
             Some((id, _)) => Ok(Some(id)),
@@ -377,6 +421,7 @@ pub fn create(
     update_env: &heed::Env,
     name: &str,
     updates_notifier: UpdateEventsEmitter,
+    word_limit: usize,
 ) -> MResult<Index> {
     // create all the store names
     let main_name = main_name(name);
@@ -388,8 +433,10 @@ pub fn create(
     let prefix_documents_cache_name = prefix_documents_cache_name(name);
     let prefix_postings_lists_cache_name = prefix_postings_lists_cache_name(name);
     let updates_name = updates_name(name);
+    let updates_priorities_name = updates_priorities_name(name);
     let updates_results_name = updates_results_name(name);
     let facets_name = facets_name(name);
+    let filters_name = filters_name(name);
 
     // open all the stores
     let main = env.create_poly_database(Some(&main_name))?;
@@ -397,11 +444,13 @@ pub fn create(
     let documents_fields = env.create_database(Some(&documents_fields_name))?;
     let documents_fields_counts = env.create_database(Some(&documents_fields_counts_name))?;
     let facets = env.create_database(Some(&facets_name))?;
+    let filters = env.create_database(Some(&filters_name))?;
     let synonyms = env.create_database(Some(&synonyms_name))?;
     let docs_words = env.create_database(Some(&docs_words_name))?;
     let prefix_documents_cache = env.create_database(Some(&prefix_documents_cache_name))?;
     let prefix_postings_lists_cache = env.create_database(Some(&prefix_postings_lists_cache_name))?;
     let updates = update_env.create_database(Some(&updates_name))?;
+    let priorities = update_env.create_database(Some(&updates_priorities_name))?;
     let updates_results = update_env.create_database(Some(&updates_results_name))?;
 
     Ok(Index {
@@ -414,10 +463,12 @@ pub fn create(
         prefix_postings_lists_cache: PrefixPostingsListsCache { prefix_postings_lists_cache },
         prefix_documents_cache: PrefixDocumentsCache { prefix_documents_cache },
         facets: Facets { facets },
+        filters: Filters { filters },
 
-        updates: Updates { updates },
+        updates: Updates { updates, priorities },
         updates_results: UpdatesResults { updates_results },
         updates_notifier,
+        word_limit,
     })
 }
 
@@ -426,6 +477,7 @@ pub fn open(
     update_env: &heed::Env,
     name: &str,
     updates_notifier: UpdateEventsEmitter,
+    word_limit: usize,
 ) -> MResult<Option<Index>> {
     // create all the store names
     let main_name = main_name(name);
@@ -436,8 +488,10 @@ pub fn open(
     let docs_words_name = docs_words_name(name);
     let prefix_documents_cache_name = prefix_documents_cache_name(name);
     let facets_name = facets_name(name);
+    let filters_name = filters_name(name);
     let prefix_postings_lists_cache_name = prefix_postings_lists_cache_name(name);
     let updates_name = updates_name(name);
+    let updates_priorities_name = updates_priorities_name(name);
     let updates_results_name = updates_results_name(name);
 
     // open all the stores
@@ -485,6 +539,9 @@ pub fn open(
         Some(updates_results) => updates_results,
         None => return Ok(None),
     };
+    // the priorities and filters stores did not exist in previous versions
+    let priorities = update_env.create_database(Some(&updates_priorities_name))?;
+    let filters = env.create_database(Some(&filters_name))?;
 
     Ok(Some(Index {
         main: Main { main },
@@ -495,10 +552,12 @@ pub fn open(
         docs_words: DocsWords { docs_words },
         prefix_documents_cache: PrefixDocumentsCache { prefix_documents_cache },
         facets: Facets { facets },
+        filters: Filters { filters },
         prefix_postings_lists_cache: PrefixPostingsListsCache { prefix_postings_lists_cache },
-        updates: Updates { updates },
+        updates: Updates { updates, priorities },
         updates_results: UpdatesResults { updates_results },
         updates_notifier,
+        word_limit,
     }))
 }
 
@@ -512,6 +571,8 @@ pub fn clear(
     index.postings_lists.clear(writer)?;
     index.documents_fields.clear(writer)?;
     index.documents_fields_counts.clear(writer)?;
+    index.facets.clear(writer)?;
+    index.filters.clear(writer)?;
     index.synonyms.clear(writer)?;
     index.docs_words.clear(writer)?;
     index.prefix_documents_cache.clear(writer)?;
@@ -1,12 +1,14 @@
 use super::BEU64;
 use crate::database::UpdateT;
-use crate::update::Update;
-use heed::types::{OwnedType, SerdeJson};
+use crate::update::{Update, UpdatePriority};
+use heed::types::{OwnedType, SerdeJson, Unit};
 use heed::Result as ZResult;
 
 #[derive(Copy, Clone)]
 pub struct Updates {
     pub(crate) updates: heed::Database<OwnedType<BEU64>, SerdeJson<Update>>,
+    /// The ids of the enqueued updates with a high priority.
+    pub(crate) priorities: heed::Database<OwnedType<BEU64>, Unit>,
 }
 
 impl Updates {
@@ -26,6 +28,30 @@ impl Updates {
         }
     }
 
+    /// Returns the update that must be processed next, the oldest update
+    /// with a high priority if any, the oldest update otherwise.
+    pub fn next_update(self, reader: &heed::RoTxn<UpdateT>) -> ZResult<Option<(u64, Update)>> {
+        if let Some((key, ())) = self.priorities.first(reader)? {
+            if let Some(update) = self.get(reader, key.get())? {
+                return Ok(Some((key.get(), update)));
+            }
+        }
+        self.first_update(reader)
+    }
+
+    pub fn put_priority(
+        self,
+        writer: &mut heed::RwTxn<UpdateT>,
+        update_id: u64,
+        priority: UpdatePriority,
+    ) -> ZResult<()> {
+        let update_id = BEU64::new(update_id);
+        match priority {
+            UpdatePriority::High => self.priorities.put(writer, &update_id, &()),
+            UpdatePriority::Normal => self.priorities.delete(writer, &update_id).map(drop),
+        }
+    }
+
     // TODO do not trigger deserialize if possible
     pub fn get(self, reader: &heed::RoTxn<UpdateT>, update_id: u64) -> ZResult<Option<Update>> {
         let update_id = BEU64::new(update_id);
@@ -45,14 +71,14 @@ impl Updates {
 
     pub fn del_update(self, writer: &mut heed::RwTxn<UpdateT>, update_id: u64) -> ZResult<bool> {
         let update_id = BEU64::new(update_id);
+        self.priorities.delete(writer, &update_id)?;
         self.updates.delete(writer, &update_id)
     }
 
     pub fn pop_front(self, writer: &mut heed::RwTxn<UpdateT>) -> ZResult<Option<(u64, Update)>> {
         match self.first_update(writer)? {
             Some((update_id, update)) => {
-                let key = BEU64::new(update_id);
-                self.updates.delete(writer, &key)?;
+                self.del_update(writer, update_id)?;
                 Ok(Some((update_id, update)))
             }
             None => Ok(None),
@@ -60,6 +86,7 @@ impl Updates {
     }
 
     pub fn clear(self, writer: &mut heed::RwTxn<UpdateT>) -> ZResult<()> {
+        self.priorities.clear(writer)?;
         self.updates.clear(writer)
     }
 }
@@ -1,9 +1,9 @@
 use std::borrow::Cow;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, BTreeMap};
 
 use fst::{set::OpBuilder, SetBuilder};
 use indexmap::IndexMap;
-use meilisearch_schema::{Schema, FieldId};
+use meilisearch_schema::{is_nested_in, Schema, FieldId};
 use meilisearch_types::DocumentId;
 use sdset::{duo::Union, SetOperation};
 use serde::Deserialize;
@@ -12,10 +12,11 @@ use serde_json::Value;
 use crate::database::{MainT, UpdateT};
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
 use crate::facets;
+use crate::filters;
 use crate::raw_indexer::RawIndexer;
 use crate::serde::Deserializer;
 use crate::store::{self, DocumentsFields, DocumentsFieldsCounts, DiscoverIds};
-use crate::update::helpers::{index_value, value_to_number, extract_document_id};
+use crate::update::helpers::{check_document_types, flatten_document, index_value, value_to_typed_number, extract_document_id};
 use crate::update::{apply_documents_deletion, compute_short_prefixes, next_update_id, Update};
 use crate::{Error, MResult, RankedMap};
 
@@ -133,13 +134,13 @@ where A: AsRef<[u8]>,
                 writer,
                 document_id,
                 *indexed_pos,
-                number_of_words as u16,
+                number_of_words as u32,
             )?;
         }
     }
 
     if schema.is_ranked(field_id) {
-        let number = value_to_number(value).unwrap_or_default();
+        let number = value_to_typed_number(value, schema.field_type(field_id)).unwrap_or_default();
         ranked_map.insert(document_id, field_id, number);
     }
 
@@ -169,8 +170,10 @@ pub fn apply_addition<'a, 'b>(
     let mut documents_additions = HashMap::new();
     let mut new_external_docids = BTreeMap::new();
     let mut new_internal_docids = Vec::with_capacity(new_documents.len());
+    let mut type_errors = Vec::new();
+    let mut nested_fields = HashSet::new();
 
-    for mut document in new_documents {
+    for document in new_documents {
         let external_docids_get = |docid: &str| {
             match (external_docids.get(docid), new_external_docids.get(docid)) {
                 (_, Some(&id))
@@ -187,8 +190,12 @@ pub fn apply_addition<'a, 'b>(
                 &mut available_ids,
             )?;
 
-        new_external_docids.insert(external_docid, internal_docid.0 as u64);
-        new_internal_docids.push(internal_docid);
+        // a partial update replaces the fields it contains as a whole, as before
+        // the nested objects were flattened, `address` replaces `address.city`
+        let updated_fields: Vec<_> = if partial { document.keys().cloned().collect() } else { Vec::new() };
+
+        // nested objects are indexed as dotted fields, `address.city`
+        let mut document = flatten_document(document, &mut nested_fields);
 
         if partial {
             let mut deserializer = Deserializer {
@@ -202,13 +209,27 @@ pub fn apply_addition<'a, 'b>(
             let old_document = Option::<HashMap<String, Value>>::deserialize(&mut deserializer)?;
             if let Some(old_document) = old_document {
                 for (key, value) in old_document {
-                    document.entry(key).or_insert(value);
+                    if !updated_fields.iter().any(|name| is_nested_in(&key, name)) {
+                        document.entry(key).or_insert(value);
+                    }
                 }
             }
         }
+
+        // the documents that do not match the declared types are all reported at once
+        for error in check_document_types(&document, schema.field_types()) {
+            type_errors.push(format!("document {:?}: {}", external_docid, error));
+        }
+
+        new_external_docids.insert(external_docid, internal_docid.0 as u64);
+        new_internal_docids.push(internal_docid);
         documents_additions.insert(internal_docid, document);
     }
 
+    if !type_errors.is_empty() {
+        return Err(Error::InvalidDocumentFieldType(type_errors));
+    }
+
     // 2. remove the documents postings lists
     let number_of_inserted_documents = documents_additions.len();
     let documents_ids = new_external_docids.iter().map(|(id, _)| id.clone()).collect();
@@ -222,7 +243,7 @@ pub fn apply_addition<'a, 'b>(
     let stop_words = index.main.stop_words_fst(writer)?.map_data(Cow::into_owned)?;
 
 
-    let mut indexer = RawIndexer::new(stop_words);
+    let mut indexer = RawIndexer::with_word_limit(stop_words, index.word_limit);
 
     // For each document in this update
     for (document_id, document) in &documents_additions {
@@ -243,6 +264,11 @@ pub fn apply_addition<'a, 'b>(
         }
     }
 
+    // the nested fields are returned in their original shape
+    for name in &nested_fields {
+        schema.set_nested(name)?;
+    }
+
     write_documents_addition_index(
         writer,
         index,
@@ -265,6 +291,12 @@ pub fn apply_addition<'a, 'b>(
         index.facets.add(writer, facet_map)?;
     }
 
+    // index the filterable attributes of the new documents
+    if let Some(filterable_attributes) = index.main.filterable_attributes(writer)? {
+        let filter_map = filters::filter_map_from_docids(writer, index, &new_internal_docids, filterable_attributes.as_ref())?;
+        index.filters.add(writer, filter_map)?;
+    }
+
     // update is finished; update sorted document id cache with new state
     let mut document_ids = index.main.internal_docids(writer)?.to_vec();
     super::cache_document_ids_sorted(writer, &ranked_map, index, &mut document_ids)?;
@@ -308,6 +340,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
     index.main.put_ranked_map(writer, &ranked_map)?;
     index.main.put_number_of_documents(writer, |_| 0)?;
     index.facets.clear(writer)?;
+    index.filters.clear(writer)?;
     index.postings_lists.clear(writer)?;
     index.docs_words.clear(writer)?;
 
@@ -317,13 +350,17 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
         .unwrap();
 
     let number_of_inserted_documents = documents_ids_to_reindex.len();
-    let mut indexer = RawIndexer::new(stop_words);
+    let mut indexer = RawIndexer::with_word_limit(stop_words, index.word_limit);
     let mut ram_store = HashMap::new();
 
     if let Some(ref attributes_for_facetting) = index.main.attributes_for_faceting(writer)? {
         let facet_map = facets::facet_map_from_docids(writer, &index, &documents_ids_to_reindex, &attributes_for_facetting)?;
         index.facets.add(writer, facet_map)?;
     }
+    if let Some(ref filterable_attributes) = index.main.filterable_attributes(writer)? {
+        let filter_map = filters::filter_map_from_docids(writer, &index, &documents_ids_to_reindex, &filterable_attributes)?;
+        index.filters.add(writer, filter_map)?;
+    }
     // ^-- https://github.com/meilisearch/MeiliSearch/pull/631#issuecomment-626624470 --v
     for document_id in &documents_ids_to_reindex {
         for result in index.documents_fields.document_fields(writer, *document_id)? {
@@ -6,6 +6,7 @@ use sdset::{duo::DifferenceByKey, SetBuf, SetOperation};
 use crate::database::{MainT, UpdateT};
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
 use crate::facets;
+use crate::filters;
 use crate::store;
 use crate::update::{next_update_id, compute_short_prefixes, Update};
 use crate::{DocumentId, Error, MResult, RankedMap, MainWriter, Index};
@@ -104,6 +105,11 @@ pub fn apply_documents_deletion(
         index.facets.remove(writer, facet_map)?;
     }
 
+    if let Some(filterable_attributes) = index.main.filterable_attributes(writer)? {
+        let filter_map = filters::filter_map_from_docids(writer, &index, &internal_docids, &filterable_attributes)?;
+        index.filters.remove(writer, filter_map)?;
+    }
+
     // collect the ranked attributes according to the schema
     let ranked_fields = schema.ranked();
 
@@ -1,10 +1,12 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Write as _;
 
+use chrono::{DateTime, NaiveDate, Utc};
 use indexmap::IndexMap;
-use meilisearch_schema::IndexedPos;
+use meilisearch_schema::{FieldType, IndexedPos, Schema};
 use meilisearch_types::DocumentId;
 use ordered_float::OrderedFloat;
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::Number;
 use crate::raw_indexer::RawIndexer;
@@ -96,6 +98,195 @@ pub fn value_to_number(value: &Value) -> Option<Number> {
     }
 }
 
+/// Transforms the JSON Value into a Number according to the type declared for its field,
+/// the type is inferred from the value when the field is not typed, date strings are
+/// then transformed into timestamps.
+pub fn value_to_typed_number(value: &Value, field_type: Option<&FieldType>) -> Option<Number> {
+    match field_type {
+        Some(FieldType::Date) => parse_date(value).map(Number::Signed),
+        Some(FieldType::String) | Some(FieldType::Array(_)) => None,
+        Some(FieldType::Number) | Some(FieldType::Boolean) => value_to_number(value),
+        None => value_to_number(value).or_else(|| match value {
+            Value::String(string) => parse_date_str(string).map(Number::Signed),
+            _ => None,
+        }),
+    }
+}
+
+/// Parses a date into a unix timestamp in seconds, dates are either RFC 3339 strings,
+/// `YYYY-MM-DD` strings or timestamps.
+pub fn parse_date(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(string) => parse_date_str(string),
+        Value::Number(number) => number.as_i64(),
+        _ => None,
+    }
+}
+
+/// Parses a date string into a unix timestamp in seconds.
+pub fn parse_date_str(string: &str) -> Option<i64> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(string) {
+        return Some(date.timestamp());
+    }
+    NaiveDate::parse_from_str(string, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_hms(0, 0, 0).timestamp())
+}
+
+/// Parses a date used in a filter into a unix timestamp in seconds, the date is either
+/// a date string or a date relative to the current time like `now`, `now-7d` or `now+2h`.
+///
+/// The supported units are seconds (`s`), minutes (`m`), hours (`h`), days (`d`) and weeks (`w`).
+pub fn parse_date_expression(string: &str) -> Option<i64> {
+    let relative = match string.strip_prefix("now") {
+        Some(relative) => relative,
+        None => return parse_date_str(string),
+    };
+
+    let now = Utc::now().timestamp();
+    if relative.is_empty() {
+        return Some(now);
+    }
+
+    let (sign, relative) = match (relative.strip_prefix('+'), relative.strip_prefix('-')) {
+        (Some(relative), _) => (1, relative),
+        (_, Some(relative)) => (-1, relative),
+        (None, None) => return None,
+    };
+
+    let (unit_index, _) = relative.char_indices().last()?;
+    let (amount, unit) = relative.split_at(unit_index);
+    let amount: i64 = amount.parse().ok()?;
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    now.checked_add(sign * amount.checked_mul(unit_seconds)?)
+}
+
+/// Whether the JSON Value is of the given type, null values are accepted for every type.
+pub fn value_matches_type(value: &Value, field_type: &FieldType) -> bool {
+    match (field_type, value) {
+        (_, Value::Null) => true,
+        (FieldType::String, Value::String(_)) => true,
+        (FieldType::Number, Value::Number(_)) => true,
+        (FieldType::Boolean, Value::Bool(_)) => true,
+        (FieldType::Date, value) => parse_date(value).is_some(),
+        (FieldType::Array(inner), Value::Array(values)) => {
+            values.iter().all(|value| !value.is_null() && value_matches_type(value, inner))
+        },
+        (_, _) => false,
+    }
+}
+
+/// Checks the values of a document against the declared types of its fields,
+/// returns a description of every mismatch.
+pub fn check_document_types(
+    document: &IndexMap<String, Value>,
+    field_types: &BTreeMap<String, FieldType>,
+) -> Vec<String>
+{
+    document
+        .iter()
+        .filter_map(|(name, value)| {
+            let field_type = field_types.get(name)?;
+            if value_matches_type(value, field_type) {
+                None
+            } else {
+                Some(format!("field {:?} must be of type {}, found {}", name, field_type, value))
+            }
+        })
+        .collect()
+}
+
+/// Flattens the nested objects of a document into dotted field names,
+/// `{"address": {"city": "Paris"}}` becomes `{"address.city": "Paris"}`.
+///
+/// Arrays and empty objects are kept as they are. The names produced by
+/// flattening are added to `nested`, the dotted names sent as is are not.
+pub fn flatten_document(document: IndexMap<String, Value>, nested: &mut HashSet<String>) -> IndexMap<String, Value> {
+    fn flatten_into(
+        prefix: String,
+        value: Value,
+        is_nested: bool,
+        flattened: &mut IndexMap<String, Value>,
+        nested: &mut HashSet<String>,
+    ) {
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                for (key, value) in object {
+                    flatten_into(format!("{}.{}", prefix, key), value, true, flattened, nested);
+                }
+            },
+            value => {
+                if is_nested && !nested.contains(&prefix) {
+                    nested.insert(prefix.clone());
+                }
+                flattened.insert(prefix, value);
+            },
+        }
+    }
+
+    let mut flattened = IndexMap::with_capacity(document.len());
+    for (key, value) in document {
+        flatten_into(key, value, false, &mut flattened, nested);
+    }
+    flattened
+}
+
+/// Nests the fields of a flattened document back into objects, this is the reverse
+/// operation of `flatten_document`. Only the fields the schema marks as produced by
+/// flattening are nested, the dotted names sent as is are returned as they are.
+pub fn unflatten_document(document: IndexMap<String, Value>, schema: &Schema) -> IndexMap<String, Value> {
+    // returns the value back when the path conflicts with another field
+    fn insert_nested(target: &mut Value, path: &[&str], value: Value) -> Option<Value> {
+        match path.split_first() {
+            None if target.is_null() => {
+                *target = value;
+                None
+            },
+            None => Some(value),
+            Some((first, rest)) => {
+                if target.is_null() {
+                    *target = Value::Object(Map::new());
+                }
+                match target {
+                    Value::Object(object) => {
+                        let target = object.entry(*first).or_insert(Value::Null);
+                        insert_nested(target, rest, value)
+                    },
+                    _ => Some(value),
+                }
+            },
+        }
+    }
+
+    if document.keys().all(|key| !schema.is_nested(key)) {
+        return document;
+    }
+
+    let mut nested = IndexMap::with_capacity(document.len());
+    for (key, value) in document {
+        if !schema.is_nested(&key) {
+            nested.insert(key, value);
+            continue;
+        }
+
+        let path: Vec<_> = key.split('.').collect();
+        let target = nested.entry(path[0].to_string()).or_insert(Value::Null);
+        if let Some(value) = insert_nested(target, &path[1..], value) {
+            // the dotted name is kept when it can not be nested
+            nested.insert(key, value);
+        }
+    }
+    nested
+}
+
 /// Validates a string representation to be a correct document id and returns
 /// the corresponding id or generate a new one, this is the way we produce documents ids.
 pub fn discover_document_id<F>(
@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+
+use fst::Streamer;
+
+use crate::database::{MainT, UpdateT};
+use crate::filters;
+use crate::update::{apply_clear_all, compute_short_prefixes, next_update_id, Update};
+use crate::{store, FstSetCow, MResult};
+
+fn owned_set(set: FstSetCow) -> MResult<fst::Set<Vec<u8>>> {
+    Ok(fst::Set::new(set.as_fst().as_bytes().to_vec())?)
+}
+
+/// Copies the settings and the documents of the `source` index into the `destination` index.
+///
+/// The postings lists, the fields counts and the facets of the source are copied as is,
+/// documents are not tokenized again.
+pub fn apply_index_clone(
+    writer: &mut heed::RwTxn<MainT>,
+    source: &store::Index,
+    destination: &store::Index,
+) -> MResult<()> {
+    // the destination keeps its name and creation date, everything else is replaced, the clone
+    // is processed by the update loop of the destination before the updates enqueued after it
+    apply_clear_all(writer, destination)?;
+    destination.facets.clear(writer)?;
+    destination.synonyms.clear(writer)?;
+
+    // copy the settings of the source
+    if let Some(schema) = source.main.schema(writer)? {
+        destination.main.put_schema(writer, &schema)?;
+    }
+    match source.main.field_types(writer)? {
+        Some(field_types) => destination.main.put_field_types(writer, &field_types)?,
+        None => { destination.main.delete_field_types(writer)?; },
+    }
+    if let Some(ranking_rules) = source.main.ranking_rules(writer)? {
+        destination.main.put_ranking_rules(writer, &ranking_rules)?;
+    }
+    if let Some(distinct_attribute) = source.main.distinct_attribute(writer)? {
+        destination.main.put_distinct_attribute(writer, distinct_attribute)?;
+    }
+    let attributes_for_faceting = source.main.attributes_for_faceting(writer)?.map(Cow::into_owned);
+    if let Some(attributes) = &attributes_for_faceting {
+        destination.main.put_attributes_for_faceting(writer, attributes)?;
+    }
+    let filterable_attributes = source.main.filterable_attributes(writer)?.map(Cow::into_owned);
+    if let Some(attributes) = &filterable_attributes {
+        destination.main.put_filterable_attributes(writer, attributes)?;
+    }
+    let stop_words = owned_set(source.main.stop_words_fst(writer)?)?;
+    destination.main.put_stop_words_fst(writer, &stop_words)?;
+
+    let synonyms = owned_set(source.main.synonyms_fst(writer)?)?;
+    destination.main.put_synonyms_fst(writer, &synonyms)?;
+    let mut stream = synonyms.stream();
+    while let Some(word) = stream.next() {
+        let alternatives = owned_set(source.synonyms.synonyms_fst(writer, word)?)?;
+        destination.synonyms.put_synonyms(writer, word, &alternatives)?;
+    }
+
+    if let Some(customs) = source.main.customs(writer)?.map(ToOwned::to_owned) {
+        destination.main.put_customs(writer, &customs)?;
+    }
+
+    // copy the documents related data
+    let internal_docids = source.main.internal_docids(writer)?.into_owned();
+    destination.main.put_internal_docids(writer, &internal_docids)?;
+
+    let external_docids = source.main.external_docids(writer)?;
+    let external_docids = fst::Map::new(external_docids.as_fst().as_bytes().to_vec())?;
+    destination.main.put_external_docids(writer, &external_docids)?;
+
+    let number_of_documents = source.main.number_of_documents(writer)?;
+    destination.main.put_number_of_documents(writer, |_| number_of_documents)?;
+
+    if let Some(ranked_map) = source.main.ranked_map(writer)? {
+        destination.main.put_ranked_map(writer, &ranked_map)?;
+    }
+    if let Some(fields_distribution) = source.main.fields_distribution(writer)? {
+        destination.main.put_fields_distribution(writer, &fields_distribution)?;
+    }
+    if let Some(sorted_docids) = source.main.sorted_document_ids_cache(writer)?.map(Cow::into_owned) {
+        destination.main.put_sorted_document_ids_cache(writer, &sorted_docids)?;
+    }
+
+    for document_id in internal_docids.iter().cloned() {
+        let fields = source
+            .documents_fields
+            .document_fields(writer, document_id)?
+            .map(|result| result.map(|(field_id, bytes)| (field_id, bytes.to_vec())))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (field_id, bytes) in fields {
+            destination.documents_fields.put_document_field(writer, document_id, field_id, &bytes)?;
+        }
+
+        let counts = source
+            .documents_fields_counts
+            .document_fields_counts(writer, document_id)?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (indexed_pos, count) in counts {
+            destination.documents_fields_counts.put_document_field_count(writer, document_id, indexed_pos, count)?;
+        }
+
+        let words = source.docs_words.doc_words(writer, document_id)?;
+        let words: FstSetCow = words.map_data(|bytes| Cow::Owned(bytes.into_owned()))?;
+        destination.docs_words.put_doc_words(writer, document_id, &words)?;
+    }
+
+    // reuse the postings lists of the source instead of tokenizing the documents again
+    let words = owned_set(source.main.words_fst(writer)?)?;
+    destination.main.put_words_fst(writer, &words)?;
+    let mut stream = words.stream();
+    while let Some(word) = stream.next() {
+        let matches = source.postings_lists.postings_list(writer, word)?.map(|p| p.matches.into_owned());
+        if let Some(matches) = matches {
+            destination.postings_lists.put_postings_list(writer, word, &matches)?;
+        }
+    }
+    compute_short_prefixes(writer, &words, destination)?;
+
+    if let Some(attributes) = &attributes_for_faceting {
+        for field_id in attributes.iter().cloned() {
+            let facets = source
+                .facets
+                .field_document_ids(writer, field_id)?
+                .map(|result| result.map(|(key, (value, docids))| (key, value.to_owned(), docids.into_owned())))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (key, value, docids) in facets {
+                destination.facets.put_facet_document_ids(writer, key, &docids, &value)?;
+            }
+        }
+    }
+
+    if let Some(attributes) = &filterable_attributes {
+        let filter_map = filters::filter_map_from_docids(writer, destination, &internal_docids, attributes)?;
+        destination.filters.add(writer, filter_map)?;
+    }
+
+    destination.main.put_updated_at(writer)?;
+
+    Ok(())
+}
+
+pub fn push_index_clone(
+    writer: &mut heed::RwTxn<UpdateT>,
+    updates_store: store::Updates,
+    updates_results_store: store::UpdatesResults,
+    source: String,
+    after_update: Option<u64>,
+) -> MResult<u64> {
+    let last_update_id = next_update_id(writer, updates_store, updates_results_store)?;
+
+    let update = Update::index_clone(source, after_update);
+    updates_store.put_update(writer, last_update_id, &update)?;
+
+    Ok(last_update_id)
+}
@@ -2,6 +2,7 @@ mod clear_all;
 mod customs_update;
 mod documents_addition;
 mod documents_deletion;
+mod index_clone;
 mod settings_update;
 mod helpers;
 
@@ -9,11 +10,14 @@ pub use self::clear_all::{apply_clear_all, push_clear_all};
 pub use self::customs_update::{apply_customs_update, push_customs_update};
 pub use self::documents_addition::{apply_documents_addition, apply_documents_partial_addition, DocumentsAddition};
 pub use self::documents_deletion::{apply_documents_deletion, DocumentsDeletion};
+pub use self::index_clone::{apply_index_clone, push_index_clone};
 pub use self::helpers::{index_value, value_to_string, value_to_number, discover_document_id, extract_document_id};
-pub use self::settings_update::{apply_settings_update, push_settings_update};
+pub use self::helpers::{value_to_typed_number, value_matches_type, parse_date, parse_date_str, parse_date_expression};
+pub use self::helpers::{flatten_document, unflatten_document};
+pub use self::settings_update::{apply_settings_update, push_settings_update, settings_update_impact, SettingsUpdateImpact};
 
 use std::cmp;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use fst::{IntoStreamer, Streamer};
@@ -27,7 +31,7 @@ use serde_json::Value;
 use meilisearch_error::ErrorCode;
 use meilisearch_types::DocumentId;
 
-use crate::{store, MResult, RankedMap};
+use crate::{store, Error, MResult, RankedMap};
 use crate::database::{MainT, UpdateT};
 use crate::settings::SettingsUpdate;
 
@@ -35,6 +39,42 @@ use crate::settings::SettingsUpdate;
 pub struct Update {
     data: UpdateData,
     enqueued_at: DateTime<Utc>,
+    #[serde(default)]
+    pub(crate) priority: UpdatePriority,
+    #[serde(default)]
+    pub(crate) processed: UpdateProgress,
+}
+
+/// The number of documents of an addition processed in the same main transaction,
+/// the updates of the other indexes are processed between the batches of a larger addition.
+pub const DOCUMENTS_BATCH_SIZE: usize = 10_000;
+
+/// What has already been processed of an addition processed in several batches.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct UpdateProgress {
+    pub(crate) documents: usize,
+    pub(crate) duration: Duration,
+}
+
+/// The updates with a high priority are processed before the other updates,
+/// the ones of their index and the ones of the other indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdatePriority {
+    Normal,
+    High,
+}
+
+impl UpdatePriority {
+    pub fn is_normal(&self) -> bool {
+        *self == UpdatePriority::Normal
+    }
+}
+
+impl Default for UpdatePriority {
+    fn default() -> UpdatePriority {
+        UpdatePriority::Normal
+    }
 }
 
 impl Update {
@@ -42,6 +82,8 @@ impl Update {
         Update {
             data: UpdateData::ClearAll,
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
         }
     }
 
@@ -49,6 +91,8 @@ impl Update {
         Update {
             data: UpdateData::Customs(data),
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
         }
     }
 
@@ -56,6 +100,8 @@ impl Update {
         Update {
             data: UpdateData::DocumentsAddition(documents),
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
         }
     }
 
@@ -63,6 +109,8 @@ impl Update {
         Update {
             data: UpdateData::DocumentsPartial(documents),
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
         }
     }
 
@@ -70,6 +118,8 @@ impl Update {
         Update {
             data: UpdateData::DocumentsDeletion(data),
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
         }
     }
 
@@ -77,6 +127,65 @@ impl Update {
         Update {
             data: UpdateData::Settings(Box::new(data)),
             enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
+        }
+    }
+
+    fn index_clone(source: String, after_update: Option<u64>) -> Update {
+        Update {
+            data: UpdateData::IndexClone { source, after_update },
+            enqueued_at: Utc::now(),
+            priority: UpdatePriority::Normal,
+            processed: UpdateProgress::default(),
+        }
+    }
+
+    /// Splits the first `batch_size` documents of an addition from the documents left to process,
+    /// the documents left are returned in an update that keeps the id of this update.
+    pub(crate) fn split_documents(self, batch_size: usize) -> (Update, Option<Update>) {
+        let Update { data, enqueued_at, priority, processed } = self;
+
+        let (data, left) = match data {
+            UpdateData::DocumentsAddition(mut documents) if documents.len() > batch_size => {
+                let left = documents.split_off(batch_size);
+                (UpdateData::DocumentsAddition(documents), Some(UpdateData::DocumentsAddition(left)))
+            }
+            UpdateData::DocumentsPartial(mut documents) if documents.len() > batch_size => {
+                let left = documents.split_off(batch_size);
+                (UpdateData::DocumentsPartial(documents), Some(UpdateData::DocumentsPartial(left)))
+            }
+            data => (data, None),
+        };
+
+        let left = left.map(|data| {
+            let documents = processed.documents + batch_size;
+            let processed = UpdateProgress { documents, ..processed };
+            Update { data, enqueued_at, priority, processed }
+        });
+
+        (Update { data, enqueued_at, priority, processed }, left)
+    }
+
+    /// The type of this update, counting the documents of the batches already processed.
+    pub fn update_type(&self) -> UpdateType {
+        match self.data.update_type() {
+            UpdateType::DocumentsAddition { number } => {
+                UpdateType::DocumentsAddition { number: self.processed.documents + number }
+            }
+            UpdateType::DocumentsPartial { number } => {
+                UpdateType::DocumentsPartial { number: self.processed.documents + number }
+            }
+            update_type => update_type,
+        }
+    }
+
+    /// The uid of the index this update reads from, when it is not the index it was enqueued on,
+    /// along with the last update of this other index to process before this update.
+    pub(crate) fn source(&self) -> Option<(&str, Option<u64>)> {
+        match &self.data {
+            UpdateData::IndexClone { source, after_update } => Some((source, *after_update)),
+            _ => None,
         }
     }
 }
@@ -88,7 +197,9 @@ pub enum UpdateData {
     DocumentsAddition(Vec<IndexMap<String, Value>>),
     DocumentsPartial(Vec<IndexMap<String, Value>>),
     DocumentsDeletion(Vec<String>),
-    Settings(Box<SettingsUpdate>)
+    Settings(Box<SettingsUpdate>),
+    /// Copies the `source` index once it has processed its `after_update` update.
+    IndexClone { source: String, after_update: Option<u64> },
 }
 
 impl UpdateData {
@@ -108,6 +219,9 @@ impl UpdateData {
             UpdateData::Settings(update) => UpdateType::Settings {
                 settings: update.clone(),
             },
+            UpdateData::IndexClone { source, .. } => UpdateType::IndexClone {
+                source: source.clone(),
+            },
         }
     }
 }
@@ -121,6 +235,7 @@ pub enum UpdateType {
     DocumentsPartial { number: usize },
     DocumentsDeletion { number: usize },
     Settings { settings: Box<SettingsUpdate> },
+    IndexClone { source: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,6 +244,8 @@ pub struct ProcessedUpdateResult {
     pub update_id: u64,
     #[serde(rename = "type")]
     pub update_type: UpdateType,
+    #[serde(default, skip_serializing_if = "UpdatePriority::is_normal")]
+    pub priority: UpdatePriority,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -148,6 +265,8 @@ pub struct EnqueuedUpdateResult {
     pub update_id: u64,
     #[serde(rename = "type")]
     pub update_type: UpdateType,
+    #[serde(default, skip_serializing_if = "UpdatePriority::is_normal")]
+    pub priority: UpdatePriority,
     pub enqueued_at: DateTime<Utc>,
 }
 
@@ -186,7 +305,8 @@ pub fn update_status(
             Some(update) => Ok(Some(UpdateStatus::Enqueued {
                 content: EnqueuedUpdateResult {
                     update_id,
-                    update_type: update.data.update_type(),
+                    update_type: update.update_type(),
+                    priority: update.priority,
                     enqueued_at: update.enqueued_at,
                 },
             })),
@@ -215,12 +335,13 @@ pub fn next_update_id(
 pub fn update_task<'a, 'b>(
     writer: &'a mut heed::RwTxn<'b, MainT>,
     index: &store::Index,
+    source: Option<&store::Index>,
     update_id: u64,
     update: Update,
 ) -> MResult<ProcessedUpdateResult> {
     debug!("Processing update number {}", update_id);
 
-    let Update { enqueued_at, data } = update;
+    let Update { enqueued_at, data, priority, processed } = update;
 
     let (update_type, result, duration) = match data {
         UpdateData::ClearAll => {
@@ -243,7 +364,7 @@ pub fn update_task<'a, 'b>(
             let start = Instant::now();
 
             let update_type = UpdateType::DocumentsAddition {
-                number: documents.len(),
+                number: processed.documents + documents.len(),
             };
 
             let result = apply_documents_addition(writer, index, documents);
@@ -254,7 +375,7 @@ pub fn update_task<'a, 'b>(
             let start = Instant::now();
 
             let update_type = UpdateType::DocumentsPartial {
-                number: documents.len(),
+                number: processed.documents + documents.len(),
             };
 
             let result = apply_documents_partial_addition(writer, index, documents);
@@ -285,6 +406,17 @@ pub fn update_task<'a, 'b>(
                 *settings,
             );
 
+            (update_type, result, start.elapsed())
+        }
+        UpdateData::IndexClone { source: uid, .. } => {
+            let start = Instant::now();
+
+            let result = match source {
+                Some(source) => apply_index_clone(writer, source, index),
+                None => Err(Error::IndexNotFound(uid.clone())),
+            };
+            let update_type = UpdateType::IndexClone { source: uid };
+
             (update_type, result, start.elapsed())
         }
     };
@@ -297,11 +429,13 @@ pub fn update_task<'a, 'b>(
     let status = ProcessedUpdateResult {
         update_id,
         update_type,
+        priority,
         error: result.as_ref().map_err(|e| e.to_string()).err(),
         error_code: result.as_ref().map_err(|e| e.error_name()).err(),
         error_type: result.as_ref().map_err(|e| e.error_type()).err(),
         error_link: result.as_ref().map_err(|e| e.error_url()).err(),
-        duration: duration.as_secs_f64(),
+        // the duration of the batches of an addition already processed is counted
+        duration: (processed.duration + duration).as_secs_f64(),
         enqueued_at,
         processed_at: Utc::now(),
     };
@@ -1,15 +1,19 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
 
 use heed::Result as ZResult;
 use fst::{set::OpBuilder, SetBuilder};
 use sdset::SetBuf;
-use meilisearch_schema::Schema;
+use serde::Serialize;
+use serde_json::Value;
+use meilisearch_schema::{FieldId, FieldType, IndexedPos, Schema};
+use meilisearch_tokenizer::Tokenizer;
 
 use crate::database::{MainT, UpdateT};
 use crate::settings::{UpdateState, SettingsUpdate, RankingRule};
 use crate::update::documents_addition::reindex_all_documents;
-use crate::update::{next_update_id, Update};
-use crate::{store, MResult, Error};
+use crate::update::{next_update_id, value_matches_type, value_to_string, Update};
+use crate::{store, DocIndex, MResult, Error};
 
 pub fn push_settings_update(
     writer: &mut heed::RwTxn<UpdateT>,
@@ -25,55 +29,60 @@ pub fn push_settings_update(
     Ok(last_update_id)
 }
 
-pub fn apply_settings_update(
-    writer: &mut heed::RwTxn<MainT>,
+/// The schema resulting from a settings update and the work required to apply it, computed
+/// without writing anything, shared by the application of the update and its dry run.
+struct SettingsUpdatePlan {
+    schema: Schema,
+    must_reindex: bool,
+    distinct_attribute: UpdateState<FieldId>,
+    attributes_for_faceting: UpdateState<SetBuf<FieldId>>,
+    filterable_attributes: UpdateState<SetBuf<FieldId>>,
+    /// The old and the new stop words, when they are updated.
+    stop_words: Option<(BTreeSet<String>, BTreeSet<String>)>,
+}
+
+fn plan_settings_update(
+    reader: &heed::RoTxn<MainT>,
     index: &store::Index,
-    settings: SettingsUpdate,
-) -> MResult<()> {
+    settings: &SettingsUpdate,
+) -> MResult<SettingsUpdatePlan> {
     let mut must_reindex = false;
 
-    let mut schema = match index.main.schema(writer)? {
+    let mut schema = match index.main.schema(reader)? {
         Some(schema) => schema,
         None => {
-            match settings.primary_key.clone() {
-                UpdateState::Update(id) => Schema::with_primary_key(&id),
+            match &settings.primary_key {
+                UpdateState::Update(id) => Schema::with_primary_key(id),
                 _ => return Err(Error::MissingPrimaryKey)
             }
         }
     };
 
-    match settings.ranking_rules {
+    match &settings.ranking_rules {
         UpdateState::Update(v) => {
             let ranked_field: Vec<&str> = v.iter().filter_map(RankingRule::field).collect();
             schema.update_ranked(&ranked_field)?;
-            index.main.put_ranking_rules(writer, &v)?;
             must_reindex = true;
         },
         UpdateState::Clear => {
-            index.main.delete_ranking_rules(writer)?;
             schema.clear_ranked();
             must_reindex = true;
         },
         UpdateState::Nothing => (),
     }
 
-    match settings.distinct_attribute {
-        UpdateState::Update(v) => {
-            let field_id = schema.insert(&v)?;
-            index.main.put_distinct_attribute(writer, field_id)?;
-        },
-        UpdateState::Clear => {
-            index.main.delete_distinct_attribute(writer)?;
-        },
-        UpdateState::Nothing => (),
-    }
+    let distinct_attribute = match &settings.distinct_attribute {
+        UpdateState::Update(v) => UpdateState::Update(schema.insert(v)?),
+        UpdateState::Clear => UpdateState::Clear,
+        UpdateState::Nothing => UpdateState::Nothing,
+    };
 
-    match settings.searchable_attributes.clone() {
+    match &settings.searchable_attributes {
         UpdateState::Update(v) => {
             if v.iter().any(|e| e == "*") || v.is_empty() {
                 schema.set_all_fields_as_indexed();
             } else {
-                schema.update_indexed(v)?;
+                schema.update_indexed(v.clone())?;
             }
             must_reindex = true;
         },
@@ -83,12 +92,13 @@ pub fn apply_settings_update(
         },
         UpdateState::Nothing => (),
     }
-    match settings.displayed_attributes.clone() {
+
+    match &settings.displayed_attributes {
         UpdateState::Update(v) => {
             if v.contains("*") || v.is_empty() {
                 schema.set_all_fields_as_displayed();
             } else {
-                schema.update_displayed(v)?
+                schema.update_displayed(v.clone())?
             }
         },
         UpdateState::Clear => {
@@ -97,60 +107,186 @@ pub fn apply_settings_update(
         UpdateState::Nothing => (),
     }
 
-    match settings.attributes_for_faceting {
+    let attributes_for_faceting = match &settings.attributes_for_faceting {
         UpdateState::Update(attrs) => {
-            apply_attributes_for_faceting_update(writer, index, &mut schema, &attrs)?;
+            must_reindex = true;
+            UpdateState::Update(insert_attributes(&mut schema, attrs)?)
+        },
+        UpdateState::Clear => UpdateState::Clear,
+        UpdateState::Nothing => UpdateState::Nothing,
+    };
+
+    let filterable_attributes = match &settings.filterable_attributes {
+        UpdateState::Update(attrs) => {
+            must_reindex = true;
+            UpdateState::Update(insert_attributes(&mut schema, attrs)?)
+        },
+        UpdateState::Clear => UpdateState::Clear,
+        UpdateState::Nothing => UpdateState::Nothing,
+    };
+
+    // the ranked map is computed from the declared types
+    match &settings.field_types {
+        UpdateState::Update(field_types) => {
+            check_documents_types(reader, index, &schema, field_types)?;
+            schema.set_field_types(field_types.clone());
             must_reindex = true;
         },
         UpdateState::Clear => {
-            index.main.delete_attributes_for_faceting(writer)?;
-            index.facets.clear(writer)?;
+            schema.set_field_types(BTreeMap::new());
+            must_reindex = true;
         },
         UpdateState::Nothing => (),
     }
 
-    index.main.put_schema(writer, &schema)?;
+    let stop_words = match &settings.stop_words {
+        UpdateState::Update(stop_words) => Some(stop_words.clone()),
+        UpdateState::Clear => Some(BTreeSet::new()),
+        UpdateState::Nothing => None,
+    };
 
-    match settings.stop_words {
-        UpdateState::Update(stop_words) => {
-            if apply_stop_words_update(writer, index, stop_words)? {
+    // the words that are no longer stop words must be indexed again
+    let stop_words = match stop_words {
+        Some(stop_words) => {
+            let old_stop_words: BTreeSet<String> = index.main.stop_words(reader)?.into_iter().collect();
+            if old_stop_words.difference(&stop_words).next().is_some() {
                 must_reindex = true;
             }
+            Some((old_stop_words, stop_words))
+        },
+        None => None,
+    };
+
+    Ok(SettingsUpdatePlan {
+        schema,
+        must_reindex,
+        distinct_attribute,
+        attributes_for_faceting,
+        filterable_attributes,
+        stop_words,
+    })
+}
+
+fn insert_attributes(schema: &mut Schema, attributes: &[String]) -> MResult<SetBuf<FieldId>> {
+    let mut attribute_ids = Vec::new();
+    for name in attributes {
+        attribute_ids.push(schema.insert(name)?);
+    }
+    Ok(SetBuf::from_dirty(attribute_ids))
+}
+
+pub fn apply_settings_update(
+    writer: &mut heed::RwTxn<MainT>,
+    index: &store::Index,
+    settings: SettingsUpdate,
+) -> MResult<()> {
+    let plan = plan_settings_update(writer, index, &settings)?;
+
+    match settings.ranking_rules {
+        UpdateState::Update(v) => index.main.put_ranking_rules(writer, &v)?,
+        UpdateState::Clear => { index.main.delete_ranking_rules(writer)?; },
+        UpdateState::Nothing => (),
+    }
+
+    match plan.distinct_attribute {
+        UpdateState::Update(field_id) => index.main.put_distinct_attribute(writer, field_id)?,
+        UpdateState::Clear => { index.main.delete_distinct_attribute(writer)?; },
+        UpdateState::Nothing => (),
+    }
+
+    match plan.attributes_for_faceting {
+        UpdateState::Update(attribute_ids) => {
+            index.main.put_attributes_for_faceting(writer, &attribute_ids)?;
         },
         UpdateState::Clear => {
-            if apply_stop_words_update(writer, index, BTreeSet::new())? {
-                must_reindex = true;
-            }
+            index.main.delete_attributes_for_faceting(writer)?;
+            index.facets.clear(writer)?;
         },
         UpdateState::Nothing => (),
     }
 
+    match plan.filterable_attributes {
+        UpdateState::Update(attribute_ids) => {
+            index.main.put_filterable_attributes(writer, &attribute_ids)?;
+        },
+        UpdateState::Clear => {
+            index.main.delete_filterable_attributes(writer)?;
+            index.filters.clear(writer)?;
+        },
+        UpdateState::Nothing => (),
+    }
+
+    match settings.field_types {
+        UpdateState::Update(field_types) => index.main.put_field_types(writer, &field_types)?,
+        UpdateState::Clear => { index.main.delete_field_types(writer)?; },
+        UpdateState::Nothing => (),
+    }
+
+    index.main.put_schema(writer, &plan.schema)?;
+
+    if let Some((_, stop_words)) = plan.stop_words {
+        apply_stop_words_update(writer, index, stop_words)?;
+    }
+
     match settings.synonyms {
         UpdateState::Update(synonyms) => apply_synonyms_update(writer, index, synonyms)?,
         UpdateState::Clear => apply_synonyms_update(writer, index, BTreeMap::new())?,
         UpdateState::Nothing => (),
     }
 
-    if must_reindex {
+    if plan.must_reindex {
         reindex_all_documents(writer, index)?;
     }
 
     Ok(())
 }
 
-fn apply_attributes_for_faceting_update(
-    writer: &mut heed::RwTxn<MainT>,
+/// The number of mismatching fields reported when the documents do not match the declared types,
+/// the documents are not read any further once this number is exceeded.
+const MAX_REPORTED_TYPE_ERRORS: usize = 100;
+
+/// Ensures that the stored documents match the field types about to be declared.
+fn check_documents_types(
+    reader: &heed::RoTxn<MainT>,
     index: &store::Index,
-    schema: &mut Schema,
-    attributes: &[String]
-    ) -> MResult<()> {
-    let mut attribute_ids = Vec::new();
-    for name in attributes {
-        attribute_ids.push(schema.insert(name)?);
+    schema: &Schema,
+    field_types: &BTreeMap<String, FieldType>,
+) -> MResult<()> {
+    let primary_key = schema.primary_key().and_then(|name| schema.id(name));
+    let mut errors = Vec::new();
+
+    'documents: for document_id in index.main.internal_docids(reader)?.iter().cloned() {
+        for (name, field_type) in field_types {
+            let field_id = match schema.id(name) {
+                Some(field_id) => field_id,
+                None => continue,
+            };
+
+            if let Some(value) = index.document_attribute::<Value>(reader, document_id, field_id)? {
+                if !value_matches_type(&value, field_type) {
+                    if errors.len() == MAX_REPORTED_TYPE_ERRORS {
+                        errors.push(format!("only the first {} mismatching fields are reported", MAX_REPORTED_TYPE_ERRORS));
+                        break 'documents;
+                    }
+
+                    let external_id = match primary_key {
+                        Some(id) => index.document_attribute::<Value>(reader, document_id, id)?,
+                        None => None,
+                    };
+                    errors.push(format!(
+                        "document {}: field {:?} must be of type {}, found {}",
+                        external_id.unwrap_or(Value::Null), name, field_type, value,
+                    ));
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidDocumentFieldType(errors))
     }
-    let attributes_for_faceting = SetBuf::from_dirty(attribute_ids);
-    index.main.put_attributes_for_faceting(writer, &attributes_for_faceting)?;
-    Ok(())
 }
 
 pub fn apply_stop_words_update(
@@ -311,3 +447,140 @@ pub fn apply_synonyms_update(
 
     Ok(())
 }
+
+/// The number of documents read to estimate the size of the newly searchable attributes.
+const IMPACT_SAMPLE_SIZE: usize = 1000;
+
+/// What applying a settings update would change, computed without modifying the index.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsUpdateImpact {
+    /// Whether all the documents would be indexed again.
+    pub must_reindex: bool,
+    pub reindexed_documents: u64,
+    /// The stores that would be cleared and written again.
+    pub rebuilt_stores: BTreeSet<&'static str>,
+    /// An estimation, in bytes, of the size variation of the postings lists.
+    ///
+    /// The words that are no longer stop words are not taken into account
+    /// as only the reindexation can tell where they appear.
+    pub estimated_size_change: i64,
+}
+
+/// Validates the given settings update against the schema of the index and
+/// reports the work its application would trigger, nothing is written.
+pub fn settings_update_impact(
+    reader: &heed::RoTxn<MainT>,
+    index: &store::Index,
+    settings: &SettingsUpdate,
+) -> MResult<SettingsUpdateImpact> {
+    let mut impact = SettingsUpdateImpact::default();
+
+    let old_schema = index.main.schema(reader)?;
+    let plan = plan_settings_update(reader, index, settings)?;
+    impact.must_reindex = plan.must_reindex;
+
+    if let UpdateState::Clear = plan.attributes_for_faceting {
+        impact.rebuilt_stores.insert("facets");
+    }
+
+    if let UpdateState::Clear = plan.filterable_attributes {
+        impact.rebuilt_stores.insert("filters");
+    }
+
+    if let Some((old_stop_words, stop_words)) = &plan.stop_words {
+        // the postings lists of the new stop words are deleted
+        for word in stop_words.difference(old_stop_words) {
+            if let Some(postings) = index.postings_lists.postings_list(reader, word.as_bytes())? {
+                impact.estimated_size_change -= (postings.matches.len() * mem::size_of::<DocIndex>()) as i64;
+            }
+        }
+
+        impact.rebuilt_stores.insert("stopWords");
+        impact.rebuilt_stores.insert("words");
+        impact.rebuilt_stores.insert("postingsLists");
+    }
+
+    if !matches!(settings.synonyms, UpdateState::Nothing) {
+        impact.rebuilt_stores.insert("synonyms");
+    }
+
+    if impact.must_reindex {
+        impact.reindexed_documents = index.main.number_of_documents(reader)?;
+        impact.rebuilt_stores.extend(&[
+            "words",
+            "postingsLists",
+            "docsWords",
+            "documentsFieldsCounts",
+            "prefixPostingsListsCache",
+            "rankedMap",
+            "facets",
+            "filters",
+        ]);
+
+        if let Some(old_schema) = &old_schema {
+            impact.estimated_size_change += estimate_indexed_size_change(reader, index, old_schema, &plan.schema)?;
+        }
+    }
+
+    Ok(impact)
+}
+
+/// Estimates the size variation of the postings lists when the searchable attributes
+/// change from the ones of `old_schema` to the ones of `new_schema`.
+fn estimate_indexed_size_change(
+    reader: &heed::RoTxn<MainT>,
+    index: &store::Index,
+    old_schema: &Schema,
+    new_schema: &Schema,
+) -> MResult<i64> {
+    let removed: Vec<IndexedPos> = old_schema
+        .indexed()
+        .iter()
+        .filter(|id| new_schema.is_indexed(**id).is_none())
+        .filter_map(|id| old_schema.is_indexed(*id).cloned())
+        .collect();
+
+    let added: Vec<FieldId> = new_schema
+        .indexed()
+        .iter()
+        .filter(|id| old_schema.is_indexed(**id).is_none())
+        .cloned()
+        .collect();
+
+    let mut removed_words = 0u64;
+    if !removed.is_empty() {
+        for result in index.documents_fields_counts.all_documents_fields_counts(reader)? {
+            let (_, indexed_pos, count) = result?;
+            if removed.contains(&indexed_pos) {
+                removed_words += u64::from(count);
+            }
+        }
+    }
+
+    // the words of the attributes that are not indexed yet are unknown,
+    // a sample of the documents is tokenized to extrapolate them
+    let mut added_words = 0u64;
+    if !added.is_empty() {
+        let mut sampled = 0u64;
+        for result in index.documents_fields_counts.documents_ids(reader)?.take(IMPACT_SAMPLE_SIZE) {
+            let document_id = result?;
+            for field_id in &added {
+                if let Some(bytes) = index.documents_fields.document_attribute(reader, document_id, *field_id)? {
+                    let value: Value = serde_json::from_slice(bytes)?;
+                    let text = value_to_string(&value);
+                    added_words += Tokenizer::new(&text).count() as u64;
+                }
+            }
+            sampled += 1;
+        }
+
+        if sampled != 0 {
+            let number_of_documents = index.main.number_of_documents(reader)?;
+            added_words = added_words * number_of_documents / sampled;
+        }
+    }
+
+    let doc_index_size = mem::size_of::<DocIndex>() as i64;
+    Ok((added_words as i64 - removed_words as i64) * doc_index_size)
+}
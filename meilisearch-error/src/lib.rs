@@ -53,6 +53,11 @@ pub enum Code {
     InvalidIndexUid,
     OpenIndex,
 
+    // alias related error
+    AliasAlreadyExists,
+    AliasNotFound,
+    InvalidAlias,
+
     // invalid state error
     InvalidState,
     MissingPrimaryKey,
@@ -60,6 +65,8 @@ pub enum Code {
 
     MaxFieldsLimitExceeded,
     MissingDocumentId,
+    InvalidDocumentFieldType,
+    InvalidFieldType,
 
     Facet,
     Filter,
@@ -79,6 +86,8 @@ pub enum Code {
 
     DumpAlreadyInProgress,
     DumpProcessFailed,
+
+    SnapshotAlreadyInProgress,
 }
 
 impl Code {
@@ -97,6 +106,12 @@ impl Code {
             InvalidIndexUid => ErrCode::invalid("invalid_index_uid", StatusCode::BAD_REQUEST),
             OpenIndex => ErrCode::internal("index_not_accessible", StatusCode::INTERNAL_SERVER_ERROR),
 
+            // alias related errors
+            AliasAlreadyExists => ErrCode::invalid("alias_already_exists", StatusCode::BAD_REQUEST),
+            // thrown when requesting an unexisting alias
+            AliasNotFound => ErrCode::invalid("alias_not_found", StatusCode::NOT_FOUND),
+            InvalidAlias => ErrCode::invalid("invalid_alias", StatusCode::BAD_REQUEST),
+
             // invalid state error
             InvalidState => ErrCode::internal("invalid_state", StatusCode::INTERNAL_SERVER_ERROR),
             // thrown when no primary key has been set
@@ -107,6 +122,10 @@ impl Code {
             // invalid document
             MaxFieldsLimitExceeded => ErrCode::invalid("max_fields_limit_exceeded", StatusCode::BAD_REQUEST),
             MissingDocumentId => ErrCode::invalid("missing_document_id", StatusCode::BAD_REQUEST),
+            // a document value does not match the type declared for its field
+            InvalidDocumentFieldType => ErrCode::invalid("invalid_document_field_type", StatusCode::BAD_REQUEST),
+            // the declared type of a field is unknown
+            InvalidFieldType => ErrCode::invalid("invalid_field_type", StatusCode::BAD_REQUEST),
 
             // error related to facets
             Facet => ErrCode::invalid("invalid_facet", StatusCode::BAD_REQUEST),
@@ -129,6 +148,9 @@ impl Code {
             // error related to dump
             DumpAlreadyInProgress => ErrCode::invalid("dump_already_in_progress", StatusCode::CONFLICT),
             DumpProcessFailed => ErrCode::internal("dump_process_failed", StatusCode::INTERNAL_SERVER_ERROR),
+
+            // error related to snapshot
+            SnapshotAlreadyInProgress => ErrCode::invalid("snapshot_already_in_progress", StatusCode::CONFLICT),
         }
     }
 
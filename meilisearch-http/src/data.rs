@@ -7,6 +7,7 @@ use meilisearch_core::{Database, DatabaseOptions, Index};
 use sha2::Digest;
 
 use crate::error::{Error as MSError, ResponseError};
+use crate::helpers::remote_storage::RemoteStorage;
 use crate::index_update_callback;
 use crate::option::Opt;
 
@@ -29,9 +30,13 @@ pub struct DataInner {
     pub db_path: String,
     pub dumps_folder: PathBuf,
     pub dump_batch_size: usize,
+    pub snapshot_dir: PathBuf,
+    pub snapshot_retention: usize,
+    pub remote_storage: Option<RemoteStorage>,
     pub api_keys: ApiKeys,
     pub server_pid: u32,
     pub http_payload_size_limit: usize,
+    pub max_total_hits: usize,
 }
 
 #[derive(Clone)]
@@ -60,19 +65,36 @@ impl ApiKeys {
 
 impl Data {
     pub fn new(opt: Opt) -> Result<Data, Box<dyn Error>> {
+        Data::open(opt, |path, options| Database::open_or_create(path, options))
+    }
+
+    /// Open the database written by another version of the engine, in order to migrate it.
+    pub fn new_outdated(opt: Opt) -> Result<Data, Box<dyn Error>> {
+        Data::open(opt, |path, options| Database::open_outdated(path, options))
+    }
+
+    fn open<F>(opt: Opt, open_database: F) -> Result<Data, Box<dyn Error>>
+    where
+        F: FnOnce(String, DatabaseOptions) -> meilisearch_core::MResult<Database>,
+    {
         let db_path = opt.db_path.clone();
         let dumps_folder = opt.dumps_folder.clone();
         let dump_batch_size = opt.dump_batch_size;
+        let snapshot_dir = opt.snapshot_path.clone().unwrap_or_else(|| PathBuf::from("snapshots/"));
+        let snapshot_retention = opt.snapshot_retention;
+        let remote_storage = RemoteStorage::from_opt(&opt);
         let server_pid = std::process::id();
 
         let db_opt = DatabaseOptions {
             main_map_size: opt.max_mdb_size,
             update_map_size: opt.max_udb_size,
+            max_indexed_words: opt.max_indexed_words,
         };
 
         let http_payload_size_limit = opt.http_payload_size_limit;
+        let max_total_hits = opt.max_total_hits;
 
-        let db = Arc::new(Database::open_or_create(opt.db_path, db_opt)?);
+        let db = Arc::new(open_database(opt.db_path, db_opt)?);
 
         let mut api_keys = ApiKeys {
             master: opt.master_key,
@@ -87,9 +109,13 @@ impl Data {
             db_path,
             dumps_folder,
             dump_batch_size,
+            snapshot_dir,
+            snapshot_retention,
+            remote_storage,
             api_keys,
             server_pid,
             http_payload_size_limit,
+            max_total_hits,
         };
 
         let data = Data {
@@ -104,6 +130,22 @@ impl Data {
         Ok(data)
     }
 
+    /// Shuts the database down once the updates it received are processed, its files can be
+    /// moved once this returns. Fails if another handle on the data is still alive.
+    pub fn close(self) -> Result<(), MSError> {
+        // closing the database unsets the update callback and the handle on the data it holds
+        self.db.close()?;
+
+        match Arc::try_unwrap(self.inner) {
+            // the environments are closed along with the last handle on the database
+            Ok(inner) => {
+                drop(inner);
+                Ok(())
+            }
+            Err(_) => Err(MSError::internal("the database is still in use")),
+        }
+    }
+
     fn create_index(&self, uid: &str) -> Result<Index, ResponseError> {
         if !uid
             .chars()
@@ -1,18 +1,23 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{create_dir_all, File};
+use std::hash::{Hash, Hasher};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::thread;
 
 use actix_web::web;
-use chrono::offset::Utc;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
 use indexmap::IndexMap;
 use log::{error, info};
 use meilisearch_core::{MainWriter, MainReader, UpdateReader};
 use meilisearch_core::settings::Settings;
-use meilisearch_core::update::{apply_settings_update, apply_documents_addition};
+use meilisearch_core::Filter;
+use meilisearch_core::update::{apply_settings_update, apply_documents_addition, apply_documents_deletion, unflatten_document};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use siphasher::sip::SipHasher;
 use tempfile::TempDir;
 
 use crate::Data;
@@ -39,6 +44,27 @@ pub struct DumpMetadata {
     indexes: Vec<crate::routes::index::IndexResponse>,
     db_version: String,
     dump_version: DumpVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dumped_at: Option<DateTime<Utc>>,
+    /// The id of the last processed update of each index when the dump was created.
+    #[serde(default)]
+    last_update_ids: BTreeMap<String, u64>,
+    /// The uid of the dump an incremental dump is based on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_dump: Option<String>,
+    /// The indexes of an incremental dump that only contain the documents added or changed
+    /// since the base dump, along with the ids of the documents deleted since.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    incremental_indexes: Vec<String>,
+    /// The indexes of the base dump that were deleted since it was created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    deleted_indexes: Vec<String>,
+    /// The filter expressions the exported documents of each index were restricted with.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    filters: BTreeMap<String, String>,
+    /// Whether the `updates.jsonl` files were left out of the dump.
+    #[serde(default)]
+    skip_updates: bool,
 }
 
 impl DumpMetadata {
@@ -48,9 +74,21 @@ impl DumpMetadata {
             indexes,
             db_version,
             dump_version: DumpVersion::CURRENT,
+            dumped_at: None,
+            last_update_ids: BTreeMap::new(),
+            base_dump: None,
+            incremental_indexes: Vec::new(),
+            deleted_indexes: Vec::new(),
+            filters: BTreeMap::new(),
+            skip_updates: false,
         }
     }
 
+    /// Whether only the changes since the base dump of the index with the given uid are exported.
+    fn is_incremental(&self, index_uid: &str) -> bool {
+        self.incremental_indexes.iter().any(|uid| uid == index_uid)
+    }
+
     /// Extract DumpMetadata from `metadata.json` file present at provided `folder_path`
     fn from_path(folder_path: &Path) -> Result<Self, Error> {
         let path = folder_path.join("metadata.json");
@@ -62,7 +100,7 @@ impl DumpMetadata {
     }
 
     /// Write DumpMetadata in `metadata.json` file at provided `folder_path`
-    fn to_path(&self, folder_path: &Path) -> Result<(), Error> {
+    pub(crate) fn to_path(&self, folder_path: &Path) -> Result<(), Error> {
         let path = folder_path.join("metadata.json");
         let file = File::create(path)?;
 
@@ -72,6 +110,71 @@ impl DumpMetadata {
     }
 }
 
+/// The hash of the content of each document of an index, by external document id.
+type DocumentsHashes = BTreeMap<String, u64>;
+
+/// What an incremental dump needs to know about the dump it is based on.
+struct BaseDump {
+    metadata: DumpMetadata,
+    /// The hashes of the documents of each index exported in the base dump.
+    hashes: HashMap<String, DocumentsHashes>,
+}
+
+impl BaseDump {
+    /// Extract the metadata and the documents hashes of the compressed dump at `dump_path`
+    /// without decompressing the whole dump.
+    fn from_archive(dump_path: &Path) -> Result<Self, Error> {
+        let file = File::open(dump_path)?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+        let mut metadata = None;
+        let mut hashes = HashMap::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let path = entry.path()?.into_owned();
+            match path.file_name().and_then(|name| name.to_str()) {
+                Some("metadata.json") => metadata = Some(serde_json::from_reader(entry)?),
+                Some("documents_hashes.json") => {
+                    let index_uid = path
+                        .parent()
+                        .and_then(Path::file_name)
+                        .and_then(|name| name.to_str())
+                        .map(ToString::to_string);
+                    if let Some(index_uid) = index_uid {
+                        hashes.insert(index_uid, serde_json::from_reader(entry)?);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        match metadata {
+            Some(metadata) => Ok(BaseDump { metadata, hashes }),
+            None => Err(Error::Internal(format!("no metadata found in dump {:?}", dump_path))),
+        }
+    }
+}
+
+/// The external id of a document from the value of its primary key.
+fn external_docid(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        serde_json::Value::String(string) => Some(string.clone()),
+        _ => None,
+    }
+}
+
+/// Extract the ids of the documents deleted since the base dump from the
+/// `deleted_documents.json` file present at provided `folder_path`
+fn deleted_documents_from_path(folder_path: &Path) -> Result<Vec<String>, Error> {
+    let path = folder_path.join("deleted_documents.json");
+    let file = File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    let deleted_documents = serde_json::from_reader(reader)?;
+
+    Ok(deleted_documents)
+}
+
 /// Extract Settings from `settings.json` file present at provided `folder_path`
 fn settings_from_path(folder_path: &Path) -> Result<Settings, Error> {
     let path = folder_path.join("settings.json");
@@ -83,7 +186,7 @@ fn settings_from_path(folder_path: &Path) -> Result<Settings, Error> {
 }
 
 /// Write Settings in `settings.json` file at provided `folder_path`
-fn settings_to_path(settings: &Settings, folder_path: &Path) -> Result<(), Error> {
+pub(crate) fn settings_to_path(settings: &Settings, folder_path: &Path) -> Result<(), Error> {
     let path = folder_path.join("settings.json");
     let file = File::create(path)?;
 
@@ -92,11 +195,13 @@ fn settings_to_path(settings: &Settings, folder_path: &Path) -> Result<(), Error
     Ok(())
 }
 
-/// Import settings and documents of a dump with version `DumpVersion::V1` in specified index.
+/// Import settings and documents of a dump with version `DumpVersion::V1` in specified index,
+/// the documents deleted since the base dump of an `incremental` index are removed.
 fn import_index_v1(
     data: &Data,
     dumps_folder: &Path,
     index_uid: &str,
+    incremental: bool,
     document_batch_size: usize,
     write_txn: &mut MainWriter,
 ) -> Result<(), Error> {
@@ -115,7 +220,6 @@ fn import_index_v1(
     let settings = settings.to_update().map_err(|_e| Error::dump_failed())?;
     apply_settings_update(write_txn, &index, settings)?;
 
-    // create iterator over documents in `documents.jsonl` to make batch importation
     // create iterator over documents in `documents.jsonl` to make batch importation
     let documents = {
         let file = File::open(&index_path.join("documents.jsonl"))?;
@@ -143,6 +247,14 @@ fn import_index_v1(
         apply_documents_addition(write_txn, &index, values)?;
     }
 
+    // remove the documents deleted since the base dump
+    if incremental {
+        let deleted_documents = deleted_documents_from_path(&index_path)?;
+        if !deleted_documents.is_empty() {
+            apply_documents_deletion(write_txn, &index, deleted_documents)?;
+        }
+    }
+
     // sync index information: stats, updated_at, last_update
     if let Err(e) = crate::index_update_callback_txn(index, index_uid, data, write_txn) {
         return Err(Error::Internal(e));
@@ -174,10 +286,22 @@ pub fn import_dump(
         DumpVersion::V1 => import_index_v1,
     };
 
-    // remove indexes which have same `uid` than indexes to import and create empty indexes
+    // remove the indexes deleted since the base dump of an incremental dump
     let existing_index_uids = data.db.indexes_uids();
+    for index_uid in metadata.deleted_indexes.iter() {
+        if existing_index_uids.contains(index_uid) {
+            data.db.delete_index(index_uid)?;
+        }
+    }
+
+    // remove indexes which have same `uid` than indexes to import and create empty indexes,
+    // the indexes of an incremental dump are only updated with the changes since the base dump
     for index in metadata.indexes.iter() {
-        if existing_index_uids.contains(&index.uid) {
+        let exists = existing_index_uids.contains(&index.uid);
+        if exists && metadata.is_incremental(&index.uid) {
+            continue;
+        }
+        if exists {
             data.db.delete_index(index.uid.clone())?;
         }
         index::create_index_sync(&data.db, index.uid.clone(), index.name.clone(), index.primary_key.clone())?;
@@ -185,8 +309,9 @@ pub fn import_dump(
 
     // import each indexes content
     data.db.main_write::<_, _, Error>(|mut writer| {
-        for index in metadata.indexes {
-            import_index(&data, tmp_dir_path, &index.uid, document_batch_size, &mut writer)?;
+        for index in metadata.indexes.iter() {
+            let incremental = metadata.is_incremental(&index.uid);
+            import_index(&data, tmp_dir_path, &index.uid, incremental, document_batch_size, &mut writer)?;
         }
         Ok(())
     })?;
@@ -195,6 +320,102 @@ pub fn import_dump(
     Ok(())
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexImportResponse {
+    index_uid: String,
+    update_ids: Vec<u64>,
+}
+
+/// Enqueue the content of the dump at `dump_path` as updates of the running database.
+///
+/// Every index of the dump is created if it does not exist yet, or cleared otherwise,
+/// then its settings and documents are enqueued as regular updates. The indexes of an
+/// incremental dump are not cleared, the documents deleted since its base dump are
+/// deleted instead, and the indexes deleted since are deleted too.
+pub fn enqueue_dump_import(
+    data: &Data,
+    dump_path: &Path,
+    document_batch_size: usize,
+) -> Result<Vec<IndexImportResponse>, Error> {
+    let tmp_dir = TempDir::new()?;
+    let tmp_dir_path = tmp_dir.path();
+
+    compression::from_tar_gz(dump_path, tmp_dir_path)?;
+
+    let metadata = DumpMetadata::from_path(&tmp_dir_path)?;
+    match metadata.dump_version {
+        DumpVersion::V1 => (),
+    }
+
+    for index_uid in metadata.deleted_indexes.iter() {
+        if data.db.open_index(index_uid).is_some() {
+            data.db.delete_index(index_uid)?;
+        }
+    }
+
+    let mut responses = Vec::new();
+    for index_info in metadata.indexes.iter() {
+        let index_uid = &index_info.uid;
+        let incremental = metadata.is_incremental(index_uid);
+        let mut update_ids = Vec::new();
+
+        let index = match data.db.open_index(index_uid) {
+            Some(index) if incremental => index,
+            Some(index) => {
+                update_ids.push(data.db.update_write(|w| index.clear_all(w))?);
+                index
+            },
+            None => {
+                index::create_index_sync(&data.db, index_uid.clone(), index_info.name.clone(), index_info.primary_key.clone())?;
+                data.db.open_index(index_uid).ok_or(Error::index_not_found(index_uid))?
+            },
+        };
+
+        let index_path = tmp_dir_path.join(index_uid);
+
+        let settings = settings_from_path(&index_path)?;
+        let settings = settings.to_update().map_err(|_e| Error::dump_failed())?;
+        update_ids.push(data.db.update_write(|w| index.settings_update(w, settings))?);
+
+        let documents = {
+            let file = File::open(&index_path.join("documents.jsonl"))?;
+            let reader = std::io::BufReader::new(file);
+            let deserializer = serde_json::Deserializer::from_reader(reader);
+            deserializer.into_iter::<IndexMap<String, serde_json::Value>>()
+        };
+
+        let mut addition = index.documents_addition();
+        let mut batch_len = 0;
+        for document in documents {
+            addition.update_document(document?);
+            batch_len += 1;
+            if batch_len == document_batch_size {
+                let batch = std::mem::replace(&mut addition, index.documents_addition());
+                update_ids.push(data.db.update_write(|w| batch.finalize(w))?);
+                batch_len = 0;
+            }
+        }
+
+        if batch_len > 0 {
+            update_ids.push(data.db.update_write(|w| addition.finalize(w))?);
+        }
+
+        if incremental {
+            let deleted_documents = deleted_documents_from_path(&index_path)?;
+            if !deleted_documents.is_empty() {
+                let mut deletion = index.documents_deletion();
+                deletion.extend(deleted_documents);
+                update_ids.push(data.db.update_write(|w| deletion.finalize(w))?);
+            }
+        }
+
+        responses.push(IndexImportResponse { index_uid: index_uid.clone(), update_ids });
+    }
+
+    Ok(responses)
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum DumpStatus {
@@ -235,6 +456,52 @@ impl DumpInfo {
     pub fn set_current(&self) {
         *DUMP_INFO.lock().unwrap() = Some(self.clone());
     }
+
+    /// Sets this dump as the current one, unless another dump is already in progress.
+    pub fn start(&self) -> Result<(), Error> {
+        let mut current = DUMP_INFO.lock().unwrap();
+        if current.as_ref().map_or(false, DumpInfo::dump_already_in_progress) {
+            return Err(Error::dump_conflict());
+        }
+        *current = Some(self.clone());
+        Ok(())
+    }
+}
+
+/// An index to export in a selective dump, along with the filter its documents must match.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DumpIndexSelection {
+    pub uid: String,
+    pub filter: Option<String>,
+}
+
+/// What a dump must export, by default every index with all its documents and updates.
+#[derive(Debug, Default, Clone)]
+pub struct DumpOptions {
+    /// The uid of a previous dump, only the documents that changed since are exported.
+    pub base_dump: Option<String>,
+    /// The indexes to export, all of them when `None`.
+    pub indexes: Option<Vec<DumpIndexSelection>>,
+    /// Whether to leave the `updates.jsonl` files out of the dump.
+    pub skip_updates: bool,
+}
+
+impl DumpOptions {
+    fn filter(&self, index_uid: &str) -> Option<&str> {
+        self.indexes
+            .iter()
+            .flatten()
+            .find(|selection| selection.uid == index_uid)
+            .and_then(|selection| selection.filter.as_deref())
+    }
+
+    fn is_selected(&self, index_uid: &str) -> bool {
+        match &self.indexes {
+            Some(selections) => selections.iter().any(|selection| selection.uid == index_uid),
+            None => true,
+        }
+    }
 }
 
 /// Generate uid from creation date
@@ -247,14 +514,6 @@ pub fn compressed_dumps_folder(dumps_folder: &Path, dump_uid: &str) -> PathBuf {
     dumps_folder.join(format!("{}.tar.gz", dump_uid))
 }
 
-/// Write metadata in dump
-fn dump_metadata(data: &web::Data<Data>, folder_path: &Path, indexes: Vec<IndexResponse>) -> Result<(), Error> {
-    let (db_major, db_minor, db_patch) = data.db.version();
-    let metadata = DumpMetadata::new(indexes, format!("{}.{}.{}", db_major, db_minor, db_patch));
-
-    metadata.to_path(folder_path)
-}
-
 /// Export settings of provided index in dump
 fn dump_index_settings(data: &web::Data<Data>, reader: &MainReader, folder_path: &Path, index_uid: &str) -> Result<(), Error> {
     let settings = crate::routes::setting::get_all_sync(data, reader, index_uid)?;
@@ -277,20 +536,99 @@ fn dump_index_updates(data: &web::Data<Data>, reader: &UpdateReader, folder_path
     Ok(())
 }
 
-/// Export documents of provided index in dump
-fn dump_index_documents(data: &web::Data<Data>, reader: &MainReader, folder_path: &Path, index_uid: &str) -> Result<(), Error> {
+/// Export documents of provided index in dump, along with the hashes of their content.
+///
+/// When the hashes of the documents of the `base` dump are given only the documents added
+/// or changed since are exported, and the ids of the documents deleted since are listed.
+fn dump_index_documents(
+    data: &web::Data<Data>,
+    reader: &MainReader,
+    folder_path: &Path,
+    index: &IndexResponse,
+    filter: Option<&str>,
+    base: Option<&DocumentsHashes>,
+) -> Result<(), Error> {
     let documents_path = folder_path.join("documents.jsonl");
     let file = File::create(documents_path)?;
     let dump_batch_size = data.dump_batch_size;
 
-    let mut offset = 0;
-    loop {
-        let documents = crate::routes::document::get_all_documents_sync(data, reader, index_uid, offset, dump_batch_size, None)?;
-        if documents.is_empty() { break; } else { offset += dump_batch_size; }
+    let mut hashes = DocumentsHashes::new();
+    let mut export_document = |document: IndexMap<String, serde_json::Value>| -> Result<(), Error> {
+        let bytes = serde_json::to_vec(&document)?;
+        let docid = index.primary_key.as_ref().and_then(|key| document.get(key)).and_then(external_docid);
+
+        if let Some(docid) = docid {
+            let mut hasher = SipHasher::new();
+            bytes.hash(&mut hasher);
+            let hash = hasher.finish();
+
+            let unchanged = base.map_or(false, |base| base.get(&docid) == Some(&hash));
+            hashes.insert(docid, hash);
+            if unchanged {
+                return Ok(());
+            }
+        }
 
-        for document in documents {
-            serde_json::to_writer(&file, &document)?;
-            writeln!(&file)?;
+        (&file).write_all(&bytes)?;
+        writeln!(&file)?;
+        Ok(())
+    };
+
+    match filter {
+        Some(filter) => dump_index_filtered_documents(data, reader, &index.uid, filter, &mut export_document)?,
+        None => {
+            let mut offset = 0;
+            loop {
+                let documents = crate::routes::document::get_all_documents_sync(data, reader, &index.uid, offset, dump_batch_size, None)?;
+                if documents.is_empty() { break; } else { offset += dump_batch_size; }
+
+                for document in documents {
+                    export_document(document)?;
+                }
+            }
+        },
+    }
+
+    if let Some(base) = base {
+        let deleted_documents: Vec<_> = base.keys().filter(|docid| !hashes.contains_key(*docid)).collect();
+        let file = File::create(folder_path.join("deleted_documents.json"))?;
+        serde_json::to_writer(file, &deleted_documents)?;
+    }
+
+    let file = File::create(folder_path.join("documents_hashes.json"))?;
+    serde_json::to_writer(file, &hashes)?;
+
+    Ok(())
+}
+
+/// Export the documents of provided index matching the `filter` expression in dump
+fn dump_index_filtered_documents(
+    data: &web::Data<Data>,
+    reader: &MainReader,
+    index_uid: &str,
+    filter: &str,
+    mut export_document: impl FnMut(IndexMap<String, serde_json::Value>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let index = data
+        .db
+        .open_index(index_uid)
+        .ok_or(Error::index_not_found(index_uid))?;
+
+    // an index without schema does not contain any document
+    let schema = match index.main.schema(reader)? {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+    let filter = Filter::parse(filter, &schema)?;
+
+    for document_id in index.documents_fields_counts.documents_ids(reader)? {
+        let document_id = document_id?;
+        if !filter.test(reader, &index, document_id)? {
+            continue;
+        }
+
+        if let Some(document) = index.document::<IndexMap<String, serde_json::Value>>(reader, None, document_id)? {
+            export_document(unflatten_document(document, &schema))?;
         }
     }
 
@@ -306,7 +644,21 @@ fn fail_dump_process<E: std::error::Error>(dump_info: DumpInfo, context: &str, e
 }
 
 /// Main function of dump.
-fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInfo) {
+fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInfo, options: DumpOptions) {
+    let dumped_at = Utc::now();
+
+    // read the metadata and the documents hashes of the base dump of an incremental dump
+    let base = match &options.base_dump {
+        Some(uid) => match BaseDump::from_archive(&compressed_dumps_folder(&dumps_folder, uid)) {
+            Ok(base) => Some(base),
+            Err(e) => {
+                fail_dump_process(dump_info, &format!("reading base dump {}", uid), e);
+                return ;
+            }
+        },
+        None => None,
+    };
+
     // open read transaction on Update
     let update_reader = match data.db.update_read_txn() {
         Ok(r) => r,
@@ -343,9 +695,51 @@ fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInf
             return ;
         }
     };
+    let existing_index_uids: Vec<_> = indexes.iter().map(|index| index.uid.clone()).collect();
+    let indexes: Vec<_> = indexes.into_iter().filter(|index| options.is_selected(&index.uid)).collect();
+
+    // fetch the last processed update of each index
+    let (db_major, db_minor, db_patch) = data.db.version();
+    let mut metadata = DumpMetadata::new(indexes.clone(), format!("{}.{}.{}", db_major, db_minor, db_patch));
+    metadata.dumped_at = Some(dumped_at);
+    metadata.skip_updates = options.skip_updates;
+    for index in &indexes {
+        if let Some(filter) = options.filter(&index.uid) {
+            metadata.filters.insert(index.uid.clone(), filter.to_string());
+        }
+    }
+    for index in &indexes {
+        let last_update = match data.db.open_index(&index.uid) {
+            Some(index) => index.updates_results.last_update(&update_reader),
+            None => Ok(None),
+        };
+        match last_update {
+            Ok(Some((update_id, _))) => { metadata.last_update_ids.insert(index.uid.clone(), update_id); },
+            Ok(None) => (),
+            Err(e) => {
+                fail_dump_process(dump_info, &format!("retrieving last update of index {}", &index.uid), e);
+                return ;
+            }
+        }
+    }
+
+    // an incremental dump only exports the documents that changed since its base dump
+    if let Some(base) = &base {
+        metadata.base_dump = options.base_dump.clone();
+        metadata.incremental_indexes = indexes
+            .iter()
+            .filter(|index| base.hashes.contains_key(&index.uid))
+            .map(|index| index.uid.clone())
+            .collect();
+        metadata.deleted_indexes = base.metadata.indexes
+            .iter()
+            .filter(|index| !existing_index_uids.contains(&index.uid))
+            .map(|index| index.uid.clone())
+            .collect();
+    }
 
     // create metadata
-    if let Err(e) = dump_metadata(&data, &tmp_dir_path, indexes.clone()) {
+    if let Err(e) = metadata.to_path(&tmp_dir_path) {
         fail_dump_process(dump_info, "generating metadata", e);
         return ;
     }
@@ -367,12 +761,16 @@ fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInf
         }
 
         // export documents
-        if let Err(e) = dump_index_documents(&data, &main_reader, &index_path, &index.uid) {
+        let base_hashes = base.as_ref().and_then(|base| base.hashes.get(&index.uid));
+        if let Err(e) = dump_index_documents(&data, &main_reader, &index_path, &index, options.filter(&index.uid), base_hashes) {
             fail_dump_process(dump_info, &format!("generating documents for index {}", &index.uid), e);
             return ;
         }
 
         // export updates
+        if options.skip_updates {
+            continue;
+        }
         if let Err(e) = dump_index_updates(&data, &update_reader, &index_path, &index.uid) {
             fail_dump_process(dump_info, &format!("generating updates for index {}", &index.uid), e);
             return ;
@@ -380,11 +778,20 @@ fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInf
     }
 
     // compress dump in a file named `{dump_uid}.tar.gz` in `dumps_folder`
-    if let Err(e) = crate::helpers::compression::to_tar_gz(&tmp_dir_path, &compressed_dumps_folder(&dumps_folder, &dump_info.uid)) {
+    let dump_path = compressed_dumps_folder(&dumps_folder, &dump_info.uid);
+    if let Err(e) = crate::helpers::compression::to_tar_gz(&tmp_dir_path, &dump_path) {
         fail_dump_process(dump_info, "compressing dump", e);
         return ;
     }
 
+    // upload the dump as `dumps/{dump_uid}.tar.gz` when a remote storage is configured
+    if let Some(remote_storage) = &data.remote_storage {
+        if let Err(e) = remote_storage.upload(&dump_path, &format!("dumps/{}.tar.gz", dump_info.uid)) {
+            fail_dump_process(dump_info, "uploading dump", e);
+            return ;
+        }
+    }
+
     // update dump info to `done`
     let resume = DumpInfo::new(
         dump_info.uid,
@@ -394,30 +801,71 @@ fn dump_process(data: web::Data<Data>, dumps_folder: PathBuf, dump_info: DumpInf
     resume.set_current();
 }
 
-pub fn init_dump_process(data: &web::Data<Data>, dumps_folder: &Path) -> Result<DumpInfo, Error> {
+/// Check that the selected indexes exist and that their filters are valid.
+fn check_dump_selection(data: &web::Data<Data>, selections: &[DumpIndexSelection]) -> Result<(), Error> {
+    let reader = data.db.main_read_txn()?;
+
+    for selection in selections {
+        let index = data
+            .db
+            .open_index(&selection.uid)
+            .ok_or(Error::index_not_found(&selection.uid))?;
+
+        if let (Some(filter), Some(schema)) = (&selection.filter, index.main.schema(&reader)?) {
+            Filter::parse(filter, &schema)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a dump of all the indexes in `dumps_folder` and wait for its completion,
+/// returns the path of the created dump.
+pub fn create_dump(data: &web::Data<Data>, dumps_folder: &Path) -> Result<PathBuf, Error> {
+    create_dir_all(dumps_folder)?;
+
+    let info = DumpInfo::new(generate_uid(), DumpStatus::Processing);
+    info.start()?;
+
+    let dump_path = compressed_dumps_folder(dumps_folder, &info.uid);
+    dump_process(data.clone(), dumps_folder.to_path_buf(), info, DumpOptions::default());
+
+    match DumpInfo::get_current() {
+        Some(DumpInfo { status: DumpStatus::Done, .. }) => Ok(dump_path),
+        Some(DumpInfo { error: Some(error), .. }) => Err(Error::Internal(error)),
+        _ => Err(Error::dump_failed()),
+    }
+}
+
+/// Start the creation of a dump in a new thread, when a base dump uid is given
+/// only the documents that changed since this dump are exported.
+pub fn init_dump_process(data: &web::Data<Data>, dumps_folder: &Path, options: DumpOptions) -> Result<DumpInfo, Error> {
     create_dir_all(dumps_folder).or(Err(Error::dump_failed()))?;
 
-    // check if a dump is already in progress
-    if let Some(resume) = DumpInfo::get_current() {
-        if resume.dump_already_in_progress() {
-            return Err(Error::dump_conflict())
+    if let Some(selections) = &options.indexes {
+        check_dump_selection(data, selections)?;
+    }
+
+    if let Some(uid) = &options.base_dump {
+        if !compressed_dumps_folder(dumps_folder, uid).exists() {
+            return Err(Error::not_found(format!("dump {}", uid)));
         }
     }
 
-    // generate a new dump info
+    // generate a new dump info, fails if a dump is already in progress
     let info = DumpInfo::new(
         generate_uid(),
         DumpStatus::Processing
     );
 
-    info.set_current();
+    info.start()?;
 
     let data = data.clone();
     let dumps_folder = dumps_folder.to_path_buf();
     let info_cloned = info.clone();
     // run dump process in a new thread
     thread::spawn(move || 
-        dump_process(data, dumps_folder, info_cloned)
+        dump_process(data, dumps_folder, info_cloned, options)
     );
 
     Ok(info)
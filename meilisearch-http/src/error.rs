@@ -55,6 +55,7 @@ pub enum Error {
     UnsupportedMediaType,
     DumpAlreadyInProgress,
     DumpProcessFailed,
+    SnapshotAlreadyInProgress,
 }
 
 impl error::Error for Error {}
@@ -82,6 +83,7 @@ impl ErrorCode for Error {
             UnsupportedMediaType => Code::UnsupportedMediaType,
             DumpAlreadyInProgress => Code::DumpAlreadyInProgress,
             DumpProcessFailed => Code::DumpProcessFailed,
+            SnapshotAlreadyInProgress => Code::SnapshotAlreadyInProgress,
         }
     }
 }
@@ -89,6 +91,8 @@ impl ErrorCode for Error {
 #[derive(Debug)]
 pub enum FacetCountError {
     AttributeNotSet(String),
+    EmptySeparator(String),
+    InvalidRange(String),
     SyntaxError(String),
     UnexpectedToken { found: String, expected: &'static [&'static str] },
     NoFacetSet,
@@ -121,6 +125,8 @@ impl fmt::Display for FacetCountError {
 
         match self {
             AttributeNotSet(attr) => write!(f, "Attribute {} is not set as facet", attr),
+            EmptySeparator(attr) => write!(f, "The hierarchy separator of {} can't be empty", attr),
+            InvalidRange(range) => write!(f, "Invalid range {:?}, ranges should be like \"0-50\" or \"100+\"", range),
             SyntaxError(msg) => write!(f, "Syntax error: {}", msg),
             UnexpectedToken { expected, found } => write!(f, "Unexpected {} found, expected {:?}", found, expected),
             NoFacetSet => write!(f, "Can't perform facet count, as no facet is set"),
@@ -192,6 +198,10 @@ impl Error {
     pub fn dump_failed() -> Error {
         Error::DumpProcessFailed
     }
+
+    pub fn snapshot_conflict() -> Error {
+        Error::SnapshotAlreadyInProgress
+    }
 }
 
 impl fmt::Display for Error {
@@ -216,6 +226,7 @@ impl fmt::Display for Error {
             Self::UnsupportedMediaType => f.write_str("Unsupported media type"),
             Self::DumpAlreadyInProgress => f.write_str("Another dump is already in progress"),
             Self::DumpProcessFailed => f.write_str("Dump process failed"),
+            Self::SnapshotAlreadyInProgress => f.write_str("Another snapshot is already in progress"),
         }
     }
 }
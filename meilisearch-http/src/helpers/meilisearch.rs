@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::time::Instant;
@@ -6,11 +7,12 @@ use std::time::Instant;
 use indexmap::IndexMap;
 use log::error;
 use meilisearch_core::{Filter, MainReader};
-use meilisearch_core::facets::FacetFilter;
+use meilisearch_core::facets::{FacetDistributionOptions, FacetFilter, FacetQuery, FacetRange, FacetStats};
 use meilisearch_core::criterion::*;
 use meilisearch_core::settings::RankingRule;
-use meilisearch_core::{Highlight, Index, RankedMap};
-use meilisearch_schema::{FieldId, Schema};
+use meilisearch_core::{Document, Highlight, Index, RankedMap};
+use meilisearch_core::update::unflatten_document;
+use meilisearch_schema::{is_nested_in, FieldId, Schema};
 use meilisearch_tokenizer::is_cjk;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -37,6 +39,16 @@ impl IndexSearchExt for Index {
             matches: false,
             facet_filters: None,
             facets: None,
+            facet_ranges: HashMap::new(),
+            facet_options: FacetDistributionOptions::default(),
+            facet_query: None,
+            disjunctive_facets: false,
+            exhaustive_hits_count: None,
+            pagination: None,
+            search_after: None,
+            distinct: None,
+            group_sizes: false,
+            group_by: None,
         }
     }
 }
@@ -52,7 +64,17 @@ pub struct SearchBuilder<'a> {
     filters: Option<String>,
     matches: bool,
     facet_filters: Option<FacetFilter>,
-    facets: Option<Vec<(FieldId, String)>>
+    facets: Option<Vec<(FieldId, String)>>,
+    facet_ranges: HashMap<String, Vec<FacetRange>>,
+    facet_options: FacetDistributionOptions,
+    facet_query: Option<FacetQuery>,
+    disjunctive_facets: bool,
+    exhaustive_hits_count: Option<usize>,
+    pagination: Option<(usize, usize)>,
+    search_after: Option<SearchAfter>,
+    distinct: Option<(FieldId, usize)>,
+    group_sizes: bool,
+    group_by: Option<(FieldId, usize)>,
 }
 
 impl<'a> SearchBuilder<'a> {
@@ -107,6 +129,72 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
+    pub fn add_facet_ranges(&mut self, ranges: HashMap<String, Vec<FacetRange>>) -> &SearchBuilder {
+        self.facet_ranges = ranges;
+        self
+    }
+
+    pub fn facet_distribution_options(&mut self, options: FacetDistributionOptions) -> &SearchBuilder {
+        self.facet_options = options;
+        self
+    }
+
+    /// Only counts the values of the facets matching the given facet query.
+    pub fn facet_query(&mut self, query: FacetQuery) -> &SearchBuilder {
+        self.facet_query = Some(query);
+        self
+    }
+
+    pub fn disjunctive_facets(&mut self, value: bool) -> &SearchBuilder {
+        self.disjunctive_facets = value;
+        self
+    }
+
+    /// Counts exactly the hits, after the filters and the distinct rule, up to the given cap.
+    pub fn exhaustive_hits_count(&mut self, cap: usize) -> &SearchBuilder {
+        self.exhaustive_hits_count = Some(cap);
+        self
+    }
+
+    /// Returns the hits of a page, starting at 1, along with the total number of pages,
+    /// the number of hits is then counted exactly up to the given cap.
+    pub fn page(&mut self, page: usize, hits_per_page: usize, cap: usize) -> Result<&SearchBuilder, Error> {
+        self.offset = page
+            .saturating_sub(1)
+            .checked_mul(hits_per_page)
+            .ok_or_else(|| Error::bad_request("page and hitsPerPage are too large"))?;
+        self.limit = hits_per_page;
+        self.pagination = Some((page, hits_per_page));
+        Ok(self.exhaustive_hits_count(cap))
+    }
+
+    /// Returns the hits ranked after the hit of the cursor, the last hit of the previous page.
+    pub fn search_after(&mut self, search_after: SearchAfter) -> &SearchBuilder {
+        self.search_after = Some(search_after);
+        self
+    }
+
+    /// Keeps at most `limit` hits for each value of the given attribute, instead of
+    /// the distinct attribute of the index.
+    pub fn distinct(&mut self, field_id: FieldId, limit: usize) -> &SearchBuilder {
+        self.distinct = Some((field_id, limit));
+        self
+    }
+
+    /// Returns the size of the group of each hit kept by the distinct rule, all the
+    /// hits are read to count them, they are always counted when the hits are grouped.
+    pub fn group_sizes(&mut self) -> &SearchBuilder {
+        self.group_sizes = true;
+        self
+    }
+
+    /// Groups the hits by value of the given attribute, in the order of the best hit of each group,
+    /// the offset and the limit then apply to the groups which contain their first `hits_per_group` hits.
+    pub fn group_by(&mut self, field_id: FieldId, hits_per_group: usize) -> &SearchBuilder {
+        self.group_by = Some((field_id, hits_per_group));
+        self
+    }
+
     pub fn search(self, reader: &MainReader) -> Result<SearchResult, ResponseError> {
         let schema = self
             .index
@@ -124,23 +212,51 @@ impl<'a> SearchBuilder<'a> {
 
         if let Some(filter_expression) = &self.filters {
             let filter = Filter::parse(filter_expression, &schema)?;
-            let index = &self.index;
-            query_builder.with_filter(move |id| {
-                let reader = &reader;
-                let filter = &filter;
-                match filter.test(reader, index, id) {
-                    Ok(res) => res,
-                    Err(e) => {
-                        log::warn!("unexpected error during filtering: {}", e);
-                        false
+            let filterable_attributes = self.index.main.filterable_attributes(reader)?.unwrap_or_default();
+            let attributes_for_faceting = self.index.main.attributes_for_faceting(reader)?.unwrap_or_default();
+            // the conditions that can be computed with the stores restrict the documents
+            // before the bucket sort, only the other ones are tested on each document
+            let (docids, remaining) = filter.split_docids(reader, self.index, &filterable_attributes, &attributes_for_faceting)?;
+
+            if let Some(docids) = docids {
+                // a disjunctive facet is not restricted by the filter conditions on its own attribute
+                if self.disjunctive_facets && remaining.is_none() {
+                    for (field_id, _) in self.facets.iter().flatten() {
+                        let docids = match filter.without_attribute(*field_id) {
+                            Some(filter) => filter.docids(reader, self.index, &filterable_attributes, &attributes_for_faceting)?,
+                            None => None,
+                        };
+                        query_builder.with_disjunctive_filter_docids(*field_id, docids);
                     }
                 }
-            });
+                query_builder.with_filter_docids(docids)
+            }
+
+            if let Some(filter) = remaining {
+                let index = &self.index;
+                query_builder.with_filter(move |id| {
+                    let reader = &reader;
+                    let filter = &filter;
+                    match filter.test(reader, index, id) {
+                        Ok(res) => res,
+                        Err(e) => {
+                            log::warn!("unexpected error during filtering: {}", e);
+                            false
+                        }
+                    }
+                });
+            }
         }
 
-        if let Some(field) = self.index.main.distinct_attribute(reader)? {
+        // the hits of each group are kept by the distinct rule of the attribute to group by
+        let distinct = match self.group_by.or(self.distinct) {
+            Some(distinct) => Some(distinct),
+            None => self.index.main.distinct_attribute(reader)?.map(|field| (field, 1)),
+        };
+
+        if let Some((field, limit)) = distinct {
             let index = &self.index;
-            query_builder.with_distinct(1, move |id| {
+            query_builder.with_distinct(limit, move |id| {
                 match index.document_attribute_bytes(reader, id, field) {
                     Ok(Some(bytes)) => {
                         let mut s = SipHasher::new();
@@ -154,9 +270,24 @@ impl<'a> SearchBuilder<'a> {
 
         query_builder.set_facet_filter(self.facet_filters);
         query_builder.set_facets(self.facets);
+        query_builder.set_facet_ranges(self.facet_ranges);
+        query_builder.set_facet_distribution_options(self.facet_options);
+        query_builder.set_facet_query(self.facet_query);
+        query_builder.set_disjunctive_facets(self.disjunctive_facets);
+        query_builder.set_exhaustive_hits_count(self.exhaustive_hits_count);
+        query_builder.set_search_after(self.search_after);
+        query_builder.set_group_sizes(self.group_sizes || self.group_by.is_some());
+        // the offset and the limit apply to the groups of hits
+        query_builder.set_group_hits(self.group_by.is_some());
+
+        let end = self
+            .offset
+            .checked_add(self.limit)
+            .ok_or_else(|| Error::bad_request("offset and limit are too large"))?;
+        let range = self.offset..end;
 
         let start = Instant::now();
-        let result = query_builder.query(reader, self.query.as_deref(), self.offset..(self.offset + self.limit));
+        let result = query_builder.query(reader, self.query.as_deref(), range);
         let search_result = result.map_err(Error::search_documents)?;
         let time_ms = start.elapsed().as_millis() as usize;
 
@@ -187,8 +318,7 @@ impl<'a> SearchBuilder<'a> {
             },
         }
 
-        let mut hits = Vec::with_capacity(self.limit);
-        for doc in search_result.documents {
+        let format_hit = |doc: Document, group_size: Option<usize>| -> Result<SearchHit, ResponseError> {
             let mut document: IndexMap<String, Value> = self
                 .index
                 .document(reader, Some(&all_attributes), doc.id)
@@ -198,7 +328,7 @@ impl<'a> SearchBuilder<'a> {
                 ))?;
 
             let mut formatted = document.iter()
-                .filter(|(key, _)| all_formatted.contains(key.as_str()))
+                .filter(|(key, _)| all_formatted.iter().any(|attr| is_nested_in(key, attr)))
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect();
 
@@ -226,16 +356,64 @@ impl<'a> SearchBuilder<'a> {
             };
 
             if let Some(attributes_to_retrieve) = &self.attributes_to_retrieve {
-                document.retain(|key, _| attributes_to_retrieve.contains(&key.to_string()))
+                document.retain(|key, _| attributes_to_retrieve.iter().any(|attr| is_nested_in(key, attr)))
             }
 
-            let hit = SearchHit {
-                document,
-                formatted,
+            // the dotted fields are returned in their original nested shape
+            Ok(SearchHit {
+                document: unflatten_document(document, &schema),
+                formatted: unflatten_document(formatted, &schema),
                 matches_info,
-            };
+                group_size,
+            })
+        };
 
-            hits.push(hit);
+        let mut group_sizes = search_result.group_sizes.unwrap_or_default();
+        let mut hits = None;
+        let mut groups = None;
+        let mut next_search_after = None;
+
+        match self.group_by {
+            Some((field, _)) => {
+                // the hits of the requested groups are sorted, the groups are created in the order of their best hit,
+                // the documents without value for the attribute are each in their own group
+                let mut positions = HashMap::new();
+                let mut all_groups: Vec<(Option<&[u8]>, Vec<Document>)> = Vec::new();
+                for doc in search_result.documents {
+                    let key = self.index.document_attribute_bytes(reader, doc.id, field)?;
+                    match key.map(|key| positions.entry(key)) {
+                        Some(Entry::Occupied(entry)) => all_groups[*entry.get()].1.push(doc),
+                        Some(Entry::Vacant(entry)) => {
+                            entry.insert(all_groups.len());
+                            all_groups.push((key, vec![doc]));
+                        }
+                        None => all_groups.push((None, vec![doc])),
+                    }
+                }
+
+                let mut search_groups = Vec::with_capacity(all_groups.len());
+                for (key, docs) in all_groups {
+                    let key = match key {
+                        Some(bytes) => serde_json::from_slice(bytes).map_err(Error::internal)?,
+                        None => Value::Null,
+                    };
+                    let nb_hits = group_sizes.get(&docs[0].id).copied().unwrap_or(1);
+                    let hits = docs.into_iter().map(|doc| format_hit(doc, None)).collect::<Result<_, _>>()?;
+                    search_groups.push(SearchGroup { key, nb_hits, hits });
+                }
+                groups = Some(search_groups);
+            }
+            None => {
+                // a full page may be followed by other hits, which start after its last hit
+                next_search_after = search_result.search_after.as_ref().map(SearchAfter::encode);
+
+                let mut search_hits = Vec::with_capacity(search_result.documents.len());
+                for doc in search_result.documents {
+                    let group_size = group_sizes.remove(&doc.id);
+                    search_hits.push(format_hit(doc, group_size)?);
+                }
+                hits = Some(search_hits);
+            }
         }
 
         let results = SearchResult {
@@ -247,7 +425,21 @@ impl<'a> SearchBuilder<'a> {
             processing_time_ms: time_ms,
             query: self.query.unwrap_or_default(),
             facets_distribution: search_result.facets,
+            facets_stats: search_result.facets_stats,
+            hierarchical_facets_distribution: search_result.hierarchical_facets.filter(|facets| !facets.is_empty()),
             exhaustive_facets_count: search_result.exhaustive_facets_count,
+            page: self.pagination.map(|(page, _)| page),
+            hits_per_page: self.pagination.map(|(_, hits_per_page)| hits_per_page),
+            total_pages: self.pagination.map(|(_, hits_per_page)| match hits_per_page {
+                0 => 0,
+                hits_per_page => {
+                    let remainder = search_result.nb_hits % hits_per_page;
+                    search_result.nb_hits / hits_per_page + (remainder != 0) as usize
+                }
+            }),
+            next_search_after,
+            groups,
+            nb_groups: search_result.nb_groups,
         };
 
         Ok(results)
@@ -326,12 +518,16 @@ pub struct SearchHit {
     pub formatted: IndexMap<String, Value>,
     #[serde(rename = "_matchesInfo", skip_serializing_if = "Option::is_none")]
     pub matches_info: Option<MatchesInfos>,
+    #[serde(rename = "_groupSize", skip_serializing_if = "Option::is_none")]
+    pub group_size: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchResult {
-    pub hits: Vec<SearchHit>,
+    /// The hits, absent when the hits are returned in groups.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hits: Option<Vec<SearchHit>>,
     pub offset: usize,
     pub limit: usize,
     pub nb_hits: usize,
@@ -339,9 +535,35 @@ pub struct SearchResult {
     pub processing_time_ms: usize,
     pub query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub facets_distribution: Option<HashMap<String, HashMap<String, usize>>>,
+    pub facets_distribution: Option<HashMap<String, IndexMap<String, usize>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets_stats: Option<HashMap<String, FacetStats>>,
+    /// The values of the hierarchical facets counted by level, the first level first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hierarchical_facets_distribution: Option<HashMap<String, Vec<IndexMap<String, usize>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exhaustive_facets_count: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hits_per_page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_search_after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<SearchGroup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nb_groups: Option<usize>,
+}
+
+/// The hits sharing a value of the attribute to group by, with the number of hits of the group.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchGroup {
+    pub key: Value,
+    pub nb_hits: usize,
+    pub hits: Vec<SearchHit>,
 }
 
 /// returns the start index and the length on the crop.
@@ -395,7 +617,7 @@ fn crop_text(
     let matches = matches
         .take_while(|m| (m.char_index as usize) + (m.char_length as usize) <= start + count)
         .map(|m| Highlight {
-            char_index: m.char_index - start as u16,
+            char_index: m.char_index - start as u32,
             ..m
         })
         .collect();
@@ -443,7 +665,7 @@ fn calculate_matches(
     for m in matches.iter() {
         if let Some(attribute) = schema.name(FieldId::new(m.attribute)) {
             if let Some(ref attributes_to_retrieve) = attributes_to_retrieve {
-                if !attributes_to_retrieve.contains(attribute) {
+                if !attributes_to_retrieve.iter().any(|attr| is_nested_in(attribute, attr)) {
                     continue;
                 }
             }
@@ -480,7 +702,7 @@ fn calculate_highlights(
     let mut highlight_result = document.clone();
 
     for (attribute, matches) in matches.iter() {
-        if attributes_to_highlight.contains(attribute) {
+        if attributes_to_highlight.iter().any(|attr| is_nested_in(attribute, attr)) {
             if let Some(Value::String(value)) = document.get(attribute) {
                 let value: Vec<_> = value.chars().collect();
                 let mut highlighted_value = String::new();
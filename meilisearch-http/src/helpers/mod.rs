@@ -2,6 +2,7 @@ pub mod authentication;
 pub mod meilisearch;
 pub mod normalize_path;
 pub mod compression;
+pub mod remote_storage;
 
 pub use authentication::Authentication;
 pub use normalize_path::NormalizePath;
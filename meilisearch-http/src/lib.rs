@@ -9,6 +9,7 @@ pub mod routes;
 pub mod analytics;
 pub mod snapshot;
 pub mod dump;
+pub mod migration;
 
 use actix_http::Error;
 use actix_service::ServiceFactory;
@@ -50,6 +51,7 @@ pub fn create_app(
         .service(routes::load_css)
         .configure(routes::document::services)
         .configure(routes::index::services)
+        .configure(routes::alias::services)
         .configure(routes::search::services)
         .configure(routes::setting::services)
         .configure(routes::stop_words::services)
@@ -58,6 +60,7 @@ pub fn create_app(
         .configure(routes::stats::services)
         .configure(routes::key::services)
         .configure(routes::dump::services)
+        .configure(routes::snapshot::services)
 }
 
 pub fn index_update_callback_txn(index: Index, index_uid: &str, data: &Data, mut writer: &mut MainWriter) -> Result<(), String> {
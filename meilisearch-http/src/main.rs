@@ -1,12 +1,14 @@
+use std::path::{Path, PathBuf};
 use std::{env, thread};
 
 use actix_cors::Cors;
 use actix_web::{middleware, HttpServer};
 use main_error::MainError;
 use meilisearch_http::helpers::NormalizePath;
+use meilisearch_http::helpers::remote_storage::{self, RemoteStorage};
 use meilisearch_http::{create_app, index_update_callback, Data, Opt};
 use structopt::StructOpt;
-use meilisearch_http::{snapshot, dump};
+use meilisearch_http::{snapshot, dump, migration};
 
 mod analytics;
 
@@ -52,10 +54,35 @@ async fn main() -> Result<(), MainError> {
         _ => unreachable!(),
     }
 
+    // the snapshots and dumps of a remote storage are first downloaded in a temporary directory
+    let download_dir = tempfile::TempDir::new()?;
+    let fetch_remote = |path: &PathBuf| -> Result<PathBuf, MainError> {
+        if !remote_storage::is_remote_url(path) {
+            return Ok(path.clone());
+        }
+        let storage = RemoteStorage::from_opt(&opt).ok_or("a remote storage endpoint is required to load a remote file")?;
+        let dest = download_dir.path().join("download.tar.gz");
+        // a missing remote object is reported like a missing local file
+        if !storage.download(&path.to_string_lossy(), &dest)? {
+            return Ok(path.clone());
+        }
+        Ok(dest)
+    };
+
+    // a backup left by an interrupted migration is the only complete copy of the database
+    migration::recover_backup(Path::new(&opt.db_path))?;
+
     if let Some(path) = &opt.load_from_snapshot {
-        snapshot::load_snapshot(&opt.db_path, path, opt.ignore_snapshot_if_db_exists, opt.ignore_missing_snapshot)?;
+        let path = fetch_remote(path)?;
+        snapshot::load_snapshot(&opt.db_path, &path, opt.ignore_snapshot_if_db_exists, opt.ignore_missing_snapshot)?;
     }
 
+    let migration = if opt.migrate_db {
+        migration::prepare_migration(&opt)?
+    } else {
+        None
+    };
+
     let data = Data::new(opt.clone())?;
 
     if !opt.no_analytics {
@@ -70,8 +97,13 @@ async fn main() -> Result<(), MainError> {
     }));
 
 
+    if let Some(migration) = migration {
+        migration.finish(&data, opt.dump_batch_size)?;
+    }
+
     if let Some(path) = &opt.import_dump {
-        dump::import_dump(&data, path, opt.dump_batch_size)?;
+        let path = fetch_remote(path)?;
+        dump::import_dump(&data, &path, opt.dump_batch_size)?;
     }
 
     if let Some(path) = &opt.snapshot_path {
@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use actix_web::web;
+use log::{error, info, warn};
+use meilisearch_core::settings::{Settings, DEFAULT_RANKING_RULES};
+use meilisearch_core::{DatabaseOptions, LegacyDatabase, LegacyIndex, MainReader};
+use meilisearch_schema::{FieldId, Schema};
+use tempfile::TempDir;
+
+use crate::dump::{self, DumpMetadata};
+use crate::error::Error;
+use crate::helpers::compression;
+use crate::routes::index::IndexResponse;
+use crate::routes::setting::{get_displayed_attributes, get_indexed_attributes};
+use crate::{Data, Opt};
+
+/// The migration of a database written by another version of the engine.
+///
+/// The outdated database is moved to a backup directory and dumped with the same
+/// machinery as the dump route, the dump is then imported in a new database. The
+/// databases written with the first storage format are read with a dedicated reader.
+/// The backup is removed once the importation succeeded and restored otherwise, a backup
+/// left by an interrupted migration is restored on startup by `recover_backup`.
+pub struct Migration {
+    db_path: PathBuf,
+    backup_path: PathBuf,
+    dump_path: PathBuf,
+    // the dump is removed along with this directory once the migration is over
+    _dump_dir: TempDir,
+}
+
+/// Infer the path of the backup of the database from its path.
+fn backup_path(db_path: &Path) -> PathBuf {
+    let mut backup_path = db_path.as_os_str().to_owned();
+    backup_path.push(".backup");
+    PathBuf::from(backup_path)
+}
+
+/// Infer the path the backup of the database is moved to before being removed.
+fn removed_backup_path(db_path: &Path) -> PathBuf {
+    let mut removed_path = backup_path(db_path).into_os_string();
+    removed_path.push(".removed");
+    PathBuf::from(removed_path)
+}
+
+/// Restore the backup of a migration interrupted before its end, the database at `db_path`
+/// is replaced by the backup which is migrated again on the next `prepare_migration`.
+/// The remains of the backup of a migration that succeeded are removed.
+pub fn recover_backup(db_path: &Path) -> Result<(), Error> {
+    let removed_path = removed_backup_path(db_path);
+    if removed_path.exists() {
+        fs::remove_dir_all(&removed_path)?;
+    }
+
+    let backup_path = backup_path(db_path);
+    if !backup_path.exists() {
+        return Ok(());
+    }
+
+    warn!("Found the backup of an interrupted migration at {:?}, restoring it", backup_path);
+    // the database is either missing or partially imported
+    if db_path.exists() {
+        fs::remove_dir_all(db_path)?;
+    }
+    fs::rename(&backup_path, db_path)?;
+
+    Ok(())
+}
+
+/// Dump the database of `opt.db_path` if it was written by another version of the engine,
+/// returns `None` when the database does not need to be migrated.
+pub fn prepare_migration(opt: &Opt) -> Result<Option<Migration>, Error> {
+    let db_path = Path::new(&opt.db_path);
+    if !db_path.exists() || !meilisearch_core::is_outdated(db_path)? {
+        return Ok(None);
+    }
+
+    let format = meilisearch_core::storage_format(db_path)?;
+    if format > meilisearch_core::STORAGE_FORMAT {
+        return Err(meilisearch_core::Error::StorageFormatMismatch(format).into());
+    }
+
+    let backup_path = backup_path(db_path);
+    if backup_path.exists() {
+        return Err(Error::Internal(format!("a migration backup already exists at {:?}", backup_path)));
+    }
+
+    let version = meilisearch_core::database_version(db_path)?;
+    info!("Migrating the database at {:?} written by version {:?}...", db_path, version);
+
+    fs::rename(db_path, &backup_path)?;
+
+    let dump = match format {
+        1 => dump_legacy(opt, &backup_path),
+        _ => dump_outdated(opt, &backup_path),
+    };
+
+    let migration = dump.map(|(dump_dir, dump_path)| Migration {
+        db_path: db_path.to_path_buf(),
+        backup_path: backup_path.clone(),
+        dump_path,
+        _dump_dir: dump_dir,
+    });
+
+    if migration.is_err() {
+        fs::rename(&backup_path, db_path)?;
+    }
+
+    migration.map(Some)
+}
+
+/// Dump the outdated database at `backup_path` in a temporary directory.
+fn dump_outdated(opt: &Opt, backup_path: &Path) -> Result<(TempDir, PathBuf), Error> {
+    let dump_dir = TempDir::new()?;
+
+    let mut outdated_opt = opt.clone();
+    outdated_opt.db_path = backup_path.to_string_lossy().to_string();
+    // the intermediate dump must not be uploaded
+    outdated_opt.remote_storage_endpoint = None;
+
+    let data = web::Data::new(Data::new_outdated(outdated_opt).map_err(|e| Error::Internal(e.to_string()))?);
+    let dump = dump::create_dump(&data, dump_dir.path());
+
+    // the outdated database must be shut down before its files are moved
+    let data = Arc::try_unwrap(data.into_inner())
+        .map_err(|_| Error::internal("the outdated database is still in use"))?;
+    data.close()?;
+
+    Ok((dump_dir, dump?))
+}
+
+/// Dump the database at `backup_path` written with the first storage format in a temporary
+/// directory, the dump has the layout of the dumps created by the dump route.
+fn dump_legacy(opt: &Opt, backup_path: &Path) -> Result<(TempDir, PathBuf), Error> {
+    let dump_dir = TempDir::new()?;
+    let content_dir = TempDir::new()?;
+
+    let options = DatabaseOptions {
+        main_map_size: opt.max_mdb_size,
+        update_map_size: opt.max_udb_size,
+        max_indexed_words: opt.max_indexed_words,
+    };
+    let db = LegacyDatabase::open(backup_path, &options)?;
+    let reader = db.main_read_txn()?;
+
+    let mut indexes = Vec::new();
+    for index_uid in db.indexes_uids(&reader)? {
+        let index = match db.open_index(&reader, &index_uid)? {
+            Some(index) => index,
+            None => {
+                error!("Index {} is referenced in the indexes list but cannot be found", index_uid);
+                continue;
+            }
+        };
+
+        let missing = |what: &str| Error::Internal(format!("Impossible to get the {} of index {}", what, index_uid));
+        let name = index.main.name(&reader)?.ok_or_else(|| missing("name"))?;
+        let created_at = index.main.created_at(&reader)?.ok_or_else(|| missing("create date"))?;
+        let updated_at = index.main.updated_at(&reader)?.ok_or_else(|| missing("last update date"))?;
+        let schema = index.schema(&reader)?;
+
+        let index_path = content_dir.path().join(&index_uid);
+        fs::create_dir_all(&index_path)?;
+
+        let settings = legacy_settings(&reader, &index, schema.as_ref())?;
+        dump::settings_to_path(&settings, &index_path)?;
+
+        // the nested objects were not flattened by this format, the documents are dumped as they are
+        let file = File::create(index_path.join("documents.jsonl"))?;
+        // an index without schema does not contain any document
+        if let Some(schema) = schema.clone() {
+            for document in index.documents(&reader, schema)? {
+                serde_json::to_writer(&file, &document?)?;
+                writeln!(&file)?;
+            }
+        }
+
+        let primary_key = schema.as_ref().and_then(Schema::primary_key).map(str::to_string);
+        indexes.push(IndexResponse { name, uid: index_uid, created_at, updated_at, primary_key });
+    }
+
+    let (major, minor, patch) = db.version();
+    DumpMetadata::new(indexes, format!("{}.{}.{}", major, minor, patch)).to_path(content_dir.path())?;
+
+    let dump_path = dump_dir.path().join("legacy.tar.gz");
+    compression::to_tar_gz(content_dir.path(), &dump_path)?;
+
+    Ok((dump_dir, dump_path))
+}
+
+/// The settings of an index written with the first storage format.
+fn legacy_settings(reader: &MainReader, index: &LegacyIndex, schema: Option<&Schema>) -> Result<Settings, Error> {
+    let stop_words: BTreeSet<String> = index.main.stop_words(reader)?.into_iter().collect();
+
+    let mut synonyms = BTreeMap::new();
+    for synonym in index.main.synonyms(reader)? {
+        let list = index.synonyms.synonyms(reader, synonym.as_bytes())?;
+        synonyms.insert(synonym, list);
+    }
+
+    let ranking_rules = index
+        .main
+        .ranking_rules(reader)?
+        .unwrap_or_else(|| DEFAULT_RANKING_RULES.to_vec())
+        .into_iter()
+        .map(|r| r.to_string())
+        .collect();
+
+    let name = |id: FieldId| schema.and_then(|schema| schema.name(id)).map(str::to_string);
+    let distinct_attribute = index.distinct_attribute(reader)?.and_then(name);
+    let attributes_for_faceting = index.attributes_for_faceting(reader)?.into_iter().filter_map(name).collect();
+    let filterable_attributes = index.filterable_attributes(reader)?.into_iter().filter_map(name).collect();
+
+    let field_types = index.main.field_types(reader)?.unwrap_or_default();
+
+    Ok(Settings {
+        ranking_rules: Some(Some(ranking_rules)),
+        distinct_attribute: Some(distinct_attribute),
+        searchable_attributes: Some(schema.map(get_indexed_attributes)),
+        displayed_attributes: Some(schema.map(get_displayed_attributes)),
+        stop_words: Some(Some(stop_words)),
+        synonyms: Some(Some(synonyms)),
+        attributes_for_faceting: Some(Some(attributes_for_faceting)),
+        filterable_attributes: Some(Some(filterable_attributes)),
+        field_types: Some(Some(field_types)),
+    })
+}
+
+impl Migration {
+    /// Import the dump of the outdated database in the new database of `data`,
+    /// the backup is removed on success and restored otherwise.
+    pub fn finish(self, data: &Data, document_batch_size: usize) -> Result<(), Error> {
+        match dump::import_dump(data, &self.dump_path, document_batch_size) {
+            Ok(()) => {
+                // the backup is moved first, a partially removed backup must never be restored
+                let removed_path = removed_backup_path(&self.db_path);
+                fs::rename(&self.backup_path, &removed_path)?;
+                fs::remove_dir_all(&removed_path)?;
+                info!("Migration of the database at {:?} succeed", self.db_path);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Migration of the database at {:?} failed, restoring the backup: {}", self.db_path, e);
+                fs::remove_dir_all(&self.db_path)?;
+                fs::rename(&self.backup_path, &self.db_path)?;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(backup_path(Path::new("data.ms")), PathBuf::from("data.ms.backup"));
+        assert_eq!(backup_path(Path::new("/var/lib/meili/data.ms")), PathBuf::from("/var/lib/meili/data.ms.backup"));
+        assert_eq!(removed_backup_path(Path::new("data.ms")), PathBuf::from("data.ms.backup.removed"));
+    }
+
+    #[test]
+    fn recover_interrupted_migration() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("data.ms");
+
+        // the migration was interrupted while the new database was imported
+        fs::create_dir_all(&db_path).unwrap();
+        fs::write(db_path.join("FORMAT"), "3").unwrap();
+        fs::create_dir_all(backup_path(&db_path)).unwrap();
+        fs::write(backup_path(&db_path).join("FORMAT"), "2").unwrap();
+
+        recover_backup(&db_path).unwrap();
+
+        assert!(!backup_path(&db_path).exists());
+        assert_eq!(fs::read_to_string(db_path.join("FORMAT")).unwrap(), "2");
+
+        // the migration was interrupted before the new database was created
+        fs::rename(&db_path, backup_path(&db_path)).unwrap();
+
+        recover_backup(&db_path).unwrap();
+
+        assert!(!backup_path(&db_path).exists());
+        assert_eq!(fs::read_to_string(db_path.join("FORMAT")).unwrap(), "2");
+    }
+
+    #[test]
+    fn recover_removed_backup() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("data.ms");
+
+        // the migration succeeded but the backup was not entirely removed
+        fs::create_dir_all(&db_path).unwrap();
+        fs::write(db_path.join("FORMAT"), "3").unwrap();
+        fs::create_dir_all(removed_backup_path(&db_path)).unwrap();
+
+        recover_backup(&db_path).unwrap();
+
+        assert!(!removed_backup_path(&db_path).exists());
+        assert_eq!(fs::read_to_string(db_path.join("FORMAT")).unwrap(), "3");
+    }
+}
@@ -60,6 +60,14 @@ pub struct Opt {
     #[structopt(long, env = "MEILI_HTTP_PAYLOAD_SIZE_LIMIT", default_value = "10485760")] // 10MB
     pub http_payload_size_limit: usize,
 
+    /// The maximum number of hits counted exactly when a search asks for an exhaustive count
+    #[structopt(long, env = "MEILI_MAX_TOTAL_HITS", default_value = "1000")]
+    pub max_total_hits: usize,
+
+    /// The number of words indexed in each attribute of a document, the following words are not searchable
+    #[structopt(long, env = "MEILI_MAX_INDEXED_WORDS", default_value = "1000")]
+    pub max_indexed_words: usize,
+
     /// Read server certificates from CERTFILE.
     /// This should contain PEM-format certificates
     /// in the right order (the first certificate should
@@ -116,6 +124,11 @@ pub struct Opt {
     #[structopt(long, requires = "snapshot-path", env = "MEILI_SNAPSHOT_INTERVAL_SEC")]
     pub snapshot_interval_sec: Option<u64>,
 
+    /// Defines the number of snapshots kept in the snapshot directory, the oldest ones are removed.
+    /// Zero keeps all the snapshots.
+    #[structopt(long, env = "MEILI_SNAPSHOT_RETENTION", default_value = "1")]
+    pub snapshot_retention: usize,
+
     /// Folder where dumps are created when the dump route is called.
     #[structopt(long, env = "MEILI_DUMPS_FOLDER", default_value = "dumps/")]
     pub dumps_folder: PathBuf,
@@ -127,6 +140,33 @@ pub struct Opt {
     /// The batch size used in the importation process, the bigger it is the faster the dump is created.
     #[structopt(long, env = "MEILI_DUMP_BATCH_SIZE", default_value = "1024")]
     pub dump_batch_size: usize,
+
+    /// Migrate a database written by another version of the engine instead of refusing to start.
+    /// The database is dumped and imported again, a backup is kept until the migration succeeds.
+    #[structopt(long, env = "MEILI_MIGRATE_DB")]
+    pub migrate_db: bool,
+
+    /// The url of an S3-compatible object storage where the dumps and snapshots are uploaded once created.
+    /// The `--load-from-snapshot` and `--import-dump` options also accept `s3://{bucket}/{key}` urls
+    /// of objects of this storage.
+    #[structopt(long, requires = "remote-storage-bucket", env = "MEILI_REMOTE_STORAGE_ENDPOINT")]
+    pub remote_storage_endpoint: Option<String>,
+
+    /// The bucket of the remote storage where the dumps and snapshots are uploaded.
+    #[structopt(long, requires = "remote-storage-endpoint", env = "MEILI_REMOTE_STORAGE_BUCKET")]
+    pub remote_storage_bucket: Option<String>,
+
+    /// The region of the remote storage, defaults to `us-east-1`.
+    #[structopt(long, requires = "remote-storage-endpoint", env = "MEILI_REMOTE_STORAGE_REGION")]
+    pub remote_storage_region: Option<String>,
+
+    /// The access key used to authenticate to the remote storage.
+    #[structopt(long, requires = "remote-storage-endpoint", env = "MEILI_REMOTE_STORAGE_ACCESS_KEY")]
+    pub remote_storage_access_key: Option<String>,
+
+    /// The secret key used to authenticate to the remote storage.
+    #[structopt(long, requires = "remote-storage-endpoint", env = "MEILI_REMOTE_STORAGE_SECRET_KEY")]
+    pub remote_storage_secret_key: Option<String>,
 }
 
 impl Opt {
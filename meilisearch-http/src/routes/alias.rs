@@ -0,0 +1,123 @@
+use actix_web::{delete, get, post, put};
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ResponseError;
+use crate::helpers::Authentication;
+use crate::Data;
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_aliases)
+        .service(get_alias)
+        .service(create_alias)
+        .service(update_alias)
+        .service(delete_alias);
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AliasResponse {
+    alias: String,
+    index_uid: String,
+}
+
+#[derive(Deserialize)]
+struct AliasParam {
+    alias: String,
+}
+
+#[get("/aliases", wrap = "Authentication::Private")]
+async fn list_aliases(data: web::Data<Data>) -> Result<HttpResponse, ResponseError> {
+    let aliases: Vec<_> = data
+        .db
+        .aliases()
+        .into_iter()
+        .map(|(alias, index_uid)| AliasResponse { alias, index_uid })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(aliases))
+}
+
+#[get("/aliases/{alias}", wrap = "Authentication::Private")]
+async fn get_alias(
+    data: web::Data<Data>,
+    path: web::Path<AliasParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = data
+        .db
+        .alias(&path.alias)
+        .ok_or(meilisearch_core::Error::AliasNotFound(path.alias.clone()))?;
+
+    let response = AliasResponse {
+        alias: path.alias.clone(),
+        index_uid,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AliasCreateRequest {
+    alias: String,
+    index_uid: String,
+}
+
+#[post("/aliases", wrap = "Authentication::Private")]
+async fn create_alias(
+    data: web::Data<Data>,
+    body: web::Json<AliasCreateRequest>,
+) -> Result<HttpResponse, ResponseError> {
+    if !body
+        .alias
+        .chars()
+        .all(|x| x.is_ascii_alphanumeric() || x == '-' || x == '_')
+    {
+        return Err(meilisearch_core::Error::InvalidAlias(
+            "an alias can only be composed of alphanumeric characters, hyphens (-) and underscores (_)".to_string(),
+        ).into());
+    }
+
+    data.db.create_alias(&body.alias, &body.index_uid)?;
+
+    let response = AliasResponse {
+        alias: body.alias.clone(),
+        index_uid: body.index_uid.clone(),
+    };
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AliasUpdateRequest {
+    index_uid: String,
+}
+
+#[put("/aliases/{alias}", wrap = "Authentication::Private")]
+async fn update_alias(
+    data: web::Data<Data>,
+    path: web::Path<AliasParam>,
+    body: web::Json<AliasUpdateRequest>,
+) -> Result<HttpResponse, ResponseError> {
+    data.db.update_alias(&path.alias, &body.index_uid)?;
+
+    let response = AliasResponse {
+        alias: path.alias.clone(),
+        index_uid: body.index_uid.clone(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[delete("/aliases/{alias}", wrap = "Authentication::Private")]
+async fn delete_alias(
+    data: web::Data<Data>,
+    path: web::Path<AliasParam>,
+) -> Result<HttpResponse, ResponseError> {
+    if data.db.delete_alias(&path.alias)? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(meilisearch_core::Error::AliasNotFound(path.alias.clone()).into())
+    }
+}
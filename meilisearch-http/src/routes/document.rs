@@ -3,14 +3,14 @@ use std::collections::{BTreeSet, HashSet};
 use actix_web::{delete, get, post, put};
 use actix_web::{web, HttpResponse};
 use indexmap::IndexMap;
-use meilisearch_core::{update, MainReader};
+use meilisearch_core::{update, MainReader, UpdatePriority};
 use serde_json::Value;
 use serde::Deserialize;
 
 use crate::Data;
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
-use crate::routes::{IndexParam, IndexUpdateResponse};
+use crate::routes::{IndexParam, IndexUpdateResponse, UpdatePriorityQuery};
 
 type Document = IndexMap<String, Value>;
 
@@ -54,7 +54,10 @@ async fn get_document(
         .document(&reader, None, internal_id)?
         .ok_or(Error::document_not_found(&path.document_id))?;
 
-    Ok(HttpResponse::Ok().json(document))
+    // a stored document implies a schema
+    let schema = index.main.schema(&reader)?.ok_or(Error::internal("missing schema"))?;
+
+    Ok(HttpResponse::Ok().json(update::unflatten_document(document, &schema)))
 }
 
 #[delete(
@@ -64,6 +67,7 @@ async fn get_document(
 async fn delete_document(
     data: web::Data<Data>,
     path: web::Path<DocumentParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -73,7 +77,13 @@ async fn delete_document(
     let mut documents_deletion = index.documents_deletion();
     documents_deletion.delete_document_by_external_docid(path.document_id.clone());
 
-    let update_id = data.db.update_write(|w| documents_deletion.finalize(w))?;
+    let update_id = data.db.update_write::<_, _, ResponseError>(|w| {
+        let update_id = documents_deletion.finalize(w)?;
+        if let Some(priority) = params.priority {
+            index.set_update_priority(w, update_id, priority)?;
+        }
+        Ok(update_id)
+    })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -99,6 +109,11 @@ pub fn get_all_documents_sync(
         .open_index(index_uid)
         .ok_or(Error::index_not_found(index_uid))?;
 
+    // an index without schema does not contain any document
+    let schema = match index.main.schema(reader)? {
+        Some(schema) => schema,
+        None => return Ok(Vec::new()),
+    };
 
     let documents_ids: Result<BTreeSet<_>, _> = index
         .documents_fields_counts
@@ -115,7 +130,7 @@ pub fn get_all_documents_sync(
         if let Ok(Some(document)) =
             index.document::<Document>(reader, attributes.as_ref(), document_id)
         {
-            documents.push(document);
+            documents.push(update::unflatten_document(document, &schema));
         }
     }
 
@@ -158,6 +173,7 @@ fn find_primary_key(document: &IndexMap<String, Value>) -> Option<String> {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 struct UpdateDocumentsQuery {
     primary_key: Option<String>,
+    priority: Option<UpdatePriority>,
 }
 
 async fn update_multiple_documents(
@@ -199,7 +215,13 @@ async fn update_multiple_documents(
             document_addition.update_document(document);
         }
 
-        Ok(data.db.update_write(|w| document_addition.finalize(w))?)
+        Ok(data.db.update_write::<_, _, ResponseError>(|w| {
+            let update_id = document_addition.finalize(w)?;
+            if let Some(priority) = params.priority {
+                index.set_update_priority(w, update_id, priority)?;
+            }
+            Ok(update_id)
+        })?)
     })?;
     return Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)));
 }
@@ -231,6 +253,7 @@ async fn update_documents(
 async fn delete_documents(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Vec<Value>>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
@@ -245,7 +268,13 @@ async fn delete_documents(
         documents_deletion.delete_document_by_external_docid(document_id);
     }
 
-    let update_id = data.db.update_write(|w| documents_deletion.finalize(w))?;
+    let update_id = data.db.update_write::<_, _, ResponseError>(|w| {
+        let update_id = documents_deletion.finalize(w)?;
+        if let Some(priority) = params.priority {
+            index.set_update_priority(w, update_id, priority)?;
+        }
+        Ok(update_id)
+    })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -254,13 +283,20 @@ async fn delete_documents(
 async fn clear_all_documents(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
         .open_index(&path.index_uid)
         .ok_or(Error::index_not_found(&path.index_uid))?;
 
-    let update_id = data.db.update_write(|w| index.clear_all(w))?;
+    let update_id = data.db.update_write::<_, _, ResponseError>(|w| {
+        let update_id = index.clear_all(w)?;
+        if let Some(priority) = params.priority {
+            index.set_update_priority(w, update_id, priority)?;
+        }
+        Ok(update_id)
+    })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -1,26 +1,51 @@
 use std::fs::File;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use actix_web::error::BlockingError;
+use actix_web::http::header;
 use actix_web::{get, post};
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, web};
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
-use crate::dump::{DumpInfo, DumpStatus, compressed_dumps_folder, init_dump_process};
+use crate::dump::{DumpIndexSelection, DumpInfo, DumpOptions, DumpStatus, compressed_dumps_folder, enqueue_dump_import, init_dump_process};
 use crate::Data;
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
 
 pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(trigger_dump)
-        .service(get_dump_status);
+        .service(get_dump_status)
+        .service(import_dump);
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DumpRequest {
+    /// The uid of a previous dump, only the documents that changed since are exported.
+    since: Option<String>,
+    /// The indexes to export, all of them when missing.
+    indexes: Option<Vec<DumpIndexSelection>>,
+    #[serde(default)]
+    skip_updates: bool,
 }
 
 #[post("/dumps", wrap = "Authentication::Private")]
 async fn trigger_dump(
     data: web::Data<Data>,
+    body: Option<web::Json<DumpRequest>>,
 ) -> Result<HttpResponse, ResponseError> {
     let dumps_folder = Path::new(&data.dumps_folder);
-    match init_dump_process(&data, &dumps_folder) {
+    let options = match body {
+        Some(body) => {
+            let body = body.into_inner();
+            DumpOptions { base_dump: body.since, indexes: body.indexes, skip_updates: body.skip_updates }
+        },
+        None => DumpOptions::default(),
+    };
+    match init_dump_process(&data, &dumps_folder, options) {
         Ok(resume) => Ok(HttpResponse::Accepted().json(resume)),
         Err(e) => Err(e.into())
     }
@@ -62,3 +87,79 @@ async fn get_dump_status(
         Err(Error::not_found("dump does not exist").into())
     }
 }
+
+/// The dump to import, one of the dumps of this instance, the other dumps must be uploaded.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DumpImportRequest {
+    dump_uid: String,
+}
+
+enum DumpSource {
+    Path(PathBuf),
+    Upload(Bytes),
+}
+
+/// Whether the body of the request is a compressed dump and not a `DumpImportRequest`.
+fn is_dump_upload(req: &HttpRequest) -> bool {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+
+    matches!(content_type, Some("application/gzip") | Some("application/x-gzip") | Some("application/octet-stream"))
+}
+
+#[post("/dumps/import", wrap = "Authentication::Private")]
+async fn import_dump(
+    data: web::Data<Data>,
+    req: HttpRequest,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, ResponseError> {
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(Error::bad_request)?;
+        if body.len() + chunk.len() > data.http_payload_size_limit {
+            return Err(Error::PayloadTooLarge.into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let source = if is_dump_upload(&req) {
+        if body.is_empty() {
+            return Err(Error::bad_request("the uploaded dump is empty").into());
+        }
+        DumpSource::Upload(body.freeze())
+    } else {
+        let request: DumpImportRequest = serde_json::from_slice(&body).map_err(Error::bad_request)?;
+        let dump_uid = request.dump_uid;
+        if !dump_uid.chars().all(|x| x.is_ascii_alphanumeric() || x == '-') {
+            return Err(Error::not_found("dump does not exist").into());
+        }
+
+        let dump_path = compressed_dumps_folder(Path::new(&data.dumps_folder), &dump_uid);
+        if !dump_path.exists() {
+            return Err(Error::not_found("dump does not exist").into());
+        }
+        DumpSource::Path(dump_path)
+    };
+
+    // the dump is decompressed and read outside of the server workers
+    let response = web::block(move || match source {
+        DumpSource::Path(dump_path) => enqueue_dump_import(&data, &dump_path, data.dump_batch_size),
+        DumpSource::Upload(bytes) => {
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            enqueue_dump_import(&data, file.path(), data.dump_batch_size)
+        },
+    })
+    .await
+    .map_err(|e| match e {
+        BlockingError::Error(e) => e,
+        BlockingError::Canceled => Error::internal("dump importation canceled"),
+    })?;
+
+    Ok(HttpResponse::Accepted().json(response))
+}
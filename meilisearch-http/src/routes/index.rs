@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use crate::Data;
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
-use crate::routes::IndexParam;
+use crate::routes::{IndexParam, IndexUpdateResponse};
 
 pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(list_indexes)
@@ -18,6 +18,8 @@ pub fn services(cfg: &mut web::ServiceConfig) {
         .service(create_index)
         .service(update_index)
         .service(delete_index)
+        .service(clone_index)
+        .service(swap_indexes)
         .service(get_update_status)
         .service(get_all_updates_status);
 }
@@ -36,8 +38,8 @@ fn generate_uid() -> String {
 pub struct IndexResponse {
     pub name: String,
     pub uid: String,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     pub primary_key: Option<String>,
 }
 
@@ -100,47 +102,52 @@ async fn list_indexes(data: web::Data<Data>) -> Result<HttpResponse, ResponseErr
     Ok(HttpResponse::Ok().json(indexes))
 }
 
-#[get("/indexes/{index_uid}", wrap = "Authentication::Private")]
-async fn get_index(
-    data: web::Data<Data>,
-    path: web::Path<IndexParam>,
-) -> Result<HttpResponse, ResponseError> {
+pub fn get_index_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str) -> Result<IndexResponse, ResponseError> {
     let index = data
         .db
-        .open_index(&path.index_uid)
-        .ok_or(Error::index_not_found(&path.index_uid))?;
+        .open_index(index_uid)
+        .ok_or(Error::index_not_found(index_uid))?;
 
-    let reader = data.db.main_read_txn()?;
-    let name = index.main.name(&reader)?.ok_or(Error::internal(
+    let name = index.main.name(reader)?.ok_or(Error::internal(
             "Impossible to get the name of an index",
     ))?;
     let created_at = index
         .main
-        .created_at(&reader)?
+        .created_at(reader)?
         .ok_or(Error::internal(
                 "Impossible to get the create date of an index",
         ))?;
     let updated_at = index
         .main
-        .updated_at(&reader)?
+        .updated_at(reader)?
         .ok_or(Error::internal(
                 "Impossible to get the last update date of an index",
         ))?;
 
-    let primary_key = match index.main.schema(&reader) {
+    let primary_key = match index.main.schema(reader) {
         Ok(Some(schema)) => match schema.primary_key() {
             Some(primary_key) => Some(primary_key.to_owned()),
             None => None,
         },
         _ => None,
     };
-    let index_response = IndexResponse {
+
+    Ok(IndexResponse {
         name,
-        uid: path.index_uid.clone(),
+        uid: index_uid.to_string(),
         created_at,
         updated_at,
         primary_key,
-    };
+    })
+}
+
+#[get("/indexes/{index_uid}", wrap = "Authentication::Private")]
+async fn get_index(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let reader = data.db.main_read_txn()?;
+    let index_response = get_index_sync(&data, &reader, &path.index_uid)?;
 
     Ok(HttpResponse::Ok().json(index_response))
 }
@@ -252,7 +259,7 @@ struct UpdateIndexResponse {
     name: String,
     uid: String,
     created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     primary_key: Option<String>,
 }
 
@@ -330,6 +337,79 @@ async fn delete_index(
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct IndexCloneRequest {
+    uid: String,
+    name: Option<String>,
+}
+
+#[post("/indexes/{index_uid}/clone", wrap = "Authentication::Private")]
+async fn clone_index(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    body: web::Json<IndexCloneRequest>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    if !body
+        .uid
+        .chars()
+        .all(|x| x.is_ascii_alphanumeric() || x == '-' || x == '_')
+    {
+        return Err(Error::InvalidIndexUid.into());
+    }
+
+    let name = body.name.clone().unwrap_or_else(|| body.uid.clone());
+    create_index_sync(&data.db, body.uid.clone(), name, None)?;
+    let destination = data
+        .db
+        .open_index(&body.uid)
+        .ok_or(Error::index_not_found(&body.uid))?;
+
+    // the copy is processed by the update loop of the destination index, before the updates
+    // enqueued on it afterwards, the update loops know the indexes by uid and not by alias
+    let source_uid = data.db.alias(&path.index_uid).unwrap_or_else(|| path.index_uid.clone());
+    let update_id = data.db.update_write(|w| {
+        // the high priority updates can be processed before the updates enqueued earlier,
+        // the copy waits for the last update the source processed and not the last enqueued
+        let after_update = index.updates_results.last_update(w)?.map(|(update_id, _)| update_id);
+        destination.index_clone(w, source_uid, after_update)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SwapIndexesRequest {
+    indexes: (String, String),
+}
+
+#[post("/swap-indexes", wrap = "Authentication::Private")]
+async fn swap_indexes(
+    data: web::Data<Data>,
+    body: web::Json<SwapIndexesRequest>,
+) -> Result<HttpResponse, ResponseError> {
+    let (first, second) = &body.indexes;
+
+    data.db.swap_indexes(first, second).map_err(|e| match e {
+        meilisearch_core::Error::IndexNotFound(uid) => ResponseError::from(Error::index_not_found(uid)),
+        e => ResponseError::from(e),
+    })?;
+
+    let reader = data.db.main_read_txn()?;
+    let indexes = vec![
+        get_index_sync(&data, &reader, first)?,
+        get_index_sync(&data, &reader, second)?,
+    ];
+
+    Ok(HttpResponse::Ok().json(indexes))
+}
+
 #[derive(Deserialize)]
 struct UpdateParam {
     index_uid: String,
@@ -1,12 +1,15 @@
 use actix_web::{get, HttpResponse};
+use meilisearch_core::UpdatePriority;
 use serde::{Deserialize, Serialize};
 
+pub mod alias;
 pub mod document;
 pub mod health;
 pub mod index;
 pub mod key;
 pub mod search;
 pub mod setting;
+pub mod snapshot;
 pub mod stats;
 pub mod stop_words;
 pub mod synonym;
@@ -29,6 +32,12 @@ impl IndexUpdateResponse {
     }
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UpdatePriorityQuery {
+    pub priority: Option<UpdatePriority>,
+}
+
 #[get("/")]
 pub async fn load_html() -> HttpResponse {
     HttpResponse::Ok()
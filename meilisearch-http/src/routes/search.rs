@@ -1,9 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
 
 use actix_web::{get, post, web, HttpResponse};
 use log::warn;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::error::{Error, FacetCountError, ResponseError};
 use crate::helpers::meilisearch::{IndexSearchExt, SearchResult};
@@ -11,11 +13,14 @@ use crate::helpers::Authentication;
 use crate::routes::IndexParam;
 use crate::Data;
 
-use meilisearch_core::facets::FacetFilter;
-use meilisearch_schema::{FieldId, Schema};
+use meilisearch_core::criterion::SearchAfter;
+use meilisearch_core::facets::{FacetDistributionOptions, FacetFilter, FacetQuery, FacetRange, FacetSort};
+use meilisearch_schema::{is_nested_in, FieldId, Schema};
 
 pub fn services(cfg: &mut web::ServiceConfig) {
-    cfg.service(search_with_post).service(search_with_url_query);
+    cfg.service(search_with_post)
+        .service(search_with_url_query)
+        .service(search_facet_values);
 }
 
 #[derive(Serialize, Deserialize)]
@@ -32,6 +37,19 @@ pub struct SearchQuery {
     matches: Option<bool>,
     facet_filters: Option<String>,
     facets_distribution: Option<String>,
+    max_values_per_facet: Option<usize>,
+    sort_facet_values_by: Option<FacetSort>,
+    hierarchical_facets: Option<String>,
+    disjunctive_facets: Option<bool>,
+    exhaustive_hits_count: Option<bool>,
+    page: Option<usize>,
+    hits_per_page: Option<usize>,
+    search_after: Option<String>,
+    distinct: Option<String>,
+    distinct_limit: Option<usize>,
+    group_sizes: Option<bool>,
+    group_by: Option<String>,
+    hits_per_group: Option<usize>,
 }
 
 #[get("/indexes/{index_uid}/search", wrap = "Authentication::Public")]
@@ -40,7 +58,7 @@ async fn search_with_url_query(
     path: web::Path<IndexParam>,
     params: web::Query<SearchQuery>,
 ) -> Result<HttpResponse, ResponseError> {
-    let search_result = params.search(&path.index_uid, data)?;
+    let search_result = params.search(&path.index_uid, data, None)?;
     Ok(HttpResponse::Ok().json(search_result))
 }
 
@@ -54,10 +72,32 @@ pub struct SearchQueryPost {
     attributes_to_crop: Option<Vec<String>>,
     crop_length: Option<usize>,
     attributes_to_highlight: Option<Vec<String>>,
-    filters: Option<String>,
+    filters: Option<Value>,
     matches: Option<bool>,
     facet_filters: Option<Value>,
-    facets_distribution: Option<Vec<String>>,
+    facets_distribution: Option<Vec<FacetDistribution>>,
+    max_values_per_facet: Option<usize>,
+    sort_facet_values_by: Option<FacetSort>,
+    hierarchical_facets: Option<BTreeMap<String, String>>,
+    disjunctive_facets: Option<bool>,
+    exhaustive_hits_count: Option<bool>,
+    page: Option<usize>,
+    hits_per_page: Option<usize>,
+    search_after: Option<String>,
+    distinct: Option<String>,
+    distinct_limit: Option<usize>,
+    group_sizes: Option<bool>,
+    group_by: Option<String>,
+    hits_per_group: Option<usize>,
+}
+
+/// An attribute for which to count the values, or attributes associated with
+/// the ranges of numbers to count, like `{"price": ["0-50", "50-100", "100+"]}`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FacetDistribution {
+    Attribute(String),
+    Ranges(BTreeMap<String, Vec<String>>),
 }
 
 impl From<SearchQueryPost> for SearchQuery {
@@ -70,14 +110,35 @@ impl From<SearchQueryPost> for SearchQuery {
             attributes_to_crop: other.attributes_to_crop.map(|attrs| attrs.join(",")),
             crop_length: other.crop_length,
             attributes_to_highlight: other.attributes_to_highlight.map(|attrs| attrs.join(",")),
-            filters: other.filters,
+            filters: other.filters.map(filters_to_string),
             matches: other.matches,
             facet_filters: other.facet_filters.map(|f| f.to_string()),
-            facets_distribution: other.facets_distribution.map(|f| format!("{:?}", f)),
+            facets_distribution: other.facets_distribution.map(|f| json!(f).to_string()),
+            max_values_per_facet: other.max_values_per_facet,
+            sort_facet_values_by: other.sort_facet_values_by,
+            hierarchical_facets: other.hierarchical_facets.map(|h| json!(h).to_string()),
+            disjunctive_facets: other.disjunctive_facets,
+            exhaustive_hits_count: other.exhaustive_hits_count,
+            page: other.page,
+            hits_per_page: other.hits_per_page,
+            search_after: other.search_after,
+            distinct: other.distinct,
+            distinct_limit: other.distinct_limit,
+            group_sizes: other.group_sizes,
+            group_by: other.group_by,
+            hits_per_group: other.hits_per_group,
         }
     }
 }
 
+/// The filters are either an expression or given with the array syntax of the facet filters.
+fn filters_to_string(filters: Value) -> String {
+    match filters {
+        Value::String(filters) => filters,
+        filters => filters.to_string(),
+    }
+}
+
 #[post("/indexes/{index_uid}/search", wrap = "Authentication::Public")]
 async fn search_with_post(
     data: web::Data<Data>,
@@ -85,15 +146,107 @@ async fn search_with_post(
     params: web::Json<SearchQueryPost>,
 ) -> Result<HttpResponse, ResponseError> {
     let query: SearchQuery = params.0.into();
-    let search_result = query.search(&path.index_uid, data)?;
+    let search_result = query.search(&path.index_uid, data, None)?;
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FacetSearchQuery {
+    facet_name: String,
+    facet_query: Option<String>,
+    q: Option<String>,
+    filters: Option<Value>,
+    facet_filters: Option<Value>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct FacetHit {
+    value: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetSearchResult {
+    facet_hits: Vec<FacetHit>,
+    facet_query: String,
+    exhaustive_facets_count: Option<bool>,
+    processing_time_ms: usize,
+}
+
+/// Returns the values of a facet matching the facet query, with the number of documents
+/// matching the search that contain them.
+#[post("/indexes/{index_uid}/facet-search", wrap = "Authentication::Public")]
+async fn search_facet_values(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Json<FacetSearchQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let start = Instant::now();
+    let params = params.into_inner();
+
+    // the values are counted the same way as the facets distribution of a search
+    let query = SearchQuery {
+        q: params.q,
+        offset: None,
+        limit: Some(0),
+        attributes_to_retrieve: None,
+        attributes_to_crop: None,
+        crop_length: None,
+        attributes_to_highlight: None,
+        filters: params.filters.map(filters_to_string),
+        matches: None,
+        facet_filters: params.facet_filters.map(|f| f.to_string()),
+        facets_distribution: Some(json!([params.facet_name]).to_string()),
+        max_values_per_facet: None,
+        sort_facet_values_by: None,
+        hierarchical_facets: None,
+        disjunctive_facets: None,
+        exhaustive_hits_count: None,
+        page: None,
+        hits_per_page: None,
+        search_after: None,
+        distinct: None,
+        distinct_limit: None,
+        group_sizes: None,
+        group_by: None,
+        hits_per_group: None,
+    };
+
+    // only the values matching the facet query are counted
+    let facet_query = params.facet_query.unwrap_or_default();
+    let matcher = FacetQuery::new(&facet_query);
+    let search_result = query.search(&path.index_uid, data, Some(matcher))?;
+
+    let counts = search_result
+        .facets_distribution
+        .and_then(|mut facets| facets.remove(&params.facet_name))
+        .ok_or_else(|| FacetCountError::AttributeNotSet(params.facet_name.clone()))?;
+
+    let mut facet_hits: Vec<_> = counts
+        .into_iter()
+        .filter(|(_, count)| *count != 0)
+        .map(|(value, count)| FacetHit { value, count })
+        .collect();
+    facet_hits.sort_unstable_by(|a, b| (Reverse(a.count), &a.value).cmp(&(Reverse(b.count), &b.value)));
+    facet_hits.truncate(params.limit.unwrap_or(10));
+
+    Ok(HttpResponse::Ok().json(FacetSearchResult {
+        facet_hits,
+        facet_query,
+        exhaustive_facets_count: search_result.exhaustive_facets_count,
+        processing_time_ms: start.elapsed().as_millis() as usize,
+    }))
+}
+
 impl SearchQuery {
     fn search(
         &self,
         index_uid: &str,
         data: web::Data<Data>,
+        facet_query: Option<FacetQuery>,
     ) -> Result<SearchResult, ResponseError> {
         let index = data
             .db
@@ -113,11 +266,85 @@ impl SearchQuery {
 
         let mut search_builder = index.new_search(query);
 
-        if let Some(offset) = self.offset {
-            search_builder.offset(offset);
+        match (self.page, self.hits_per_page) {
+            (None, None) => {
+                if let Some(offset) = self.offset {
+                    search_builder.offset(offset);
+                }
+                if let Some(limit) = self.limit {
+                    search_builder.limit(limit);
+                }
+                if self.exhaustive_hits_count == Some(true) {
+                    search_builder.exhaustive_hits_count(data.max_total_hits);
+                }
+            }
+            (page, hits_per_page) => {
+                if self.offset.is_some() || self.limit.is_some() {
+                    return Err(Error::bad_request("offset and limit can't be used along with page and hitsPerPage").into());
+                }
+                let page = page.unwrap_or(1);
+                if page == 0 {
+                    return Err(Error::bad_request("page must be greater than or equal to 1").into());
+                }
+                search_builder.page(page, hits_per_page.unwrap_or(20), data.max_total_hits)?;
+            }
         }
-        if let Some(limit) = self.limit {
-            search_builder.limit(limit);
+
+        if let Some(search_after) = &self.search_after {
+            if self.offset.is_some() || self.page.is_some() || self.hits_per_page.is_some() {
+                return Err(Error::bad_request("offset, page and hitsPerPage can't be used along with searchAfter").into());
+            }
+            let search_after = SearchAfter::decode(search_after)
+                .map_err(|_| Error::bad_request(format!("invalid searchAfter cursor: {}", search_after)))?;
+            search_builder.search_after(search_after);
+        }
+
+        if self.distinct.is_some() || self.distinct_limit.is_some() {
+            let field_id = match &self.distinct {
+                Some(attribute) => schema
+                    .id(attribute)
+                    .ok_or_else(|| Error::bad_request(format!("the distinct attribute {} does not exist", attribute)))?,
+                None => index
+                    .main
+                    .distinct_attribute(&reader)?
+                    .ok_or_else(|| Error::bad_request("distinctLimit can't be used without a distinct attribute"))?,
+            };
+            let limit = self.distinct_limit.unwrap_or(1);
+            if limit == 0 {
+                return Err(Error::bad_request("distinctLimit must be greater than or equal to 1").into());
+            }
+            search_builder.distinct(field_id, limit);
+        }
+
+        // the group sizes are only counted on demand, all the hits must be read to count them
+        if self.group_sizes.unwrap_or(false) {
+            if self.distinct.is_none() && self.distinct_limit.is_none() {
+                return Err(Error::bad_request("groupSizes can't be used without distinct or distinctLimit").into());
+            }
+            search_builder.group_sizes();
+        }
+
+        match &self.group_by {
+            Some(attribute) => {
+                if self.distinct.is_some() || self.distinct_limit.is_some() || self.search_after.is_some() {
+                    return Err(Error::bad_request("distinct, distinctLimit and searchAfter can't be used along with groupBy").into());
+                }
+                if self.page.is_some() || self.hits_per_page.is_some() {
+                    return Err(Error::bad_request("page and hitsPerPage can't be used along with groupBy").into());
+                }
+                let field_id = schema
+                    .id(attribute)
+                    .ok_or_else(|| Error::bad_request(format!("the attribute to group by {} does not exist", attribute)))?;
+                let hits_per_group = self.hits_per_group.unwrap_or(1);
+                if hits_per_group == 0 {
+                    return Err(Error::bad_request("hitsPerGroup must be greater than or equal to 1").into());
+                }
+                search_builder.group_by(field_id, hits_per_group);
+            }
+            None if self.hits_per_group.is_some() => {
+                return Err(Error::bad_request("hitsPerGroup can't be used without groupBy").into());
+            }
+            None => (),
         }
 
         let available_attributes = schema.displayed_name();
@@ -131,7 +358,8 @@ impl SearchQuery {
                 } else {
                     restricted_attributes = HashSet::new();
                     for attr in attributes_to_retrieve {
-                        if available_attributes.contains(attr) {
+                        // `address` retrieves the nested `address.city` field
+                        if available_attributes.iter().any(|a| is_nested_in(a, attr)) {
                             restricted_attributes.insert(attr);
                             search_builder.add_retrievable_field(attr.to_string());
                         } else {
@@ -160,8 +388,23 @@ impl SearchQuery {
         if let Some(facets) = &self.facets_distribution {
             match index.main.attributes_for_faceting(&reader)? {
                 Some(ref attrs) => {
-                    let field_ids = prepare_facet_list(&facets, &schema, attrs)?;
+                    let (field_ids, ranges) = prepare_facet_list(&facets, &schema, attrs)?;
                     search_builder.add_facets(field_ids);
+                    search_builder.add_facet_ranges(ranges);
+
+                    let mut options = FacetDistributionOptions {
+                        max_values: self.max_values_per_facet,
+                        sort_by: self.sort_facet_values_by.unwrap_or_default(),
+                        ..Default::default()
+                    };
+                    if let Some(hierarchies) = &self.hierarchical_facets {
+                        options.hierarchies = prepare_facet_hierarchies(hierarchies)?;
+                    }
+                    search_builder.facet_distribution_options(options);
+
+                    if let Some(disjunctive) = self.disjunctive_facets {
+                        search_builder.disjunctive_facets(disjunctive);
+                    }
                 }
                 None => return Err(FacetCountError::NoFacetSet.into()),
             }
@@ -204,7 +447,7 @@ impl SearchQuery {
                     for attr in &restricted_attributes {
                         final_attributes.insert(attr.to_string());
                     }
-                } else if available_attributes.contains(attribute) {
+                } else if available_attributes.iter().any(|a| is_nested_in(a, attribute)) {
                     final_attributes.insert(attribute.to_string());
                 } else {
                     warn!("The attributes {:?} present in attributesToHighlight parameter doesn't exist", attribute);
@@ -223,12 +466,17 @@ impl SearchQuery {
                 search_builder.get_matches();
             }
         }
+        if let Some(facet_query) = facet_query {
+            search_builder.facet_query(facet_query);
+        }
+
         search_builder.search(&reader)
     }
 }
 
 /// Parses the incoming string into an array of attributes for which to return a count. It returns
-/// a Vec of attribute names ascociated with their id.
+/// a Vec of attribute names ascociated with their id, and the ranges of numbers to count for the
+/// attributes given as an object like `{"price": ["0-50", "50-100", "100+"]}`.
 ///
 /// An error is returned if the array is malformed, or if it contains attributes that are
 /// unexisting, or not set as facets.
@@ -236,34 +484,81 @@ fn prepare_facet_list(
     facets: &str,
     schema: &Schema,
     facet_attrs: &[FieldId],
-) -> Result<Vec<(FieldId, String)>, FacetCountError> {
+) -> Result<(Vec<(FieldId, String)>, HashMap<String, Vec<FacetRange>>), FacetCountError> {
     let json_array = serde_json::from_str(facets)?;
     match json_array {
         Value::Array(vals) => {
+            let get_field_id = |facet: &str| match schema.id(facet) {
+                Some(id) if facet_attrs.contains(&id) => Ok(Some(id)),
+                Some(_) => Err(FacetCountError::AttributeNotSet(facet.to_string())),
+                None => Ok(None),
+            };
+
             let wildcard = Value::String("*".to_string());
+            let mut field_ids = Vec::with_capacity(facet_attrs.len());
             if vals.iter().any(|f| f == &wildcard) {
-                let attrs = facet_attrs
+                field_ids = facet_attrs
                     .iter()
                     .filter_map(|&id| schema.name(id).map(|n| (id, n.to_string())))
                     .collect();
-                return Ok(attrs);
             }
-            let mut field_ids = Vec::with_capacity(facet_attrs.len());
+
+            let mut ranges = HashMap::new();
             for facet in vals {
                 match facet {
                     Value::String(facet) => {
-                        if let Some(id) = schema.id(&facet) {
-                            if !facet_attrs.contains(&id) {
-                                return Err(FacetCountError::AttributeNotSet(facet));
+                        if let Some(id) = get_field_id(&facet)? {
+                            if !field_ids.iter().any(|(field_id, _)| *field_id == id) {
+                                field_ids.push((id, facet));
                             }
-                            field_ids.push((id, facet));
                         }
                     }
-                    bad_val => return Err(FacetCountError::unexpected_token(bad_val, &["String"])),
+                    Value::Object(map) => {
+                        for (facet, facet_ranges) in map {
+                            let facet_ranges = match facet_ranges {
+                                Value::Array(facet_ranges) => facet_ranges,
+                                bad_val => return Err(FacetCountError::unexpected_token(bad_val, &["[String]"])),
+                            };
+                            let mut parsed = Vec::with_capacity(facet_ranges.len());
+                            for range in facet_ranges {
+                                match range {
+                                    Value::String(range) => match FacetRange::parse(&range) {
+                                        Some(range) => parsed.push(range),
+                                        None => return Err(FacetCountError::InvalidRange(range)),
+                                    },
+                                    bad_val => return Err(FacetCountError::unexpected_token(bad_val, &["String"])),
+                                }
+                            }
+
+                            if let Some(id) = get_field_id(&facet)? {
+                                if !field_ids.iter().any(|(field_id, _)| *field_id == id) {
+                                    field_ids.push((id, facet.clone()));
+                                }
+                                ranges.insert(facet, parsed);
+                            }
+                        }
+                    }
+                    bad_val => return Err(FacetCountError::unexpected_token(bad_val, &["String", "Object"])),
                 }
             }
-            Ok(field_ids)
+            Ok((field_ids, ranges))
         }
         bad_val => Err(FacetCountError::unexpected_token(bad_val, &["[String]"])),
     }
 }
+
+/// Parses the incoming string into the facets whose values are paths, associated with the
+/// separator of their levels, like `{"category": " > "}`.
+fn prepare_facet_hierarchies(hierarchies: &str) -> Result<HashMap<String, String>, FacetCountError> {
+    match serde_json::from_str(hierarchies)? {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(facet, separator)| match separator {
+                Value::String(separator) if separator.is_empty() => Err(FacetCountError::EmptySeparator(facet)),
+                Value::String(separator) => Ok((facet, separator)),
+                bad_val => Err(FacetCountError::unexpected_token(bad_val, &["String"])),
+            })
+            .collect(),
+        bad_val => Err(FacetCountError::unexpected_token(bad_val, &["Object"])),
+    }
+}
@@ -2,14 +2,15 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use actix_web::{delete, get, post};
 use actix_web::{web, HttpResponse};
-use meilisearch_core::{MainReader, UpdateWriter};
+use serde::Deserialize;
+use meilisearch_core::{Index, MainReader, UpdatePriority, UpdateWriter};
 use meilisearch_core::settings::{Settings, SettingsUpdate, UpdateState, DEFAULT_RANKING_RULES};
-use meilisearch_schema::Schema;
+use meilisearch_schema::{FieldType, Schema};
 
 use crate::Data;
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
-use crate::routes::{IndexParam, IndexUpdateResponse};
+use crate::routes::{IndexParam, IndexUpdateResponse, UpdatePriorityQuery};
 
 pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(update_all)
@@ -29,7 +30,13 @@ pub fn services(cfg: &mut web::ServiceConfig) {
         .service(delete_displayed)
         .service(get_attributes_for_faceting)
         .service(delete_attributes_for_faceting)
-        .service(update_attributes_for_faceting);
+        .service(update_attributes_for_faceting)
+        .service(get_filterable_attributes)
+        .service(delete_filterable_attributes)
+        .service(update_filterable_attributes)
+        .service(get_field_types)
+        .service(update_field_types)
+        .service(delete_field_types);
 }
 
 pub fn update_all_settings_txn(
@@ -47,23 +54,58 @@ pub fn update_all_settings_txn(
     Ok(update_id)
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct UpdateSettingsQuery {
+    dry_run: Option<bool>,
+    priority: Option<UpdatePriority>,
+}
+
 #[post("/indexes/{index_uid}/settings", wrap = "Authentication::Private")]
 async fn update_all(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdateSettingsQuery>,
     body: web::Json<Settings>,
 ) -> Result<HttpResponse, ResponseError> {
+    // only report what the update would change, nothing is enqueued
+    if params.dry_run.unwrap_or(false) {
+        let index = data
+            .db
+            .open_index(&path.index_uid)
+            .ok_or(Error::index_not_found(&path.index_uid))?;
+
+        let settings = body.into_inner().to_update().map_err(Error::bad_request)?;
+        let reader = data.db.main_read_txn()?;
+        let impact = index.settings_update_impact(&reader, &settings)?;
+
+        return Ok(HttpResponse::Ok().json(impact));
+    }
+
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
-        Ok(data.db.update_write::<_, _, ResponseError>(|writer| {
-            let settings = body.into_inner().to_update().map_err(Error::bad_request)?;
-            let update_id = index.settings_update(writer, settings)?;
-            Ok(update_id)
-        })?)
+        let settings = body.into_inner().to_update().map_err(Error::bad_request)?;
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
 
+/// Enqueues a settings update, giving it the priority requested by the caller.
+pub fn enqueue_settings_update(
+    data: &Data,
+    index: &Index,
+    settings: SettingsUpdate,
+    priority: Option<UpdatePriority>,
+) -> Result<u64, ResponseError> {
+    data.db.update_write::<_, _, ResponseError>(|writer| {
+        let update_id = index.settings_update(writer, settings)?;
+        if let Some(priority) = priority {
+            index.set_update_priority(writer, update_id, priority)?;
+        }
+        Ok(update_id)
+    })
+}
+
 pub fn get_all_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str) -> Result<Settings, Error> {
     let index = data
         .db
@@ -105,6 +147,17 @@ pub fn get_all_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str
         _ => vec![],
     };
 
+    let filterable_attributes = match (&schema, &index.main.filterable_attributes(&reader)?) {
+        (Some(schema), Some(attrs)) => attrs
+            .iter()
+            .filter_map(|&id| schema.name(id))
+            .map(str::to_string)
+            .collect(),
+        _ => vec![],
+    };
+
+    let field_types = index.main.field_types(reader)?.unwrap_or_default();
+
     let searchable_attributes = schema.as_ref().map(get_indexed_attributes);
     let displayed_attributes = schema.as_ref().map(get_displayed_attributes);
 
@@ -116,6 +169,8 @@ pub fn get_all_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str
         stop_words: Some(Some(stop_words)),
         synonyms: Some(Some(synonyms)),
         attributes_for_faceting: Some(Some(attributes_for_faceting)),
+        filterable_attributes: Some(Some(filterable_attributes)),
+        field_types: Some(Some(field_types)),
     })
 }
 
@@ -134,6 +189,7 @@ async fn get_all(
 async fn delete_all(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -149,11 +205,11 @@ async fn delete_all(
         stop_words: UpdateState::Clear,
         synonyms: UpdateState::Clear,
         attributes_for_faceting: UpdateState::Clear,
+        filterable_attributes: UpdateState::Clear,
+        field_types: UpdateState::Clear,
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -190,6 +246,7 @@ async fn get_rules(
 async fn update_rules(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Option<Vec<String>>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -199,9 +256,7 @@ async fn update_rules(
         };
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -214,6 +269,7 @@ async fn update_rules(
 async fn delete_rules(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -225,9 +281,7 @@ async fn delete_rules(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -262,6 +316,7 @@ async fn get_distinct(
 async fn update_distinct(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Option<String>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -271,9 +326,7 @@ async fn update_distinct(
         };
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -286,6 +339,7 @@ async fn update_distinct(
 async fn delete_distinct(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -297,9 +351,7 @@ async fn delete_distinct(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -330,6 +382,7 @@ async fn get_searchable(
 async fn update_searchable(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Option<Vec<String>>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -340,9 +393,7 @@ async fn update_searchable(
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
 
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -355,6 +406,7 @@ async fn update_searchable(
 async fn delete_searchable(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -366,9 +418,7 @@ async fn delete_searchable(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -401,6 +451,7 @@ async fn get_displayed(
 async fn update_displayed(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Option<BTreeSet<String>>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -410,9 +461,7 @@ async fn update_displayed(
         };
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -425,6 +474,7 @@ async fn update_displayed(
 async fn delete_displayed(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -436,9 +486,7 @@ async fn delete_displayed(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -480,6 +528,7 @@ async fn get_attributes_for_faceting(
 async fn update_attributes_for_faceting(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<Option<Vec<String>>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -489,9 +538,7 @@ async fn update_attributes_for_faceting(
         };
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -504,6 +551,7 @@ async fn update_attributes_for_faceting(
 async fn delete_attributes_for_faceting(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -515,14 +563,155 @@ async fn delete_attributes_for_faceting(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[get(
+    "/indexes/{index_uid}/settings/filterable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn get_filterable_attributes(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let filterable_attributes = data.db.main_read::<_, _, ResponseError>(|reader| {
+        let schema = index.main.schema(reader)?;
+        let attrs = index.main.filterable_attributes(reader)?;
+        let attr_names = match (&schema, &attrs) {
+            (Some(schema), Some(attrs)) => attrs
+                .iter()
+                .filter_map(|&id| schema.name(id))
+                .map(str::to_string)
+                .collect(),
+            _ => vec![],
+        };
+        Ok(attr_names)
+    })?;
+
+    Ok(HttpResponse::Ok().json(filterable_attributes))
+}
+
+#[post(
+    "/indexes/{index_uid}/settings/filterable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn update_filterable_attributes(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
+    body: web::Json<Option<Vec<String>>>,
+) -> Result<HttpResponse, ResponseError> {
+    let update_id = data.get_or_create_index(&path.index_uid, |index| {
+        let settings = Settings {
+            filterable_attributes: Some(body.into_inner()),
+            ..Settings::default()
+        };
+
+        let settings = settings.to_update().map_err(Error::bad_request)?;
+        enqueue_settings_update(&data, index, settings, params.priority)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[delete(
+    "/indexes/{index_uid}/settings/filterable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn delete_filterable_attributes(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let settings = SettingsUpdate {
+        filterable_attributes: UpdateState::Clear,
+        ..SettingsUpdate::default()
+    };
+
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[get(
+    "/indexes/{index_uid}/settings/field-types",
+    wrap = "Authentication::Private"
+)]
+async fn get_field_types(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
         .db
-        .update_write(|w| index.settings_update(w, settings))?;
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let reader = data.db.main_read_txn()?;
+    let field_types = index.main.field_types(&reader)?.unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(field_types))
+}
+
+#[post(
+    "/indexes/{index_uid}/settings/field-types",
+    wrap = "Authentication::Private"
+)]
+async fn update_field_types(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
+    body: web::Json<Option<BTreeMap<String, FieldType>>>,
+) -> Result<HttpResponse, ResponseError> {
+    let update_id = data.get_or_create_index(&path.index_uid, |index| {
+        let settings = Settings {
+            field_types: Some(body.into_inner()),
+            ..Settings::default()
+        };
+
+        let settings = settings.to_update().map_err(Error::bad_request)?;
+        enqueue_settings_update(&data, index, settings, params.priority)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[delete(
+    "/indexes/{index_uid}/settings/field-types",
+    wrap = "Authentication::Private"
+)]
+async fn delete_field_types(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let settings = SettingsUpdate {
+        field_types: UpdateState::Clear,
+        ..SettingsUpdate::default()
+    };
+
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
 
-fn get_indexed_attributes(schema: &Schema) -> Vec<String> {
+pub(crate) fn get_indexed_attributes(schema: &Schema) -> Vec<String> {
     if schema.is_indexed_all() {
         ["*"].iter().map(|s| s.to_string()).collect()
     } else {
@@ -534,7 +723,7 @@ fn get_indexed_attributes(schema: &Schema) -> Vec<String> {
     }
 }
 
-fn get_displayed_attributes(schema: &Schema) -> BTreeSet<String> {
+pub(crate) fn get_displayed_attributes(schema: &Schema) -> BTreeSet<String> {
     if schema.is_displayed_all() {
         ["*"].iter().map(|s| s.to_string()).collect()
     } else {
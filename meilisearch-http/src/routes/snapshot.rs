@@ -0,0 +1,55 @@
+use actix_web::{get, post};
+use actix_web::{HttpResponse, web};
+use serde::Deserialize;
+
+use crate::snapshot::{SnapshotInfo, SnapshotStatus, db_name, init_snapshot_process, snapshot_file};
+use crate::Data;
+use crate::error::{Error, ResponseError};
+use crate::helpers::Authentication;
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(trigger_snapshot)
+        .service(get_snapshot_status);
+}
+
+#[post("/snapshots", wrap = "Authentication::Private")]
+async fn trigger_snapshot(
+    data: web::Data<Data>,
+) -> Result<HttpResponse, ResponseError> {
+    match init_snapshot_process(&data, &data.snapshot_dir) {
+        Ok(resume) => Ok(HttpResponse::Accepted().json(resume)),
+        Err(e) => Err(e.into())
+    }
+}
+
+#[derive(Deserialize)]
+struct SnapshotParam {
+    snapshot_uid: String,
+}
+
+#[get("/snapshots/{snapshot_uid}/status", wrap = "Authentication::Private")]
+async fn get_snapshot_status(
+    data: web::Data<Data>,
+    path: web::Path<SnapshotParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let snapshot_uid = &path.snapshot_uid;
+
+    if let Some(resume) = SnapshotInfo::get_current() {
+        if &resume.uid == snapshot_uid {
+            return Ok(HttpResponse::Ok().json(resume));
+        }
+    }
+
+    let db_name = db_name(&data)?;
+
+    if snapshot_file(&data.snapshot_dir, &db_name, snapshot_uid).exists() {
+        let resume = SnapshotInfo::new(
+            snapshot_uid.into(),
+            SnapshotStatus::Done
+        );
+
+        Ok(HttpResponse::Ok().json(resume))
+    } else {
+        Err(Error::not_found("snapshot does not exist").into())
+    }
+}
@@ -7,7 +7,8 @@ use meilisearch_core::settings::{SettingsUpdate, UpdateState};
 
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
-use crate::routes::{IndexParam, IndexUpdateResponse};
+use crate::routes::setting::enqueue_settings_update;
+use crate::routes::{IndexParam, IndexUpdateResponse, UpdatePriorityQuery};
 use crate::Data;
 
 pub fn services(cfg: &mut web::ServiceConfig) {
@@ -48,6 +49,7 @@ async fn get(
 async fn update(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
     body: web::Json<BTreeMap<String, Vec<String>>>,
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
@@ -56,9 +58,7 @@ async fn update(
             ..SettingsUpdate::default()
         };
 
-        Ok(data
-            .db
-            .update_write(|w| index.settings_update(w, settings))?)
+        enqueue_settings_update(&data, index, settings, params.priority)
     })?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
@@ -71,6 +71,7 @@ async fn update(
 async fn delete(
     data: web::Data<Data>,
     path: web::Path<IndexParam>,
+    params: web::Query<UpdatePriorityQuery>,
 ) -> Result<HttpResponse, ResponseError> {
     let index = data
         .db
@@ -82,9 +83,7 @@ async fn delete(
         ..SettingsUpdate::default()
     };
 
-    let update_id = data
-        .db
-        .update_write(|w| index.settings_update(w, settings))?;
+    let update_id = enqueue_settings_update(&data, &index, settings, params.priority)?;
 
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
@@ -2,13 +2,76 @@ use crate::Data;
 use crate::error::Error;
 use crate::helpers::compression;
 
+use chrono::Utc;
 use log::error;
-use std::fs::create_dir_all;
-use std::path::Path;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, create_dir_all};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::thread;
 use std::time::{Duration};
 use tempfile::TempDir;
 
+// Mutex to share snapshot progress.
+static SNAPSHOT_INFO: Lazy<Mutex<Option<SnapshotInfo>>> = Lazy::new(Mutex::default);
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotStatus {
+    Done,
+    Processing,
+    SnapshotProcessFailed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub uid: String,
+    pub status: SnapshotStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SnapshotInfo {
+    pub fn new(uid: String, status: SnapshotStatus) -> Self {
+        Self { uid, status, error: None }
+    }
+
+    pub fn with_error(mut self, error: String) -> Self {
+        self.status = SnapshotStatus::SnapshotProcessFailed;
+        self.error = Some(error);
+
+        self
+    }
+
+    pub fn snapshot_already_in_progress(&self) -> bool {
+        self.status == SnapshotStatus::Processing
+    }
+
+    pub fn get_current() -> Option<Self> {
+        SNAPSHOT_INFO.lock().unwrap().clone()
+    }
+
+    pub fn set_current(&self) {
+        *SNAPSHOT_INFO.lock().unwrap() = Some(self.clone());
+    }
+
+    /// Set a new snapshot as the current one, unless another one is in progress.
+    fn start(uid: String) -> Result<Self, Error> {
+        let mut current = SNAPSHOT_INFO.lock().unwrap();
+        if let Some(info) = current.as_ref() {
+            if info.snapshot_already_in_progress() {
+                return Err(Error::snapshot_conflict());
+            }
+        }
+
+        let info = Self::new(uid, SnapshotStatus::Processing);
+        *current = Some(info.clone());
+        Ok(info)
+    }
+}
+
 pub fn load_snapshot(
     db_path: &str,
     snapshot_path: &Path,
@@ -36,18 +99,138 @@ pub fn create_snapshot(data: &Data, snapshot_path: &Path) -> Result<(), Error> {
     compression::to_tar_gz(tmp_dir.path(), snapshot_path).map_err(|e| Error::Internal(format!("something went wrong during snapshot compression: {}", e)))
 }
 
+/// Generate uid from creation date
+fn generate_uid() -> String {
+    Utc::now().format("%Y%m%d-%H%M%S%3f").to_string()
+}
+
+pub fn db_name(data: &Data) -> Result<String, Error> {
+    let db_name = Path::new(&data.db_path).file_name().ok_or_else(|| Error::Internal("invalid database name".to_string()))?;
+    Ok(db_name.to_str().unwrap_or("data.ms").to_string())
+}
+
+/// Infer the snapshot file path from the database name and the snapshot uid
+pub fn snapshot_file(snapshot_dir: &Path, db_name: &str, snapshot_uid: &str) -> PathBuf {
+    snapshot_dir.join(format!("{}-{}.tar.gz", db_name, snapshot_uid))
+}
+
+/// Infer the path of the latest scheduled snapshot from the database name, the scheduled
+/// snapshots were not named after their uid before and this name is imported by existing setups.
+pub fn scheduled_snapshot_file(snapshot_dir: &Path, db_name: &str) -> PathBuf {
+    snapshot_dir.join(format!("{}.tar.gz", db_name))
+}
+
+/// Whether `uid` has the format of the uids generated by `generate_uid`, `20201019-120000000`.
+fn is_snapshot_uid(uid: &str) -> bool {
+    uid.len() == 18 && uid.char_indices().all(|(i, c)| if i == 8 { c == '-' } else { c.is_ascii_digit() })
+}
+
+/// Whether `file_name` is the name of a snapshot of the database named `db_name`.
+fn is_snapshot_of(file_name: &str, db_name: &str) -> bool {
+    file_name
+        .strip_prefix(db_name)
+        .and_then(|name| name.strip_prefix('-'))
+        .and_then(|name| name.strip_suffix(".tar.gz"))
+        .map_or(false, is_snapshot_uid)
+}
+
+/// Remove the oldest snapshots of the database from `snapshot_dir`, only the `retention` most
+/// recent ones are kept. The uids are timestamps, sorting the file names sorts the snapshots.
+fn apply_retention(snapshot_dir: &Path, db_name: &str, retention: usize) -> Result<(), Error> {
+    if retention == 0 {
+        return Ok(());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(snapshot_dir)? {
+        let path = entry?.path();
+        let is_snapshot = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| is_snapshot_of(name, db_name));
+        if is_snapshot {
+            snapshots.push(path);
+        }
+    }
+
+    snapshots.sort();
+    let outdated = snapshots.len().saturating_sub(retention);
+    for path in &snapshots[..outdated] {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Make the snapshot at `snapshot_path` available under the name of the latest scheduled snapshot,
+/// the file is replaced at once so that a snapshot imported meanwhile is never incomplete.
+fn link_scheduled_snapshot(snapshot_dir: &Path, db_name: &str, snapshot_path: &Path) -> Result<(), Error> {
+    let scheduled_path = scheduled_snapshot_file(snapshot_dir, db_name);
+    let tmp_path = scheduled_path.with_extension("gz.tmp");
+
+    let _ = fs::remove_file(&tmp_path);
+    if fs::hard_link(snapshot_path, &tmp_path).is_err() {
+        fs::copy(snapshot_path, &tmp_path)?;
+    }
+    fs::rename(&tmp_path, &scheduled_path)?;
+
+    Ok(())
+}
+
+/// Create the snapshot described by `snapshot_info` and update its status, a scheduled snapshot
+/// is also available under the name of the latest scheduled snapshot.
+fn snapshot_process(data: &Data, snapshot_dir: &Path, snapshot_info: SnapshotInfo, scheduled: bool) {
+    let result = db_name(data).and_then(|db_name| {
+        let snapshot_path = snapshot_file(snapshot_dir, &db_name, &snapshot_info.uid);
+        create_snapshot(data, &snapshot_path)?;
+        if scheduled {
+            link_scheduled_snapshot(snapshot_dir, &db_name, &snapshot_path)?;
+        }
+        if let Some(remote_storage) = &data.remote_storage {
+            let key = format!("snapshots/{}-{}.tar.gz", db_name, snapshot_info.uid);
+            remote_storage.upload(&snapshot_path, &key)?;
+        }
+        apply_retention(snapshot_dir, &db_name, data.snapshot_retention)
+    });
+
+    match result {
+        Ok(()) => SnapshotInfo::new(snapshot_info.uid, SnapshotStatus::Done).set_current(),
+        Err(e) => {
+            let error = format!("Unsuccessful snapshot creation: {}", e);
+            error!("{}", error);
+            snapshot_info.with_error(error).set_current();
+        }
+    }
+}
+
+/// Start the creation of a snapshot in `snapshot_dir` in a new thread.
+pub fn init_snapshot_process(data: &Data, snapshot_dir: &Path) -> Result<SnapshotInfo, Error> {
+    create_dir_all(snapshot_dir)?;
+
+    let info = SnapshotInfo::start(generate_uid())?;
+
+    let data = data.clone();
+    let snapshot_dir = snapshot_dir.to_path_buf();
+    let info_cloned = info.clone();
+    // run snapshot process in a new thread
+    thread::spawn(move || snapshot_process(&data, &snapshot_dir, info_cloned, false));
+
+    Ok(info)
+}
+
 pub fn schedule_snapshot(data: Data, snapshot_dir: &Path, time_gap_s: u64) -> Result<(), Error> {
     if snapshot_dir.file_name().is_none() { 
         return Err(Error::Internal("invalid snapshot file path".to_string()));
     }
-    let db_name = Path::new(&data.db_path).file_name().ok_or_else(|| Error::Internal("invalid database name".to_string()))?;
+    db_name(&data)?;
     create_dir_all(snapshot_dir)?;
-    let snapshot_path = snapshot_dir.join(format!("{}.tar.gz", db_name.to_str().unwrap_or("data.ms")));
-    
+    let snapshot_dir = snapshot_dir.to_path_buf();
+
     thread::spawn(move || loop { 
         thread::sleep(Duration::from_secs(time_gap_s));
-        if let Err(e) = create_snapshot(&data, &snapshot_path) {
-            error!("Unsuccessful snapshot creation: {}", e);
+        match SnapshotInfo::start(generate_uid()) {
+            Ok(info) => snapshot_process(&data, &snapshot_dir, info, true),
+            Err(e) => error!("Skipped scheduled snapshot: {}", e),
         }
     });
 
@@ -93,4 +276,43 @@ mod tests {
         let contents = fs::read_to_string(dest_dir.join(file_2_relative)).unwrap();
         assert_eq!(contents, "Hello_file_2");
     }
+
+    #[test]
+    fn test_snapshots_retention() {
+        let tempdir = TempDir::new().unwrap();
+        let snapshot_dir = tempdir.path();
+
+        let uids = ["20201001-120000000", "20201002-120000000", "20201003-120000000"];
+        for uid in &uids {
+            fs::File::create(snapshot_file(snapshot_dir, "data.ms", uid)).unwrap();
+        }
+        // files of another database must not be removed, even when its name starts alike
+        fs::File::create(snapshot_file(snapshot_dir, "other.ms", uids[0])).unwrap();
+        fs::File::create(snapshot_file(snapshot_dir, "data.ms-old", uids[0])).unwrap();
+        fs::File::create(scheduled_snapshot_file(snapshot_dir, "data.ms")).unwrap();
+
+        apply_retention(snapshot_dir, "data.ms", 2).unwrap();
+
+        assert!(!snapshot_file(snapshot_dir, "data.ms", uids[0]).exists());
+        assert!(snapshot_file(snapshot_dir, "data.ms", uids[1]).exists());
+        assert!(snapshot_file(snapshot_dir, "data.ms", uids[2]).exists());
+        assert!(snapshot_file(snapshot_dir, "other.ms", uids[0]).exists());
+        assert!(snapshot_file(snapshot_dir, "data.ms-old", uids[0]).exists());
+        assert!(scheduled_snapshot_file(snapshot_dir, "data.ms").exists());
+    }
+
+    #[test]
+    fn test_link_scheduled_snapshot() {
+        let tempdir = TempDir::new().unwrap();
+        let snapshot_dir = tempdir.path();
+
+        for (uid, content) in &[("20201001-120000000", "first"), ("20201002-120000000", "second")] {
+            let snapshot_path = snapshot_file(snapshot_dir, "data.ms", uid);
+            fs::write(&snapshot_path, content).unwrap();
+            link_scheduled_snapshot(snapshot_dir, "data.ms", &snapshot_path).unwrap();
+        }
+
+        let content = fs::read_to_string(scheduled_snapshot_file(snapshot_dir, "data.ms")).unwrap();
+        assert_eq!(content, "second");
+    }
 }
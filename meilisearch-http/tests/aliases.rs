@@ -0,0 +1,71 @@
+use actix_web::http::StatusCode;
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn search_and_get_documents_through_alias() {
+    let mut server = common::Server::with_uid("products_2026_10_18");
+    server.create_index(json!({ "uid": "products_2026_10_18", "primaryKey": "id" })).await;
+    server.create_index(json!({ "uid": "products_2026_10_19", "primaryKey": "id" })).await;
+    server.add_or_replace_multiple_documents(json!([{ "id": 1, "title": "hello" }])).await;
+
+    let (response, status) = server.create_alias(json!({ "alias": "products", "indexUid": "products_2026_10_18" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(response, json!({ "alias": "products", "indexUid": "products_2026_10_18" }));
+
+    server.uid = "products".to_string();
+
+    let (response, status) = server.get_document(1).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["title"], "hello");
+
+    let (response, status) = server.search_post(json!({ "q": "hello" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+
+    // re-point the alias to the other index
+    let (response, status) = server.update_alias("products", json!({ "indexUid": "products_2026_10_19" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["indexUid"], "products_2026_10_19");
+
+    let (_response, status) = server.get_document(1).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (response, status) = server.list_aliases().await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, json!([{ "alias": "products", "indexUid": "products_2026_10_19" }]));
+
+    let (_response, status) = server.delete_alias("products").await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (response, status) = server.get_alias("products").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(response["errorCode"], "alias_not_found");
+}
+
+#[actix_rt::test]
+async fn create_invalid_aliases() {
+    let mut server = common::Server::with_uid("movies");
+    server.create_index(json!({ "uid": "movies" })).await;
+
+    let (response, status) = server.create_alias(json!({ "alias": "films", "indexUid": "unknown" })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(response["errorCode"], "index_not_found");
+
+    let (response, status) = server.create_alias(json!({ "alias": "movies", "indexUid": "movies" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["errorCode"], "invalid_alias");
+
+    let (_response, status) = server.create_alias(json!({ "alias": "films", "indexUid": "movies" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (response, status) = server.create_alias(json!({ "alias": "films", "indexUid": "movies" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["errorCode"], "alias_already_exists");
+
+    // deleting the index removes the aliases targeting it
+    server.delete_index().await;
+    let (response, _status) = server.list_aliases().await;
+    assert!(response.as_array().unwrap().is_empty());
+}
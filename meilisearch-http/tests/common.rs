@@ -36,6 +36,34 @@ macro_rules! test_post_get_search {
     };
 }
 
+/// Returns the ids of the hits of a search response, in the order of the hits
+pub fn hits_ids(response: &Value) -> Vec<u64> {
+    response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].as_u64().unwrap())
+        .collect()
+}
+
+/// Returns the sorted ids of the hits of a search response
+pub fn sorted_hits_ids(response: &Value) -> Vec<u64> {
+    let mut ids = hits_ids(response);
+    ids.sort_unstable();
+    ids
+}
+
+/// Checks the documents matched by each filter on both search routes
+pub async fn assert_filters(server: &mut Server, cases: &[(&str, Vec<u64>)]) {
+    for (filters, expected) in cases {
+        let query = json!({ "filters": filters });
+        test_post_get_search!(server, query, |response, status_code| {
+            assert_eq!(status_code, 200);
+            assert_eq!(sorted_hits_ids(&response), *expected, "filters: {}", filters);
+        });
+    }
+}
+
 pub struct Server {
     pub uid: String,
     pub data: Data,
@@ -51,6 +79,7 @@ impl Server {
             db_path: tmp_dir.path().join("db").to_str().unwrap().to_string(),
             dumps_folder: tmp_dir.path().join("dump"),
             dump_batch_size: 16,
+            snapshot_path: Some(tmp_dir.path().join("snapshots")),
             http_addr: "127.0.0.1:7700".to_owned(),
             master_key: None,
             env: "development".to_owned(),
@@ -58,6 +87,8 @@ impl Server {
             max_mdb_size: default_db_options.main_map_size,
             max_udb_size: default_db_options.update_map_size,
             http_payload_size_limit: 10000000,
+            max_total_hits: 1000,
+            max_indexed_words: default_db_options.max_indexed_words,
             ..Opt::default()
         };
 
@@ -265,6 +296,38 @@ impl Server {
         self.post_request("/indexes", body).await
     }
 
+    pub async fn clone_index(&mut self, body: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/clone", self.uid);
+        self.post_request(&url, body).await
+    }
+
+    pub async fn swap_indexes(&mut self, body: Value) -> (Value, StatusCode) {
+        self.post_request("/swap-indexes", body).await
+    }
+
+    pub async fn list_aliases(&mut self) -> (Value, StatusCode) {
+        self.get_request("/aliases").await
+    }
+
+    pub async fn get_alias(&mut self, alias: &str) -> (Value, StatusCode) {
+        let url = format!("/aliases/{}", alias);
+        self.get_request(&url).await
+    }
+
+    pub async fn create_alias(&mut self, body: Value) -> (Value, StatusCode) {
+        self.post_request("/aliases", body).await
+    }
+
+    pub async fn update_alias(&mut self, alias: &str, body: Value) -> (Value, StatusCode) {
+        let url = format!("/aliases/{}", alias);
+        self.put_request(&url, body).await
+    }
+
+    pub async fn delete_alias(&mut self, alias: &str) -> (Value, StatusCode) {
+        let url = format!("/aliases/{}", alias);
+        self.delete_request(&url).await
+    }
+
     pub async fn search_multi_index(&mut self, query: &str) -> (Value, StatusCode) {
         let url = format!("/indexes/search?{}", query);
         self.get_request(&url).await
@@ -295,6 +358,11 @@ impl Server {
         self.post_request(&url, body).await
     }
 
+    pub async fn facet_search(&mut self, body: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/facet-search", self.uid);
+        self.post_request(&url, body).await
+    }
+
     pub async fn get_all_updates_status(&mut self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/updates", self.uid);
         self.get_request(&url).await
@@ -484,6 +552,21 @@ impl Server {
         self.delete_request_async(&url).await
     }
 
+    pub async fn get_filterable_attributes(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/filterable-attributes", self.uid);
+        self.get_request(&url).await
+    }
+
+    pub async fn update_filterable_attributes(&mut self, body: Value) {
+        let url = format!("/indexes/{}/settings/filterable-attributes", self.uid);
+        self.post_request_async(&url, body).await;
+    }
+
+    pub async fn delete_filterable_attributes(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/filterable-attributes", self.uid);
+        self.delete_request_async(&url).await
+    }
+
     pub async fn get_synonyms(&mut self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/settings/synonyms", self.uid);
         self.get_request(&url).await
@@ -562,8 +645,43 @@ impl Server {
         self.get_request(&url).await
     }
 
-    pub async fn trigger_dump_importation(&mut self, dump_uid: &str) -> (Value, StatusCode) {
-        let url = format!("/dumps/{}/import", dump_uid);
+    pub async fn trigger_snapshot(&self) -> (Value, StatusCode) {
+        self.post_request("/snapshots", Value::Null).await
+    }
+
+    pub async fn get_snapshot_status(&mut self, snapshot_uid: &str) -> (Value, StatusCode) {
+        let url = format!("/snapshots/{}/status", snapshot_uid);
         self.get_request(&url).await
     }
+
+    pub async fn trigger_dump_with(&self, body: Value) -> (Value, StatusCode) {
+        self.post_request("/dumps", body).await
+    }
+
+    pub async fn trigger_incremental_dump(&self, base_dump_uid: &str) -> (Value, StatusCode) {
+        self.post_request("/dumps", json!({ "since": base_dump_uid })).await
+    }
+
+    pub async fn trigger_dump_importation(&mut self, dump_uid: &str) -> (Value, StatusCode) {
+        self.post_request("/dumps/import", json!({ "dumpUid": dump_uid })).await
+    }
+
+    pub async fn upload_dump_importation(&mut self, dump: Vec<u8>) -> (Value, StatusCode) {
+        eprintln!("upload_dump_importation");
+
+        let mut app =
+            test::init_service(meilisearch_http::create_app(&self.data).wrap(NormalizePath)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/dumps/import")
+            .header("Content-Type", "application/gzip")
+            .set_payload(dump)
+            .to_request();
+        let res = test::call_service(&mut app, req).await;
+        let status_code = res.status().clone();
+
+        let body = test::read_body(res).await;
+        let response = serde_json::from_slice(&body).unwrap_or_default();
+        (response, status_code)
+    }
 }
@@ -220,3 +220,49 @@ async fn documents_with_same_id_are_overwritten() {
         "test2"
     );
 }
+
+#[actix_rt::test]
+async fn add_documents_with_nested_objects() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["address.city"])).await;
+
+    let documents = json!([
+        { "id": 1, "name": "Marvin", "address": { "city": "Paris", "street": "rue de Rivoli" } },
+        { "id": 2, "name": "Kevin", "address": { "city": "Lyon", "street": "rue de la République" } },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // documents keep their nested shape
+    let (response, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!({ "id": 1, "name": "Marvin", "address": { "city": "Paris", "street": "rue de Rivoli" } }));
+
+    // nested fields can be searched, filtered, faceted and retrieved independently
+    let body = json!({
+        "q": "republique",
+        "filters": "address.city = Lyon",
+        "facetsDistribution": ["address.city"],
+        "attributesToRetrieve": ["address.city"],
+    });
+    let (response, status_code) = server.search_post(body).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["hits"], json!([{ "address": { "city": "Lyon" } }]));
+    assert_eq!(response["facetsDistribution"]["address.city"]["Lyon"], 1);
+}
+
+#[actix_rt::test]
+async fn add_documents_with_dotted_keys() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+
+    let documents = json!([
+        { "id": 1, "address.city": "Paris", "address": { "street": "rue de Rivoli" } },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the dotted keys sent as is are not nested back
+    let (response, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!({ "id": 1, "address.city": "Paris", "address": { "street": "rue de Rivoli" } }));
+}
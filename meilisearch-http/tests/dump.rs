@@ -9,12 +9,25 @@ use tempfile::TempDir;
 
 #[macro_use] mod common;
 
+async fn trigger_and_wait_dump_with(server: &mut common::Server, body: Value) -> String {
+    let (value, status_code) = server.trigger_dump_with(body).await;
+
+    assert_eq!(status_code, 202);
+
+    let dump_uid = value["uid"].as_str().unwrap().to_string();
+    wait_dump(server, dump_uid).await
+}
+
 async fn trigger_and_wait_dump(server: &mut common::Server) -> String {
     let (value, status_code) = server.trigger_dump().await;
 
     assert_eq!(status_code, 202);
 
     let dump_uid = value["uid"].as_str().unwrap().to_string();
+    wait_dump(server, dump_uid).await
+}
+
+async fn wait_dump(server: &mut common::Server, dump_uid: String) -> String {
 
     for _ in 0..20 as u8 {
         let (value, status_code) = server.get_dump_status(&dump_uid).await;
@@ -272,7 +285,9 @@ async fn dump_index_settings_should_be_valid() {
             "gender",
             "color",
             "tags"
-        ]
+        ],
+        "filterableAttributes": [],
+        "fieldTypes": {}
     });
 
     server.update_all_settings(expected.clone()).await;
@@ -360,3 +375,208 @@ async fn get_unexisting_dump_status_should_return_not_found() {
 
     assert_eq!(status_code, 404);
 }
+
+#[actix_rt::test]
+#[ignore]
+async fn incremental_dump_should_only_export_changed_documents() {
+    let mut server = common::Server::test_server().await;
+
+    let body = json!({
+        "uid": "test2",
+        "primaryKey": "test2_id",
+    });
+
+    server.create_index(body).await;
+
+    let base_uid = trigger_and_wait_dump(&mut server).await;
+
+    let (value, status_code) = server.delete_document(1).await;
+    assert_eq!(status_code, 202);
+    server.wait_update_id(value["updateId"].as_u64().unwrap()).await;
+
+    let (_, status_code) = server.delete_request("/indexes/test2").await;
+    assert_eq!(status_code, 204);
+
+    let (value, status_code) = server.trigger_incremental_dump(&base_uid).await;
+    assert_eq!(status_code, 202);
+    let uid = wait_dump(&mut server, value["uid"].as_str().unwrap().to_string()).await;
+
+    let dumps_folder = Path::new(&server.data().dumps_folder);
+    let tmp_dir = TempDir::new().unwrap();
+    let tmp_dir_path = tmp_dir.path();
+
+    compression::from_tar_gz(&dumps_folder.join(&format!("{}.tar.gz", uid)), tmp_dir_path).unwrap();
+
+    let file = File::open(tmp_dir_path.join("metadata.json")).unwrap();
+    let metadata: serde_json::Value = serde_json::from_reader(file).unwrap();
+
+    let expected = json!({
+        "baseDump": base_uid,
+        "incrementalIndexes": ["test"],
+        "deletedIndexes": ["test2"],
+    });
+
+    assert_json_include!(expected: expected, actual: metadata);
+
+    let file = File::open(tmp_dir_path.join("test").join("documents.jsonl")).unwrap();
+    assert_eq!(read_all_jsonline(file), json!([]));
+
+    let file = File::open(tmp_dir_path.join("test").join("deleted_documents.json")).unwrap();
+    let deleted_documents: serde_json::Value = serde_json::from_reader(file).unwrap();
+    assert_eq!(deleted_documents, json!(["1"]));
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn incremental_dump_with_unexisting_base_should_return_not_found() {
+    let server = common::Server::test_server().await;
+
+    let (_, status_code) = server.trigger_incremental_dump("4242").await;
+
+    assert_eq!(status_code, 404);
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn import_dump_should_enqueue_updates() {
+    let mut server = common::Server::test_server().await;
+
+    let dump_uid = trigger_and_wait_dump(&mut server).await;
+
+    let (value, status_code) = server.delete_document(1).await;
+    assert_eq!(status_code, 202);
+    server.wait_update_id(value["updateId"].as_u64().unwrap()).await;
+
+    let (value, status_code) = server.trigger_dump_importation(&dump_uid).await;
+    assert_eq!(status_code, 202);
+    assert_eq!(value[0]["indexUid"], "test");
+
+    let last_update_id = value[0]["updateIds"].as_array().unwrap().last().unwrap().as_u64().unwrap();
+    server.wait_update_id(last_update_id).await;
+
+    let (_, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 200);
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn import_incremental_dump_should_delete_documents() {
+    let mut server = common::Server::test_server().await;
+
+    let base_uid = trigger_and_wait_dump(&mut server).await;
+
+    let (value, status_code) = server.delete_document(1).await;
+    assert_eq!(status_code, 202);
+    server.wait_update_id(value["updateId"].as_u64().unwrap()).await;
+
+    let (value, status_code) = server.trigger_incremental_dump(&base_uid).await;
+    assert_eq!(status_code, 202);
+    let dump_uid = wait_dump(&mut server, value["uid"].as_str().unwrap().to_string()).await;
+
+    // bring the deleted document back with the base dump
+    let (value, status_code) = server.trigger_dump_importation(&base_uid).await;
+    assert_eq!(status_code, 202);
+    let last_update_id = value[0]["updateIds"].as_array().unwrap().last().unwrap().as_u64().unwrap();
+    server.wait_update_id(last_update_id).await;
+
+    let (_, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 200);
+
+    let (value, status_code) = server.trigger_dump_importation(&dump_uid).await;
+    assert_eq!(status_code, 202);
+    let last_update_id = value[0]["updateIds"].as_array().unwrap().last().unwrap().as_u64().unwrap();
+    server.wait_update_id(last_update_id).await;
+
+    let (_, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 404);
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn import_uploaded_dump_should_enqueue_updates() {
+    let mut server = common::Server::test_server().await;
+
+    let dump_uid = trigger_and_wait_dump(&mut server).await;
+    let dumps_folder = Path::new(&server.data().dumps_folder);
+    let dump = std::fs::read(dumps_folder.join(&format!("{}.tar.gz", dump_uid))).unwrap();
+
+    let (value, status_code) = server.delete_document(1).await;
+    assert_eq!(status_code, 202);
+    server.wait_update_id(value["updateId"].as_u64().unwrap()).await;
+
+    let (value, status_code) = server.upload_dump_importation(dump).await;
+    assert_eq!(status_code, 202);
+    assert_eq!(value[0]["indexUid"], "test");
+
+    let last_update_id = value[0]["updateIds"].as_array().unwrap().last().unwrap().as_u64().unwrap();
+    server.wait_update_id(last_update_id).await;
+
+    let (_, status_code) = server.get_document(1).await;
+    assert_eq!(status_code, 200);
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn import_unexisting_dump_should_return_not_found() {
+    let mut server = common::Server::test_server().await;
+
+    let (_, status_code) = server.trigger_dump_importation("4242").await;
+
+    assert_eq!(status_code, 404);
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn selective_dump_should_only_export_filtered_documents() {
+    let mut server = common::Server::test_server().await;
+
+    let body = json!({
+        "uid": "test2",
+        "primaryKey": "test2_id",
+    });
+
+    server.create_index(body).await;
+
+    let body = json!({
+        "indexes": [{ "uid": "test", "filter": "color = green" }],
+        "skipUpdates": true,
+    });
+    let uid = trigger_and_wait_dump_with(&mut server, body).await;
+
+    let dumps_folder = Path::new(&server.data().dumps_folder);
+    let tmp_dir = TempDir::new().unwrap();
+    let tmp_dir_path = tmp_dir.path();
+
+    compression::from_tar_gz(&dumps_folder.join(&format!("{}.tar.gz", uid)), tmp_dir_path).unwrap();
+
+    let file = File::open(tmp_dir_path.join("metadata.json")).unwrap();
+    let metadata: serde_json::Value = serde_json::from_reader(file).unwrap();
+
+    let expected = json!({
+        "indexes": [{ "uid": "test" }],
+        "filters": { "test": "color = green" },
+        "skipUpdates": true,
+    });
+
+    assert_json_include!(expected: expected, actual: metadata);
+    assert!(!tmp_dir_path.join("test2").exists());
+    assert!(!tmp_dir_path.join("test").join("updates.jsonl").exists());
+
+    let file = File::open(tmp_dir_path.join("test").join("documents.jsonl")).unwrap();
+    let documents = read_all_jsonline(file);
+    let documents = documents.as_array().unwrap();
+
+    assert!(!documents.is_empty());
+    assert!(documents.iter().all(|document| document["color"] == "Green"));
+}
+
+#[actix_rt::test]
+#[ignore]
+async fn selective_dump_of_unexisting_index_should_return_not_found() {
+    let server = common::Server::test_server().await;
+
+    let body = json!({ "indexes": [{ "uid": "unexisting" }] });
+    let (_, status_code) = server.trigger_dump_with(body).await;
+
+    assert_eq!(status_code, 404);
+}
@@ -778,6 +778,74 @@ async fn update_existing_primary_key_is_error() {
     assert_eq!(response["errorType"], "invalid_request_error");
 }
 
+#[actix_rt::test]
+async fn swap_indexes() {
+    let mut server = common::Server::with_uid("products");
+    server.create_index(json!({ "uid": "products", "primaryKey": "id" })).await;
+    server.create_index(json!({ "uid": "products_tmp", "primaryKey": "id" })).await;
+
+    server.add_or_replace_multiple_documents(json!([{ "id": 1, "title": "old" }])).await;
+    server.uid = "products_tmp".to_string();
+    server.add_or_replace_multiple_documents(json!([{ "id": 1, "title": "new" }])).await;
+
+    let (response, status) = server.swap_indexes(json!({ "indexes": ["products", "products_tmp"] })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response[0]["uid"], "products");
+    assert_eq!(response[0]["name"], "products");
+    assert_eq!(response[1]["uid"], "products_tmp");
+
+    let (response, _status) = server.get_document(1).await;
+    assert_eq!(response["title"], "old");
+    server.uid = "products".to_string();
+    let (response, _status) = server.get_document(1).await;
+    assert_eq!(response["title"], "new");
+
+    // the updates follow the data
+    let (response, _status) = server.get_update_status(0).await;
+    assert_eq!(response["status"], "processed");
+
+    let (response, status) = server.swap_indexes(json!({ "indexes": ["products", "unknown"] })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(response["errorCode"], "index_not_found");
+}
+
+#[actix_rt::test]
+async fn clone_index() {
+    let mut server = common::Server::test_server().await;
+
+    let (source_settings, _status) = server.get_all_settings().await;
+    let (source_search, _status) = server.search_get("q=exercitation").await;
+
+    let (response, status) = server.clone_index(json!({ "uid": "test_copy" })).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let update_id = response["updateId"].as_u64().unwrap();
+
+    // the copy is processed by the destination, the updates enqueued in the meantime are kept
+    server.uid = "test_copy".to_string();
+    server.add_or_replace_multiple_documents(json!([{ "id": 1000, "name": "new document" }])).await;
+    let (response, _status) = server.get_update_status(update_id).await;
+    assert_eq!(response["status"], "processed");
+
+    let (response, status) = server.get_index().await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["name"], "test_copy");
+
+    let (settings, _status) = server.get_all_settings().await;
+    assert_json_eq!(source_settings, settings, ordered: false);
+
+    let (response, _status) = server.get_index_stats().await;
+    assert_eq!(response["numberOfDocuments"], 78);
+
+    let (search, _status) = server.search_get("q=exercitation").await;
+    assert_json_eq!(source_search["hits"], search["hits"], ordered: true);
+
+    // the destination must not exist yet
+    server.uid = "test".to_string();
+    let (response, status) = server.clone_index(json!({ "uid": "test_copy" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["errorCode"], "index_already_exists");
+}
+
 #[actix_rt::test]
 async fn test_facets_distribution_attribute() {
     let mut server = common::Server::test_server().await;
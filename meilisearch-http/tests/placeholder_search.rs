@@ -588,3 +588,356 @@ async fn placeholder_search_with_empty_query() {
         assert_eq!(response["hits"].as_array().unwrap().len(), 3);
     });
 }
+
+#[actix_rt::test]
+async fn placeholder_search_with_dates() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_ranking_rules(json!(["asc(published_at)"])).await;
+
+    let documents = json!([
+        { "id": 1, "published_at": "2024-03-01T10:00:00Z" },
+        { "id": 2, "published_at": "2023-06-15T08:30:00+02:00" },
+        { "id": 3, "published_at": "2024-01-15" },
+        { "id": 4, "published_at": "2999-01-01T00:00:00Z" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the dates are sorted by timestamp and not as strings
+    let query = json!({ "filters": "published_at > 2024-01-01" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::hits_ids(&response), [3, 1, 4]);
+    });
+
+    let query = json!({ "filters": "published_at < now AND published_at > now-3650d" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::hits_ids(&response), [2, 3, 1]);
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_filter_operators() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+
+    let documents = json!([
+        { "id": 1, "title": "MacBook Pro", "color": "red", "price": 10, "discount": 5, "description": "" },
+        { "id": 2, "title": "MacBook Air", "color": ["blue", "grey"], "price": 15, "discount": null, "description": "light" },
+        { "id": 3, "title": "iPad Pro", "color": "green", "price": 25, "description": [] },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let cases = vec![
+        ("color IN [red, blue]", vec![1, 2]),
+        ("price 10 TO 20", vec![1, 2]),
+        ("NOT price 10 TO 20", vec![3]),
+        ("discount EXISTS", vec![1, 2]),
+        ("discount IS NULL", vec![2]),
+        ("description IS EMPTY", vec![1, 3]),
+        (r#"title CONTAINS "pro""#, vec![1, 3]),
+        ("title CONTAINS pro AND color IN [green]", vec![3]),
+    ];
+
+    common::assert_filters(&mut server, &cases).await;
+
+    let query = json!({ "filters": "colour IN [red, blue]" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert!(response["message"].as_str().unwrap().contains("attribute `colour` not found"));
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_filterable_attributes() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_filterable_attributes(json!(["color", "price", "in_stock"])).await;
+
+    let (response, _status_code) = server.get_filterable_attributes().await;
+    assert_eq!(response, json!(["color", "price", "in_stock"]));
+
+    let documents = json!([
+        { "id": 1, "title": "MacBook Pro", "color": "Red", "price": 10, "in_stock": true },
+        { "id": 2, "title": "MacBook Air", "color": ["blue", "grey"], "price": 15.5, "in_stock": false },
+        { "id": 3, "title": "iPad Pro", "color": "green", "price": -25, "in_stock": "true" },
+        { "id": 4, "title": "iPad Mini", "color": "10", "price": "10" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the filters only using filterable attributes are computed with the filters store,
+    // the others are evaluated document by document, both must return the same documents
+    let cases = vec![
+        ("color = red", vec![1]),
+        ("color = BLUE OR color = green", vec![2, 3]),
+        ("color IN [grey, green]", vec![2, 3]),
+        ("price > 10", vec![2]),
+        ("price >= 10", vec![1, 2]),
+        ("price < 0", vec![3]),
+        ("price -30 TO 10", vec![1, 3]),
+        ("price = 10", vec![1, 4]),
+        ("color = 10", vec![4]),
+        ("in_stock = true", vec![1, 3]),
+        ("in_stock = false AND NOT color = red", vec![2]),
+        ("NOT price > 10", vec![1, 3, 4]),
+        ("price > 10 OR title = \"iPad Mini\"", vec![2, 4]),
+        ("price > 10 AND title != \"iPad Mini\"", vec![2]),
+        ("price >= 10 AND title != \"iPad Mini\" AND color = red", vec![1]),
+        ("(price < 0 OR title = \"MacBook Air\") AND in_stock = false", vec![2]),
+    ];
+
+    common::assert_filters(&mut server, &cases).await;
+
+    // the deleted documents are removed from the filters store
+    server.delete_document(1).await;
+    let query = json!({ "filters": "price >= 10" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::hits_ids(&response), [2]);
+    });
+
+    // without filterable attributes the filters are evaluated document by document
+    server.delete_filterable_attributes().await;
+    let cases: Vec<_> = cases
+        .into_iter()
+        .map(|(filters, expected)| (filters, expected.into_iter().filter(|id| *id != 1).collect()))
+        .collect();
+    common::assert_filters(&mut server, &cases).await;
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_numeric_facets() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["price", "in_stock", "brand"])).await;
+
+    let documents = json!([
+        { "id": 1, "brand": "Apple", "price": 1200, "in_stock": true },
+        { "id": 2, "brand": "Apple", "price": 30.5, "in_stock": false },
+        { "id": 3, "brand": "Samsung", "price": 75, "in_stock": true },
+        { "id": 4, "brand": "Samsung", "price": [10, 99.9], "in_stock": true },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let query = json!({
+        "facetsDistribution": ["in_stock", "brand", { "price": ["0-50", "50-100", "100+"] }],
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["facetsDistribution"]["price"], json!({ "0-50": 2, "50-100": 2, "100+": 1 }));
+        assert_eq!(response["facetsDistribution"]["in_stock"], json!({ "true": 3, "false": 1 }));
+        assert_eq!(response["facetsStats"], json!({ "price": { "min": 10.0, "max": 1200.0 } }));
+    });
+
+    // numbers and booleans can be used in facet filters
+    let query = json!({
+        "facetFilters": ["in_stock:true", "brand:samsung"],
+        "facetsDistribution": ["price"],
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 2);
+        assert_eq!(response["facetsStats"], json!({ "price": { "min": 10.0, "max": 99.9 } }));
+    });
+
+    let query = json!({ "facetsDistribution": [{ "price": ["100-0"] }] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert!(response["message"].as_str().unwrap().contains("Invalid range"));
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_normalized_numeric_facets() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["size", "reference"])).await;
+
+    let documents = json!([
+        { "id": 1, "size": 10, "reference": "0042" },
+        { "id": 2, "size": 10.0, "reference": "7" },
+        { "id": 3, "size": 12.5, "reference": "abc" },
+        { "id": 4, "size": "10" },
+        { "id": 5, "size": [10, "10"] },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the same number written differently is a single value, the strings get no stats
+    let query = json!({ "facetsDistribution": ["size", "reference"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        // a document containing the number and the string is counted once
+        assert_eq!(response["facetsDistribution"]["size"], json!({ "10": 4, "12.5": 1 }));
+        assert_eq!(response["facetsStats"], json!({ "size": { "min": 10.0, "max": 12.5 } }));
+    });
+
+    // the facet filters match the numbers and the strings with the same value
+    let query = json!({ "facetFilters": ["size:10"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::sorted_hits_ids(&response), [1, 2, 4, 5]);
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_ordered_and_hierarchical_facets() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["brand", "category"])).await;
+
+    let documents = json!([
+        { "id": 1, "brand": "Samsung", "category": "Electronics > Phones" },
+        { "id": 2, "brand": "Samsung", "category": "Electronics > Phones > Smartphones" },
+        { "id": 3, "brand": "Apple", "category": "Electronics > Laptops" },
+        { "id": 4, "brand": "Zte", "category": "Electronics > Phones" },
+        { "id": 5, "brand": "Zte", "category": "Home" },
+        { "id": 6, "brand": "Zte", "category": "Home > Kitchen" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let query = json!({
+        "facetsDistribution": ["brand"],
+        "maxValuesPerFacet": 2,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Zte": 3, "Samsung": 2 }));
+    });
+
+    let query = json!({
+        "facetsDistribution": ["brand"],
+        "sortFacetValuesBy": "alpha",
+        "maxValuesPerFacet": 1,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Apple": 1 }));
+    });
+
+    let query = json!({
+        "facetsDistribution": ["category"],
+        "hierarchicalFacets": { "category": " > " },
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hierarchicalFacetsDistribution"]["category"], json!([
+            { "Electronics": 4, "Home": 2 },
+            { "Electronics > Phones": 3, "Electronics > Laptops": 1, "Home > Kitchen": 1 },
+            { "Electronics > Phones > Smartphones": 1 },
+        ]));
+        assert!(response["facetsDistribution"].get("category").is_none());
+    });
+
+    let query = json!({
+        "facetsDistribution": ["category"],
+        "hierarchicalFacets": { "category": "" },
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_filters_on_facets() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["color", "brand"])).await;
+
+    let documents = json!([
+        { "id": 1, "brand": "Apple", "color": "Red" },
+        { "id": 2, "brand": "Apple", "color": ["Blue", "Green"] },
+        { "id": 3, "brand": "Samsung", "color": "blue" },
+        { "id": 4, "brand": "Samsung", "color": "light blue" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the expression and the array syntax return the same documents
+    let query = json!({ "filters": "(color = red OR color = blue) AND brand = apple" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::sorted_hits_ids(&response), [1, 2]);
+    });
+
+    let query = json!({ "filters": [["color:red", "color:blue"], "brand:apple"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::sorted_hits_ids(&response), [1, 2]);
+    });
+
+    let query = json!({ "filters": "color IN ['light blue', green] AND NOT brand = apple" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::sorted_hits_ids(&response), [4]);
+    });
+
+    let query = json!({ "filters": ["size:12"] });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
+
+#[actix_rt::test]
+async fn placeholder_search_with_disjunctive_facets() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["brand", "color"])).await;
+
+    let documents = json!([
+        { "id": 1, "title": "shoe", "brand": "Nike", "color": "red" },
+        { "id": 2, "title": "shoe", "brand": "Nike", "color": "blue" },
+        { "id": 3, "title": "shoe", "brand": "Adidas", "color": "red" },
+        { "id": 4, "title": "shirt", "brand": "Puma", "color": "red" },
+        { "id": 5, "title": "shoe", "brand": "Puma", "color": "blue" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let query = json!({
+        "facetFilters": ["brand:nike"],
+        "facetsDistribution": ["brand", "color"],
+        "disjunctiveFacets": true,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 2);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Nike": 2, "Adidas": 1, "Puma": 2 }));
+        assert_eq!(response["facetsDistribution"]["color"], json!({ "red": 1, "blue": 1 }));
+    });
+
+    // the other groups of the facet filters and the query still apply
+    let query = json!({
+        "q": "shoe",
+        "facetFilters": [["brand:nike", "brand:adidas"], "color:red"],
+        "facetsDistribution": ["brand", "color"],
+        "disjunctiveFacets": true,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 2);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Nike": 1, "Adidas": 1, "Puma": 0 }));
+        assert_eq!(response["facetsDistribution"]["color"], json!({ "red": 2, "blue": 1 }));
+    });
+
+    // the conditions of the filters on the attribute of a facet are ignored for its count too
+    let query = json!({
+        "filters": "brand = nike AND color = blue",
+        "facetsDistribution": ["brand", "color"],
+        "disjunctiveFacets": true,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 1);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Nike": 1, "Adidas": 0, "Puma": 1 }));
+        assert_eq!(response["facetsDistribution"]["color"], json!({ "red": 1, "blue": 1 }));
+    });
+
+    // without the option every facet is counted on the filtered documents
+    let query = json!({
+        "facetFilters": ["brand:nike"],
+        "facetsDistribution": ["brand"],
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["facetsDistribution"]["brand"], json!({ "Nike": 2, "Adidas": 0, "Puma": 0 }));
+    });
+}
@@ -128,7 +128,7 @@ async fn search_unexpected_params() {
 
     let query = json! ({"lol": "unexpected"});
 
-    let expected = "unknown field `lol`, expected one of `q`, `offset`, `limit`, `attributesToRetrieve`, `attributesToCrop`, `cropLength`, `attributesToHighlight`, `filters`, `matches`, `facetFilters`, `facetsDistribution` at line 1 column 6";
+    let expected = "unknown field `lol`, expected one of `q`, `offset`, `limit`, `attributesToRetrieve`, `attributesToCrop`, `cropLength`, `attributesToHighlight`, `filters`, `matches`, `facetFilters`, `facetsDistribution`, `maxValuesPerFacet`, `sortFacetValuesBy`, `hierarchicalFacets`, `disjunctiveFacets`, `exhaustiveHitsCount`, `page`, `hitsPerPage`, `searchAfter`, `distinct`, `distinctLimit`, `groupBy`, `hitsPerGroup` at line 1 column 6";
 
     let post_query = serde_json::from_str::<meilisearch_http::routes::search::SearchQueryPost>(&query.clone().to_string());
     assert!(post_query.is_err());
@@ -1829,3 +1829,352 @@ async fn update_documents_with_facet_distribution() {
     let (response2, _) = server.search_post(search).await;
     assert_json_eq!(expected_facet_distribution, response2["facetsDistribution"].clone());
 }
+
+#[actix_rt::test]
+async fn test_facet_search() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_attributes_for_faceting(json!(["brand", "color"])).await;
+
+    let documents = json!([
+        { "id": 1, "title": "phone", "brand": "Samsung", "color": "black" },
+        { "id": 2, "title": "phone", "brand": "Samsung", "color": "white" },
+        { "id": 3, "title": "tablet", "brand": "Samsung Electronics", "color": "black" },
+        { "id": 4, "title": "phone", "brand": "Apple", "color": "white" },
+        { "id": 5, "title": "laptop", "brand": "Sandisk", "color": "black" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let (response, status_code) = server.facet_search(json!({ "facetName": "brand", "facetQuery": "sam" })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["facetHits"], json!([
+        { "value": "Samsung", "count": 2 },
+        { "value": "Samsung Electronics", "count": 1 },
+    ]));
+
+    // typo on a long enough query and matching on the second word of a value
+    let (response, _) = server.facet_search(json!({ "facetName": "brand", "facetQuery": "electornics" })).await;
+    assert_eq!(response["facetHits"], json!([{ "value": "Samsung Electronics", "count": 1 }]));
+
+    // restricted by the search query and the facet filters
+    let body = json!({ "facetName": "brand", "facetQuery": "s", "q": "phone", "facetFilters": ["color:black"] });
+    let (response, _) = server.facet_search(body).await;
+    assert_eq!(response["facetHits"], json!([{ "value": "Samsung", "count": 1 }]));
+
+    // without a facet query every value is returned by count
+    let (response, _) = server.facet_search(json!({ "facetName": "color", "limit": 1 })).await;
+    assert_eq!(response["facetHits"], json!([{ "value": "black", "count": 3 }]));
+
+    let (_, status_code) = server.facet_search(json!({ "facetName": "title", "facetQuery": "p" })).await;
+    assert_eq!(status_code, 400);
+}
+
+#[actix_rt::test]
+async fn test_exhaustive_hits_count_and_pages() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+
+    let documents: Vec<_> = (0..30)
+        .map(|id| json!({ "id": id, "title": "hello world", "kind": if id % 2 == 0 { "even" } else { "odd" }, "group": id / 3 }))
+        .collect();
+    server.add_or_replace_multiple_documents(json!(documents)).await;
+
+    // the filter is tested on each document, the number of hits is not counted by default
+    let query = json!({ "q": "hello", "filters": "kind = even", "limit": 2 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["exhaustiveNbHits"], false);
+    });
+
+    let query = json!({ "q": "hello", "filters": "kind = even", "limit": 2, "exhaustiveHitsCount": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 15);
+        assert_eq!(response["exhaustiveNbHits"], true);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 2);
+    });
+
+    let query = json!({ "q": "hello", "filters": "kind = even", "page": 4, "hitsPerPage": 4 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 15);
+        assert_eq!(response["page"], 4);
+        assert_eq!(response["hitsPerPage"], 4);
+        assert_eq!(response["totalPages"], 4);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 3);
+    });
+
+    // the hits are counted after the distinct rule
+    server.update_distinct_attribute(json!("group")).await;
+    let query = json!({ "hitsPerPage": 5 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 10);
+        assert_eq!(response["page"], 1);
+        assert_eq!(response["totalPages"], 2);
+    });
+
+    let query = json!({ "page": 2, "limit": 5 });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "page": 0 });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    // the range of the requested hits must not overflow
+    let query = json!({ "page": 3, "hitsPerPage": u64::MAX });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "offset": u64::MAX, "limit": 1 });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
+
+#[actix_rt::test]
+async fn test_search_after_cursor() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_ranking_rules(json!(["typo", "words", "proximity", "attribute", "wordsPosition", "exactness", "desc(rank)"])).await;
+
+    let documents: Vec<_> = (0..30)
+        .map(|id| json!({ "id": id, "title": if id % 4 == 0 { "hello" } else { "hello world" }, "rank": id % 5, "group": id / 3 }))
+        .collect();
+    server.add_or_replace_multiple_documents(json!(documents)).await;
+
+    async fn walk(server: &mut common::Server, query: Value) -> Vec<u64> {
+        let mut ids = Vec::new();
+        let mut query = query;
+        loop {
+            let (response, status_code) = server.search_post(query.clone()).await;
+            assert_eq!(status_code, 200);
+            ids.extend(common::hits_ids(&response));
+            match response.get("nextSearchAfter") {
+                Some(cursor) => query["searchAfter"] = cursor.clone(),
+                None => return ids,
+            }
+        }
+    }
+
+    // walking the pages with the cursor returns the hits in the same order as a single page
+    for query in vec![
+        json!({ "q": "hello world" }),
+        json!({ "q": "hello", "filters": "rank > 1" }),
+        json!({ "filters": "rank > 1" }),
+    ] {
+        let mut single = query.clone();
+        single["limit"] = json!(30);
+        let (response, _) = server.search_post(single).await;
+        let expected = common::hits_ids(&response);
+        assert!(!expected.is_empty());
+
+        let mut paged = query.clone();
+        paged["limit"] = json!(4);
+        assert_eq!(walk(&mut server, paged).await, expected);
+    }
+
+    // the hit of the cursor can be deleted before the next page is requested
+    let (response, _) = server.search_post(json!({ "q": "hello world", "limit": 8 })).await;
+    let expected = common::hits_ids(&response);
+    let (response, _) = server.search_post(json!({ "q": "hello world", "limit": 4 })).await;
+    let cursor = response["nextSearchAfter"].clone();
+    server.delete_document(expected[3]).await;
+    let (response, status_code) = server.search_post(json!({ "q": "hello world", "limit": 4, "searchAfter": cursor })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(common::hits_ids(&response), expected[4..8]);
+
+    // the cursor of a placeholder search is not a cursor of a search with a query
+    let (response, _) = server.search_post(json!({ "limit": 4 })).await;
+    let query = json!({ "q": "hello", "searchAfter": response["nextSearchAfter"].clone() });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    // the hits of a distinct group already returned are not returned again
+    server.update_distinct_attribute(json!("group")).await;
+    let (response, _) = server.search_post(json!({ "q": "hello", "limit": 30 })).await;
+    let expected = common::hits_ids(&response);
+    assert_eq!(expected.len(), 10);
+    assert_eq!(walk(&mut server, json!({ "q": "hello", "limit": 3 })).await, expected);
+
+    let query = json!({ "q": "hello", "searchAfter": "100" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "q": "hello", "searchAfter": "cursor" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "q": "hello", "offset": 3, "searchAfter": "1" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
+
+#[actix_rt::test]
+async fn test_distinct_at_search_time() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+
+    // 4 products with 5 variants each, and a last product with a single variant
+    let mut documents: Vec<_> = (0..20)
+        .map(|id| json!({ "id": id, "title": "shirt", "product": id / 5, "color": if id % 2 == 0 { "red" } else { "blue" } }))
+        .collect();
+    documents.push(json!({ "id": 20, "title": "shirt", "product": 4, "color": "green" }));
+    server.add_or_replace_multiple_documents(json!(documents)).await;
+
+    let query = json!({ "q": "shirt", "distinct": "product", "groupSizes": true, "limit": 20 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let hits = response["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 5);
+        for hit in hits {
+            let expected = if hit["product"] == 4 { 1 } else { 5 };
+            assert_eq!(hit["_groupSize"], expected);
+        }
+    });
+
+    // the groups are counted after the filters
+    let query = json!({ "q": "shirt", "distinct": "product", "distinctLimit": 3, "groupSizes": true, "filters": "color = red", "limit": 20 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let hits = response["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 10);
+        for hit in hits {
+            assert_eq!(hit["color"], "red");
+            let expected = if hit["product"].as_u64().unwrap() % 2 == 0 { 3 } else { 2 };
+            assert_eq!(hit["_groupSize"], expected);
+        }
+    });
+
+    // the group sizes are only counted on demand
+    let query = json!({ "q": "shirt", "distinct": "product", "limit": 20 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 5);
+        assert!(response["hits"][0].get("_groupSize").is_none());
+    });
+
+    let query = json!({ "q": "shirt", "groupSizes": true });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    // the limit of the groups is applied to the hits of all the pages
+    let query = json!({ "distinct": "product", "distinctLimit": 2, "offset": 4, "limit": 20 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 5);
+    });
+
+    // the distinct attribute of the index is used with the distinct limit alone
+    server.update_distinct_attribute(json!("color")).await;
+    let query = json!({ "q": "shirt", "distinctLimit": 2, "limit": 20 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 5);
+    });
+
+    // the hits of the index distinct rule have no group size
+    let query = json!({ "q": "shirt" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 3);
+        assert!(response["hits"][0].get("_groupSize").is_none());
+    });
+
+    let query = json!({ "q": "shirt", "distinct": "unknown" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "q": "shirt", "distinct": "product", "distinctLimit": 0 });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
+
+#[actix_rt::test]
+async fn test_group_by_with_inner_hits() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+    server.update_ranking_rules(json!(["typo", "words", "proximity", "attribute", "wordsPosition", "exactness", "desc(views)"])).await;
+
+    let documents = json!([
+        { "id": 1, "title": "rust article", "author": "alice", "views": 10 },
+        { "id": 2, "title": "rust article", "author": "bob", "views": 50 },
+        { "id": 3, "title": "rust article", "author": "alice", "views": 30 },
+        { "id": 4, "title": "rust article", "author": "carol", "views": 40 },
+        { "id": 5, "title": "rust article", "author": "alice", "views": 20 },
+        { "id": 6, "title": "rust article", "author": "alice", "views": 5 },
+        { "id": 7, "title": "rust article", "author": "bob", "views": 1 },
+        { "id": 8, "title": "rust article", "views": 100 },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // the groups are sorted by their best hit and contain their best hits
+    let query = json!({ "q": "rust", "groupBy": "author", "hitsPerGroup": 3, "attributesToRetrieve": ["id"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbGroups"], 4);
+        assert!(response.get("hits").is_none());
+        let expected = json!([
+            { "key": null, "nbHits": 1, "hits": [{ "id": 8 }] },
+            { "key": "bob", "nbHits": 2, "hits": [{ "id": 2 }, { "id": 7 }] },
+            { "key": "carol", "nbHits": 1, "hits": [{ "id": 4 }] },
+            { "key": "alice", "nbHits": 4, "hits": [{ "id": 3 }, { "id": 5 }, { "id": 1 }] },
+        ]);
+        assert_json_eq!(expected, response["groups"].clone(), ordered: true);
+    });
+
+    // the offset and the limit apply to the groups
+    let query = json!({ "groupBy": "author", "offset": 1, "limit": 2, "filters": "views > 1", "attributesToRetrieve": ["id"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let expected = json!([
+            { "key": "bob", "nbHits": 1, "hits": [{ "id": 2 }] },
+            { "key": "carol", "nbHits": 1, "hits": [{ "id": 4 }] },
+        ]);
+        assert_json_eq!(expected, response["groups"].clone(), ordered: true);
+    });
+
+    let query = json!({ "q": "rust", "groupBy": "author", "hitsPerGroup": 2, "offset": 1, "limit": 2, "attributesToRetrieve": ["id"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbGroups"], 4);
+        let expected = json!([
+            { "key": "bob", "nbHits": 2, "hits": [{ "id": 2 }, { "id": 7 }] },
+            { "key": "carol", "nbHits": 1, "hits": [{ "id": 4 }] },
+        ]);
+        assert_json_eq!(expected, response["groups"].clone(), ordered: true);
+    });
+
+    // a huge limit does not allocate the groups or the hits ahead
+    let query = json!({ "q": "rust", "groupBy": "author", "limit": usize::MAX, "attributesToRetrieve": ["id"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["groups"].as_array().unwrap().len(), 4);
+    });
+
+    let query = json!({ "q": "rust", "groupBy": "unknown" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "q": "rust", "groupBy": "author", "distinct": "author" });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+
+    let query = json!({ "q": "rust", "hitsPerGroup": 2 });
+    test_post_get_search!(server, query, |_response, status_code| {
+        assert_eq!(status_code, 400);
+    });
+}
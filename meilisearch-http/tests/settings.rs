@@ -48,6 +48,8 @@ async fn write_all_and_delete() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     server.update_all_settings(body.clone()).await;
@@ -81,6 +83,8 @@ async fn write_all_and_delete() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     assert_json_eq!(expect, response, ordered: false);
@@ -132,6 +136,8 @@ async fn write_all_and_update() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     server.update_all_settings(body.clone()).await;
@@ -210,6 +216,8 @@ async fn write_all_and_update() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["title"],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     assert_json_eq!(expected, response, ordered: false);
@@ -240,6 +248,8 @@ async fn test_default_settings() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -273,6 +283,8 @@ async fn test_default_settings_2() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -381,6 +393,8 @@ async fn write_setting_and_update_partial() {
             "street": ["avenue"],
         },
         "attributesForFaceting": [],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -513,6 +527,8 @@ async fn test_displayed_attributes_field() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "filterableAttributes": [],
+        "fieldTypes": {},
     });
 
     server.update_all_settings(body.clone()).await;
@@ -520,4 +536,98 @@ async fn test_displayed_attributes_field() {
     let (response, _status_code) = server.get_all_settings().await;
 
     assert_json_eq!(body, response, ordered: true);
-}
\ No newline at end of file
+}
+#[actix_rt::test]
+async fn settings_dry_run_reports_impact() {
+    let mut server = common::Server::test_server().await;
+    let (settings_before, _status_code) = server.get_all_settings().await;
+    let (updates_before, _status_code) = server.get_all_updates_status().await;
+
+    let body = json!({
+        "searchableAttributes": ["name", "about"],
+        "stopWords": ["the"],
+    });
+    let (response, status_code) = server.post_request("/indexes/test/settings?dryRun=true", body).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["mustReindex"], true);
+    assert_eq!(response["reindexedDocuments"], 77);
+    assert!(response["rebuiltStores"].as_array().unwrap().contains(&json!("postingsLists")));
+    assert!(response["estimatedSizeChange"].as_i64().unwrap() < 0);
+
+    // nothing has been enqueued nor applied
+    let (settings_after, _status_code) = server.get_all_settings().await;
+    assert_json_eq!(settings_before, settings_after, ordered: true);
+    let (updates_after, _status_code) = server.get_all_updates_status().await;
+    assert_json_eq!(updates_before, updates_after, ordered: true);
+
+    // the settings are validated
+    let body = json!({ "rankingRules": ["unknown(name)"] });
+    let (_response, status_code) = server.post_request("/indexes/test/settings?dryRun=true", body).await;
+    assert_eq!(status_code, 400);
+
+    // the stored documents are checked against the declared field types
+    let body = json!({ "fieldTypes": { "age": "string" } });
+    let (response, status_code) = server.post_request("/indexes/test/settings?dryRun=true", body).await;
+    assert_eq!(status_code, 400);
+    assert_eq!(response["errorCode"], "invalid_document_field_type");
+
+    // only the first mismatching fields are reported
+    let body = json!({ "fieldTypes": { "age": "string", "name": "number" } });
+    let (response, status_code) = server.post_request("/indexes/test/settings?dryRun=true", body).await;
+    assert_eq!(status_code, 400);
+    let message = response["message"].as_str().unwrap();
+    assert_eq!(message.matches("must be of type").count(), 100);
+    assert!(message.ends_with("only the first 100 mismatching fields are reported"));
+}
+
+#[actix_rt::test]
+async fn field_types_are_enforced() {
+    let mut server = common::Server::with_uid("test");
+    server.create_index(json!({ "uid": "test", "primaryKey": "id" })).await;
+
+    let body = json!({ "age": "number", "tags": "array<string>", "birth": "date" });
+    let (response, status_code) = server.post_request("/indexes/test/settings/field-types", body.clone()).await;
+    assert_eq!(status_code, 202);
+    server.wait_update_id(response["updateId"].as_u64().unwrap()).await;
+
+    let (response, status_code) = server.get_request("/indexes/test/settings/field-types").await;
+    assert_eq!(status_code, 200);
+    assert_json_eq!(body, response, ordered: false);
+
+    // a document that does not match the declared types is rejected
+    let documents = json!([{ "id": 1, "age": "twelve", "tags": ["a"], "birth": "2000-01-01T00:00:00Z" }]);
+    let (response, _status_code) = server.post_request("/indexes/test/documents", documents).await;
+    let update_id = response["updateId"].as_u64().unwrap();
+    server.wait_update_id(update_id).await;
+    let (response, _status_code) = server.get_update_status(update_id).await;
+    assert_eq!(response["status"], "failed");
+    assert_eq!(response["errorCode"], "invalid_document_field_type");
+
+    let documents = json!([
+        { "id": 1, "age": 12, "tags": ["a"], "birth": "2000-01-01T00:00:00Z" },
+        { "id": 2, "age": 30, "tags": ["b"], "birth": "1990-06-01T00:00:00Z" },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    // filters on dates compare the timestamps
+    let (response, status_code) = server.search_post(json!({ "filters": "birth < \"1995-01-01T00:00:00Z\"" })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+    assert_eq!(response["hits"][0]["id"], 2);
+
+    // filter values must match the declared type
+    let (_response, status_code) = server.search_post(json!({ "filters": "age = twelve" })).await;
+    assert_eq!(status_code, 400);
+
+    // types that do not match the indexed documents are refused
+    let (response, _status_code) = server.post_request("/indexes/test/settings/field-types", json!({ "age": "boolean" })).await;
+    let update_id = response["updateId"].as_u64().unwrap();
+    server.wait_update_id(update_id).await;
+    let (response, _status_code) = server.get_update_status(update_id).await;
+    assert_eq!(response["status"], "failed");
+    assert_eq!(response["errorCode"], "invalid_document_field_type");
+
+    // unknown types are a bad request
+    let (_response, status_code) = server.post_request("/indexes/test/settings/field-types", json!({ "age": "integer" })).await;
+    assert_eq!(status_code, 400);
+}
@@ -59,3 +59,22 @@ async fn add_documents_and_stop_words() {
 
     // assert!(!response["hits"].as_array().unwrap().is_empty());
 }
+
+#[actix_rt::test]
+async fn update_stop_words_with_priority() {
+    let mut server = common::Server::test_server().await;
+
+    let body = json!(["ut", "ea"]);
+    let (response, status_code) = server
+        .post_request_async("/indexes/test/settings/stop-words?priority=high", body.clone())
+        .await;
+    assert_eq!(status_code, 202, "{}", response);
+
+    let (response, _status_code) = server.get_stop_words().await;
+    assert_json_eq!(body, response, ordered: false);
+
+    let (response, status_code) = server
+        .post_request("/indexes/test/settings/stop-words?priority=urgent", body)
+        .await;
+    assert_eq!(status_code, 400, "{}", response);
+}
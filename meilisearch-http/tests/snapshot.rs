@@ -0,0 +1,36 @@
+use std::thread;
+use std::time::Duration;
+
+use meilisearch_http::snapshot::{db_name, snapshot_file};
+
+mod common;
+
+#[actix_rt::test]
+async fn trigger_snapshot_and_get_status() {
+    let mut server = common::Server::test_server().await;
+
+    let (value, status_code) = server.trigger_snapshot().await;
+    assert_eq!(status_code, 202);
+    let snapshot_uid = value["uid"].as_str().unwrap().to_string();
+
+    let mut done = false;
+    for _ in 0..50u8 {
+        let (value, status_code) = server.get_snapshot_status(&snapshot_uid).await;
+        assert_eq!(status_code, 200);
+        assert_ne!(value["status"].as_str(), Some("snapshot_process_failed"));
+
+        if value["status"].as_str() == Some("done") {
+            done = true;
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(done, "snapshot creation ran out of time");
+
+    let db_name = db_name(&server.data).unwrap();
+    assert!(snapshot_file(&server.data.snapshot_dir, &db_name, &snapshot_uid).exists());
+
+    let (value, status_code) = server.get_snapshot_status("unknown").await;
+    assert_eq!(status_code, 404);
+    assert_eq!(value["errorCode"], "not_found");
+}
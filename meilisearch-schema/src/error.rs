@@ -9,6 +9,7 @@ pub enum Error {
     FieldNameNotFound(String),
     PrimaryKeyAlreadyPresent,
     MaxFieldsLimitExceeded,
+    InvalidFieldType(String),
 }
 
 impl fmt::Display for Error {
@@ -18,6 +19,7 @@ impl fmt::Display for Error {
             FieldNameNotFound(field) => write!(f, "The field {:?} doesn't exist", field),
             PrimaryKeyAlreadyPresent => write!(f, "A primary key is already present. It's impossible to update it"),
             MaxFieldsLimitExceeded => write!(f, "The maximum of possible reattributed field id has been reached"),
+            InvalidFieldType(field_type) => write!(f, "Unknown field type {:?}, expected string, number, boolean, date or array<type>", field_type),
         }
     }
 }
@@ -32,6 +34,7 @@ impl ErrorCode for Error {
             FieldNameNotFound(_) => Code::Internal,
             MaxFieldsLimitExceeded => Code::MaxFieldsLimitExceeded,
             PrimaryKeyAlreadyPresent => Code::PrimaryKeyAlreadyPresent,
+            InvalidFieldType(_) => Code::InvalidFieldType,
         }
     }
 }
@@ -0,0 +1,75 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// The type declared for a field of a typed schema.
+///
+/// Types are written as `string`, `number`, `boolean` and `date`,
+/// arrays are written with the type of their elements: `array<string>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum FieldType {
+    String,
+    Number,
+    Boolean,
+    Date,
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    /// The type of the elements of an array, the type itself otherwise.
+    pub fn element_type(&self) -> &FieldType {
+        match self {
+            FieldType::Array(inner) => inner.element_type(),
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldType::String => f.write_str("string"),
+            FieldType::Number => f.write_str("number"),
+            FieldType::Boolean => f.write_str("boolean"),
+            FieldType::Date => f.write_str("date"),
+            FieldType::Array(inner) => write!(f, "array<{}>", inner),
+        }
+    }
+}
+
+impl FromStr for FieldType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(FieldType::String),
+            "number" => Ok(FieldType::Number),
+            "boolean" => Ok(FieldType::Boolean),
+            "date" => Ok(FieldType::Date),
+            _ => match s.strip_prefix("array<").and_then(|s| s.strip_suffix('>')) {
+                Some(inner) => Ok(FieldType::Array(Box::new(inner.parse()?))),
+                None => Err(Error::InvalidFieldType(s.to_string())),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for FieldType {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<FieldType> for String {
+    fn from(field_type: FieldType) -> String {
+        field_type.to_string()
+    }
+}
+
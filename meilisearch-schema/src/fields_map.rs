@@ -3,7 +3,7 @@ use std::collections::hash_map::Iter;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{SResult, FieldId};
+use crate::{SResult, FieldId, LegacyFieldsMap};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FieldsMap {
@@ -12,6 +12,16 @@ pub struct FieldsMap {
     next_id: FieldId
 }
 
+impl From<LegacyFieldsMap> for FieldsMap {
+    fn from(legacy: LegacyFieldsMap) -> FieldsMap {
+        FieldsMap {
+            name_map: legacy.name_map.into_iter().map(|(name, id)| (name, FieldId::from(u32::from(id)))).collect(),
+            id_map: legacy.id_map.into_iter().map(|(id, name)| (FieldId::from(u32::from(id)), name)).collect(),
+            next_id: FieldId::from(u32::from(legacy.next_id)),
+        }
+    }
+}
+
 impl FieldsMap {
     pub fn len(&self) -> usize {
         self.name_map.len()
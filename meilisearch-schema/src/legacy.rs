@@ -0,0 +1,49 @@
+//! The schema as written by the previous storage formats of the database. The first
+//! format stored the field ids and the indexed positions on 16 bits, the second one
+//! did not record the fields produced by flattening the nested objects. They are
+//! only read to migrate the databases written with these formats.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+use crate::schema::OptionAll;
+use crate::{FieldId, FieldsMap, IndexedPos};
+
+#[derive(Debug, Deserialize)]
+pub struct LegacyFieldsMap {
+    pub(crate) name_map: HashMap<String, u16>,
+    pub(crate) id_map: HashMap<u16, String>,
+    pub(crate) next_id: u16,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) enum LegacyOptionAll<T> {
+    All,
+    Some(T),
+    None,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LegacySchema {
+    pub(crate) fields_map: LegacyFieldsMap,
+
+    pub(crate) primary_key: Option<u16>,
+    pub(crate) ranked: HashSet<u16>,
+    pub(crate) displayed: LegacyOptionAll<HashSet<u16>>,
+
+    pub(crate) indexed: LegacyOptionAll<Vec<u16>>,
+    pub(crate) indexed_map: HashMap<u16, u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaV2 {
+    pub(crate) fields_map: FieldsMap,
+
+    pub(crate) primary_key: Option<FieldId>,
+    pub(crate) ranked: HashSet<FieldId>,
+    pub(crate) displayed: OptionAll<HashSet<FieldId>>,
+
+    pub(crate) indexed: OptionAll<Vec<FieldId>>,
+    pub(crate) indexed_map: HashMap<FieldId, IndexedPos>,
+}
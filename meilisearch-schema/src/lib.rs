@@ -1,38 +1,51 @@
 mod error;
+mod field_type;
 mod fields_map;
+mod legacy;
 mod schema;
 
 pub use error::{Error, SResult};
+pub use field_type::FieldType;
 pub use fields_map::FieldsMap;
+pub use legacy::{LegacyFieldsMap, LegacySchema, SchemaV2};
 pub use schema::Schema;
 use serde::{Deserialize, Serialize};
 use zerocopy::{AsBytes, FromBytes};
 
+/// Whether the field `name` is the field `parent` or is nested under it,
+/// `address.city` is nested in `address`.
+pub fn is_nested_in(name: &str, parent: &str) -> bool {
+    match name.strip_prefix(parent) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.'),
+        None => false,
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
-pub struct IndexedPos(pub u16);
+pub struct IndexedPos(pub u32);
 
 impl IndexedPos {
-    pub const fn new(value: u16) -> IndexedPos {
+    pub const fn new(value: u32) -> IndexedPos {
         IndexedPos(value)
     }
 
     pub const fn min() -> IndexedPos {
-        IndexedPos(u16::min_value())
+        IndexedPos(u32::min_value())
     }
 
     pub const fn max() -> IndexedPos {
-        IndexedPos(u16::max_value())
+        IndexedPos(u32::max_value())
     }
 }
 
-impl From<u16> for IndexedPos {
-    fn from(value: u16) -> IndexedPos {
+impl From<u32> for IndexedPos {
+    fn from(value: u32) -> IndexedPos {
         IndexedPos(value)
     }
 }
 
-impl Into<u16> for IndexedPos {
-    fn into(self) -> u16 {
+impl Into<u32> for IndexedPos {
+    fn into(self) -> u32 {
         self.0
     }
 }
@@ -41,19 +54,19 @@ impl Into<u16> for IndexedPos {
 #[derive(Serialize, Deserialize)]
 #[derive(AsBytes, FromBytes)]
 #[repr(C)]
-pub struct FieldId(pub u16);
+pub struct FieldId(pub u32);
 
 impl FieldId {
-    pub const fn new(value: u16) -> FieldId {
+    pub const fn new(value: u32) -> FieldId {
         FieldId(value)
     }
 
     pub const fn min() -> FieldId {
-        FieldId(u16::min_value())
+        FieldId(u32::min_value())
     }
 
     pub const fn max() -> FieldId {
-        FieldId(u16::max_value())
+        FieldId(u32::max_value())
     }
 
     pub fn next(self) -> SResult<FieldId> {
@@ -61,14 +74,14 @@ impl FieldId {
     }
 }
 
-impl From<u16> for FieldId {
-    fn from(value: u16) -> FieldId {
+impl From<u32> for FieldId {
+    fn from(value: u32) -> FieldId {
         FieldId(value)
     }
 }
 
-impl From<FieldId> for u16 {
-    fn from(other: FieldId) -> u16 {
+impl From<FieldId> for u32 {
+    fn from(other: FieldId) -> u32 {
         other.0
     }
 }
@@ -1,10 +1,11 @@
-use crate::{FieldsMap, FieldId, SResult, Error, IndexedPos};
+use crate::{FieldsMap, FieldId, FieldType, SResult, Error, IndexedPos, is_nested_in};
+use crate::legacy::{LegacyOptionAll, LegacySchema, SchemaV2};
 use serde::{Serialize, Deserialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::borrow::Cow;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-enum OptionAll<T> {
+pub(crate) enum OptionAll<T> {
     All,
     Some(T),
     None,
@@ -35,6 +36,16 @@ impl<T> Default for OptionAll<T> {
     }
 }
 
+impl<T> LegacyOptionAll<T> {
+    fn into_option_all<U, F: FnOnce(T) -> U>(self, f: F) -> OptionAll<U> {
+        match self {
+            LegacyOptionAll::Some(x) => OptionAll::Some(f(x)),
+            LegacyOptionAll::All => OptionAll::All,
+            LegacyOptionAll::None => OptionAll::None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Schema {
     fields_map: FieldsMap,
@@ -45,6 +56,48 @@ pub struct Schema {
 
     indexed: OptionAll<Vec<FieldId>>,
     indexed_map: HashMap<FieldId, IndexedPos>,
+
+    // the fields produced by flattening the nested objects of the documents
+    nested: HashSet<FieldId>,
+
+    // the declared types are stored apart from the schema and set when it is loaded
+    #[serde(skip)]
+    field_types: BTreeMap<String, FieldType>,
+}
+
+impl From<LegacySchema> for Schema {
+    fn from(legacy: LegacySchema) -> Schema {
+        let field_id = |id: u16| FieldId::from(u32::from(id));
+
+        Schema {
+            fields_map: legacy.fields_map.into(),
+            primary_key: legacy.primary_key.map(field_id),
+            ranked: legacy.ranked.into_iter().map(field_id).collect(),
+            displayed: legacy.displayed.into_option_all(|ids| ids.into_iter().map(field_id).collect()),
+            indexed: legacy.indexed.into_option_all(|ids| ids.into_iter().map(field_id).collect()),
+            indexed_map: legacy.indexed_map
+                .into_iter()
+                .map(|(id, pos)| (field_id(id), IndexedPos::from(u32::from(pos))))
+                .collect(),
+            nested: HashSet::new(),
+            field_types: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<SchemaV2> for Schema {
+    fn from(schema: SchemaV2) -> Schema {
+        Schema {
+            fields_map: schema.fields_map,
+            primary_key: schema.primary_key,
+            ranked: schema.ranked,
+            displayed: schema.displayed,
+            indexed: schema.indexed,
+            indexed_map: schema.indexed_map,
+            nested: HashSet::new(),
+            field_types: BTreeMap::new(),
+        }
+    }
 }
 
 impl Schema {
@@ -69,6 +122,8 @@ impl Schema {
             displayed: OptionAll::All,
             indexed: OptionAll::All,
             indexed_map,
+            nested: HashSet::new(),
+            field_types: BTreeMap::new(),
         }
     }
 
@@ -101,6 +156,28 @@ impl Schema {
         self.fields_map.iter().map(|(k, _)| k.as_ref())
     }
 
+    /// The ids of the field `name` and of the fields nested under it,
+    /// `address` returns the ids of `address.city` and `address.street`.
+    pub fn nested_ids<'a>(&'a self, name: &'a str) -> impl Iterator<Item = FieldId> + 'a {
+        self.fields_map
+            .iter()
+            .filter(move |(k, _)| is_nested_in(k, name))
+            .map(|(_, id)| *id)
+    }
+
+    /// Marks the field `name` as produced by flattening a nested object of a document.
+    pub fn set_nested(&mut self, name: &str) -> SResult<FieldId> {
+        let id = self.fields_map.insert(name)?;
+        self.nested.insert(id);
+        Ok(id)
+    }
+
+    /// Whether the field `name` was produced by flattening a nested object,
+    /// a dotted name sent as is by the user is not.
+    pub fn is_nested(&self, name: &str) -> bool {
+        self.fields_map.id(name).map_or(false, |id| self.nested.contains(&id))
+    }
+
     pub fn contains(&self, name: &str) -> bool {
         self.fields_map.id(name).is_some()
     }
@@ -204,7 +281,7 @@ impl Schema {
         if let Some(indexed_pos) = self.indexed_map.get(&id) {
             return Ok((id, *indexed_pos))
         };
-        let pos = self.indexed_map.len() as u16;
+        let pos = self.indexed_map.len() as u32;
         self.indexed_map.insert(id, pos.into());
         self.indexed = self.indexed.take().map(|mut v| {
             v.push(id);
@@ -338,7 +415,7 @@ impl Schema {
         self.indexed_map.clear();
 
         for (_name, id) in self.fields_map.iter() {
-            let pos = self.indexed_map.len() as u16;
+            let pos = self.indexed_map.len() as u32;
             self.indexed_map.insert(*id, pos.into());
         }
     }
@@ -346,4 +423,17 @@ impl Schema {
     pub fn set_all_fields_as_displayed(&mut self) {
         self.displayed = OptionAll::All
     }
+
+    /// The declared type of a field, `None` when the field is not typed.
+    pub fn field_type(&self, id: FieldId) -> Option<&FieldType> {
+        self.name(id).and_then(|name| self.field_types.get(name))
+    }
+
+    pub fn field_types(&self) -> &BTreeMap<String, FieldType> {
+        &self.field_types
+    }
+
+    pub fn set_field_types(&mut self, field_types: BTreeMap<String, FieldType>) {
+        self.field_types = field_types;
+    }
 }
@@ -18,10 +18,11 @@ pub struct DocumentId(pub u32);
 /// in a document and its attributes.
 ///
 /// This is stored in the map, generated at index time,
-/// extracted and interpreted at search time.
+/// extracted and interpreted at search time. It is packed
+/// to not store the padding after the length of the word.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "zerocopy", derive(AsBytes, FromBytes))]
-#[repr(C)]
+#[repr(C, packed)]
 pub struct DocIndex {
     /// The document identifier where the word was found.
     pub document_id: DocumentId,
@@ -29,15 +30,15 @@ pub struct DocIndex {
     /// The attribute in the document where the word was found
     /// along with the index in it.
     /// This is an IndexedPos and not a FieldId. Must be converted each time.
-    pub attribute: u16,
-    pub word_index: u16,
+    pub attribute: u32,
+    pub word_index: u32,
 
     /// The position in bytes where the word was found
     /// along with the length of it.
     ///
     /// It informs on the original word area in the text indexed
     /// without needing to run the tokenizer again.
-    pub char_index: u16,
+    pub char_index: u32,
     pub char_length: u16,
 }
 
@@ -52,17 +53,17 @@ pub struct DocIndex {
 pub struct Highlight {
     /// The attribute in the document where the word was found
     /// along with the index in it.
-    pub attribute: u16,
+    pub attribute: u32,
 
     /// The position in bytes where the word was found.
     ///
     /// It informs on the original word area in the text indexed
     /// without needing to run the tokenizer again.
-    pub char_index: u16,
+    pub char_index: u32,
 
     /// The length in bytes of the found word.
     ///
     /// It informs on the original word area in the text indexed
     /// without needing to run the tokenizer again.
-    pub char_length: u16,
+    pub char_length: u32,
 }